@@ -3,15 +3,19 @@ use quote::quote;
 use syn::{punctuated::Punctuated, DeriveInput, Token};
 
 use crate::code::Code;
+use crate::comparison::Comparison;
 use crate::diagnostic_arg::DiagnosticArg;
 use crate::diagnostic_source::DiagnosticSource;
+use crate::display_attr::{self, DisplayAttr};
 use crate::forward::{Forward, WhichFn};
-use crate::help::Help;
 use crate::label::Labels;
+use crate::metadata::Metadata;
 use crate::related::Related;
 use crate::severity::Severity;
 use crate::source_code::SourceCode;
+use crate::tags::Tags;
 use crate::url::Url;
+use crate::utils::gen_all_variants_with;
 
 pub enum Diagnostic {
     Struct {
@@ -61,8 +65,15 @@ impl DiagnosticDefArgs {
 pub struct DiagnosticConcreteArgs {
     pub code: Option<Code>,
     pub severity: Option<Severity>,
-    pub help: Option<Help>,
+    pub help: Option<DisplayAttr>,
+    pub note: Option<DisplayAttr>,
+    pub expected: Option<DisplayAttr>,
+    pub found: Option<DisplayAttr>,
+    pub suggestion: Option<DisplayAttr>,
+    pub tags: Option<Tags>,
     pub labels: Option<Labels>,
+    pub metadata: Option<Metadata>,
+    pub comparison: Option<Comparison>,
     pub source_code: Option<SourceCode>,
     pub url: Option<Url>,
     pub forward: Option<Forward>,
@@ -75,14 +86,27 @@ impl DiagnosticConcreteArgs {
         let labels = Labels::from_fields(fields)?;
         let source_code = SourceCode::from_fields(fields)?;
         let related = Related::from_fields(fields)?;
-        let help = Help::from_fields(fields)?;
+        let help = DisplayAttr::from_fields(fields, WhichFn::Help)?;
+        let note = DisplayAttr::from_fields(fields, WhichFn::Note)?;
+        let expected = DisplayAttr::from_fields(fields, WhichFn::Expected)?;
+        let found = DisplayAttr::from_fields(fields, WhichFn::Found)?;
+        let suggestion = DisplayAttr::from_fields(fields, WhichFn::Suggestion)?;
         let diagnostic_source = DiagnosticSource::from_fields(fields)?;
+        let metadata = Metadata::from_fields(fields)?;
+        let comparison = Comparison::from_fields(fields)?;
         Ok(DiagnosticConcreteArgs {
             code: None,
             help,
+            note,
+            expected,
+            found,
+            suggestion,
+            tags: None,
             related,
             severity: None,
             labels,
+            metadata,
+            comparison,
             url: None,
             forward: None,
             source_code,
@@ -90,6 +114,30 @@ impl DiagnosticConcreteArgs {
         })
     }
 
+    /// Returns where the method `which`, one that returns an optional
+    /// `Display`, is derived from, if it's been specified.
+    pub(crate) fn display_attr(&self, which: WhichFn) -> Option<&DisplayAttr> {
+        match which {
+            WhichFn::Help => self.help.as_ref(),
+            WhichFn::Note => self.note.as_ref(),
+            WhichFn::Expected => self.expected.as_ref(),
+            WhichFn::Found => self.found.as_ref(),
+            WhichFn::Suggestion => self.suggestion.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn display_attr_mut(&mut self, which: WhichFn) -> &mut Option<DisplayAttr> {
+        match which {
+            WhichFn::Help => &mut self.help,
+            WhichFn::Note => &mut self.note,
+            WhichFn::Expected => &mut self.expected,
+            WhichFn::Found => &mut self.found,
+            WhichFn::Suggestion => &mut self.suggestion,
+            _ => unreachable!("MIETTE BUG: not a Display method"),
+        }
+    }
+
     fn add_args(
         &mut self,
         attr: &syn::Attribute,
@@ -128,14 +176,24 @@ impl DiagnosticConcreteArgs {
                     }
                     self.severity = Some(sev);
                 }
-                DiagnosticArg::Help(hl) => {
-                    if self.help.is_some() {
+                DiagnosticArg::Display(which, display) => {
+                    let slot = self.display_attr_mut(which);
+                    if slot.is_some() {
+                        errors.push(syn::Error::new_spanned(
+                            attr,
+                            format!("{} has already been specified", display_attr::name(which)),
+                        ));
+                    }
+                    *slot = Some(display);
+                }
+                DiagnosticArg::Tags(tg) => {
+                    if self.tags.is_some() {
                         errors.push(syn::Error::new_spanned(
                             attr,
-                            "help has already been specified",
+                            "tags has already been specified",
                         ));
                     }
-                    self.help = Some(hl);
+                    self.tags = Some(tg);
                 }
                 DiagnosticArg::Url(u) => {
                     if self.url.is_some() {
@@ -282,24 +340,42 @@ impl Diagnostic {
                     DiagnosticDefArgs::Transparent(forward) => {
                         let code_method = forward.gen_struct_method(WhichFn::Code);
                         let help_method = forward.gen_struct_method(WhichFn::Help);
+                        let note_method = forward.gen_struct_method(WhichFn::Note);
+                        let expected_method = forward.gen_struct_method(WhichFn::Expected);
+                        let found_method = forward.gen_struct_method(WhichFn::Found);
+                        let suggestion_method = forward.gen_struct_method(WhichFn::Suggestion);
                         let url_method = forward.gen_struct_method(WhichFn::Url);
                         let labels_method = forward.gen_struct_method(WhichFn::Labels);
+                        let fixes_method = forward.gen_struct_method(WhichFn::Fixes);
+                        let tags_method = forward.gen_struct_method(WhichFn::Tags);
+                        let metadata_method = forward.gen_struct_method(WhichFn::Metadata);
+                        let comparison_method = forward.gen_struct_method(WhichFn::Comparison);
                         let source_code_method = forward.gen_struct_method(WhichFn::SourceCode);
                         let severity_method = forward.gen_struct_method(WhichFn::Severity);
                         let related_method = forward.gen_struct_method(WhichFn::Related);
                         let diagnostic_source_method =
                             forward.gen_struct_method(WhichFn::DiagnosticSource);
+                        let backtrace_method = forward.gen_struct_method(WhichFn::Backtrace);
 
                         quote! {
                             impl #impl_generics miette::Diagnostic for #ident #ty_generics #where_clause {
                                 #code_method
                                 #help_method
+                                #note_method
+                                #expected_method
+                                #found_method
+                                #suggestion_method
                                 #url_method
                                 #labels_method
+                                #fixes_method
+                                #tags_method
+                                #metadata_method
+                                #comparison_method
                                 #severity_method
                                 #source_code_method
                                 #related_method
                                 #diagnostic_source_method
+                                #backtrace_method
                             }
                         }
                     }
@@ -318,8 +394,28 @@ impl Diagnostic {
                         let help_body = concrete
                             .help
                             .as_ref()
-                            .and_then(|x| x.gen_struct(fields))
+                            .and_then(|x| x.gen_struct(fields, WhichFn::Help))
                             .or_else(|| forward(WhichFn::Help));
+                        let note_body = concrete
+                            .note
+                            .as_ref()
+                            .and_then(|x| x.gen_struct(fields, WhichFn::Note))
+                            .or_else(|| forward(WhichFn::Note));
+                        let expected_body = concrete
+                            .expected
+                            .as_ref()
+                            .and_then(|x| x.gen_struct(fields, WhichFn::Expected))
+                            .or_else(|| forward(WhichFn::Expected));
+                        let found_body = concrete
+                            .found
+                            .as_ref()
+                            .and_then(|x| x.gen_struct(fields, WhichFn::Found))
+                            .or_else(|| forward(WhichFn::Found));
+                        let suggestion_body = concrete
+                            .suggestion
+                            .as_ref()
+                            .and_then(|x| x.gen_struct(fields, WhichFn::Suggestion))
+                            .or_else(|| forward(WhichFn::Suggestion));
                         let sev_body = concrete
                             .severity
                             .as_ref()
@@ -340,6 +436,22 @@ impl Diagnostic {
                             .as_ref()
                             .and_then(|x| x.gen_struct(fields))
                             .or_else(|| forward(WhichFn::Labels));
+                        let fixes_body = forward(WhichFn::Fixes);
+                        let tags_body = concrete
+                            .tags
+                            .as_ref()
+                            .and_then(|x| x.gen_struct())
+                            .or_else(|| forward(WhichFn::Tags));
+                        let metadata_body = concrete
+                            .metadata
+                            .as_ref()
+                            .and_then(|x| x.gen_struct(fields))
+                            .or_else(|| forward(WhichFn::Metadata));
+                        let comparison_body = concrete
+                            .comparison
+                            .as_ref()
+                            .and_then(|x| x.gen_struct(fields))
+                            .or_else(|| forward(WhichFn::Comparison));
                         let src_body = concrete
                             .source_code
                             .as_ref()
@@ -350,16 +462,26 @@ impl Diagnostic {
                             .as_ref()
                             .and_then(|x| x.gen_struct())
                             .or_else(|| forward(WhichFn::DiagnosticSource));
+                        let backtrace_body = forward(WhichFn::Backtrace);
                         quote! {
                             impl #impl_generics miette::Diagnostic for #ident #ty_generics #where_clause {
                                 #code_body
                                 #help_body
+                                #note_body
+                                #expected_body
+                                #found_body
+                                #suggestion_body
                                 #sev_body
                                 #rel_body
                                 #url_body
                                 #labels_body
+                                #fixes_body
+                                #tags_body
+                                #metadata_body
+                                #comparison_body
                                 #src_body
                                 #diagnostic_source
+                                #backtrace_body
                             }
                         }
                     }
@@ -372,23 +494,42 @@ impl Diagnostic {
             } => {
                 let (impl_generics, ty_generics, where_clause) = &generics.split_for_impl();
                 let code_body = Code::gen_enum(variants);
-                let help_body = Help::gen_enum(variants);
+                let help_body = DisplayAttr::gen_enum(variants, WhichFn::Help);
+                let note_body = DisplayAttr::gen_enum(variants, WhichFn::Note);
+                let expected_body = DisplayAttr::gen_enum(variants, WhichFn::Expected);
+                let found_body = DisplayAttr::gen_enum(variants, WhichFn::Found);
+                let suggestion_body = DisplayAttr::gen_enum(variants, WhichFn::Suggestion);
                 let sev_body = Severity::gen_enum(variants);
                 let labels_body = Labels::gen_enum(variants);
+                let fixes_body = gen_all_variants_with(variants, WhichFn::Fixes, |_, _, _| None);
+                let tags_body = Tags::gen_enum(variants);
+                let metadata_body = Metadata::gen_enum(variants);
+                let comparison_body = Comparison::gen_enum(variants);
                 let src_body = SourceCode::gen_enum(variants);
                 let rel_body = Related::gen_enum(variants);
                 let url_body = Url::gen_enum(ident, variants);
                 let diagnostic_source_body = DiagnosticSource::gen_enum(variants);
+                let backtrace_body =
+                    gen_all_variants_with(variants, WhichFn::Backtrace, |_, _, _| None);
                 quote! {
                     impl #impl_generics miette::Diagnostic for #ident #ty_generics #where_clause {
                         #code_body
                         #help_body
+                        #note_body
+                        #expected_body
+                        #found_body
+                        #suggestion_body
                         #sev_body
                         #labels_body
+                        #fixes_body
+                        #tags_body
+                        #metadata_body
+                        #comparison_body
                         #src_body
                         #rel_body
                         #url_body
                         #diagnostic_source_body
+                        #backtrace_body
                     }
                 }
             }
@@ -1,16 +1,18 @@
 use syn::parse::{Parse, ParseStream};
 
 use crate::code::Code;
-use crate::forward::Forward;
-use crate::help::Help;
+use crate::display_attr::{display_fn, DisplayAttr};
+use crate::forward::{Forward, WhichFn};
 use crate::severity::Severity;
+use crate::tags::Tags;
 use crate::url::Url;
 
 pub enum DiagnosticArg {
     Transparent,
     Code(Code),
     Severity(Severity),
-    Help(Help),
+    Display(WhichFn, DisplayAttr),
+    Tags(Tags),
     Url(Url),
     Forward(Forward),
 }
@@ -28,8 +30,13 @@ impl Parse for DiagnosticArg {
             Ok(DiagnosticArg::Code(input.parse()?))
         } else if ident == "severity" {
             Ok(DiagnosticArg::Severity(input.parse()?))
-        } else if ident == "help" {
-            Ok(DiagnosticArg::Help(input.parse()?))
+        } else if let Some(which) = display_fn(&ident) {
+            Ok(DiagnosticArg::Display(
+                which,
+                DisplayAttr::parse(input, which)?,
+            ))
+        } else if ident == "tags" {
+            Ok(DiagnosticArg::Tags(input.parse()?))
         } else if ident == "url" {
             Ok(DiagnosticArg::Url(input.parse()?))
         } else {
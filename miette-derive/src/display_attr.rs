@@ -0,0 +1,178 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parenthesized, parse::ParseStream, spanned::Spanned, Fields, Token};
+
+use crate::{
+    diagnostic::DiagnosticDef,
+    utils::{display_pat_members, gen_all_variants_with},
+};
+use crate::{
+    fmt::{self, Display},
+    forward::WhichFn,
+};
+
+/// Where the text returned by one of the [`Diagnostic`] methods that return
+/// an optional `Display`, like `help` or `note`, comes from: a format string
+/// given to `#[diagnostic(...)]`, or a field marked with an attribute named
+/// after the method.
+///
+/// [`Diagnostic`]: https://docs.rs/miette/latest/miette/trait.Diagnostic.html
+pub enum DisplayAttr {
+    Display(Display),
+    Field(syn::Member, Box<syn::Type>),
+}
+
+/// The methods that return an optional `Display`, and are derived from a
+/// [`DisplayAttr`].
+const DISPLAY_FNS: [WhichFn; 5] = [
+    WhichFn::Help,
+    WhichFn::Note,
+    WhichFn::Expected,
+    WhichFn::Found,
+    WhichFn::Suggestion,
+];
+
+/// Returns the method derived from the attribute named `ident`, if it's one
+/// that a [`DisplayAttr`] is parsed from.
+pub(crate) fn display_fn(ident: &syn::Ident) -> Option<WhichFn> {
+    DISPLAY_FNS
+        .iter()
+        .copied()
+        .find(|which| ident == name(*which))
+}
+
+/// Returns the name of the method, and of the attribute it's derived from.
+pub(crate) fn name(which: WhichFn) -> &'static str {
+    match which {
+        WhichFn::Help => "help",
+        WhichFn::Note => "note",
+        WhichFn::Expected => "expected",
+        WhichFn::Found => "found",
+        WhichFn::Suggestion => "suggestion",
+        _ => unreachable!("MIETTE BUG: not a Display method"),
+    }
+}
+
+impl DisplayAttr {
+    /// Parses the `#[diagnostic(...)]` argument for the method `which`.
+    pub(crate) fn parse(input: ParseStream, which: WhichFn) -> syn::Result<Self> {
+        let ident = input.parse::<syn::Ident>()?;
+        if ident == name(which) {
+            let la = input.lookahead1();
+            if la.peek(syn::token::Paren) {
+                let content;
+                parenthesized!(content in input);
+                let fmt = content.parse()?;
+                let args = if content.is_empty() {
+                    TokenStream::new()
+                } else {
+                    fmt::parse_token_expr(&content, false)?
+                };
+                let display = Display {
+                    fmt,
+                    args,
+                    has_bonus_display: false,
+                };
+                Ok(DisplayAttr::Display(display))
+            } else {
+                input.parse::<Token![=]>()?;
+                Ok(DisplayAttr::Display(Display {
+                    fmt: input.parse()?,
+                    args: TokenStream::new(),
+                    has_bonus_display: false,
+                }))
+            }
+        } else {
+            Err(syn::Error::new(
+                ident.span(),
+                format!("not a {}", name(which)),
+            ))
+        }
+    }
+
+    pub(crate) fn from_fields(fields: &syn::Fields, which: WhichFn) -> syn::Result<Option<Self>> {
+        match fields {
+            syn::Fields::Named(named) => Self::from_fields_vec(named.named.iter().collect(), which),
+            syn::Fields::Unnamed(unnamed) => {
+                Self::from_fields_vec(unnamed.unnamed.iter().collect(), which)
+            }
+            syn::Fields::Unit => Ok(None),
+        }
+    }
+
+    fn from_fields_vec(fields: Vec<&syn::Field>, which: WhichFn) -> syn::Result<Option<Self>> {
+        for (i, field) in fields.iter().enumerate() {
+            for attr in &field.attrs {
+                if attr.path.is_ident(name(which)) {
+                    let member = if let Some(ident) = field.ident.clone() {
+                        syn::Member::Named(ident)
+                    } else {
+                        syn::Member::Unnamed(syn::Index {
+                            index: i as u32,
+                            span: field.span(),
+                        })
+                    };
+                    return Ok(Some(DisplayAttr::Field(member, Box::new(field.ty.clone()))));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    pub(crate) fn gen_enum(variants: &[DiagnosticDef], which: WhichFn) -> Option<TokenStream> {
+        gen_all_variants_with(variants, which, |ident, fields, concrete| {
+            let (display_pat, display_members) = display_pat_members(fields);
+            match concrete.display_attr(which)? {
+                DisplayAttr::Display(display) => {
+                    let (fmt, args) = display.expand_shorthand_cloned(&display_members);
+                    Some(quote! {
+                        Self::#ident #display_pat => std::option::Option::Some(std::boxed::Box::new(format!(#fmt #args))),
+                    })
+                }
+                DisplayAttr::Field(member, ty) => {
+                    let field = match &member {
+                        syn::Member::Named(ident) => ident.clone(),
+                        syn::Member::Unnamed(syn::Index { index, .. }) => {
+                            format_ident!("_{}", index)
+                        }
+                    };
+                    let var = quote! { __miette_internal_var };
+                    Some(quote! {
+                        Self::#ident #display_pat => {
+                            use miette::macro_helpers::ToOption;
+                            miette::macro_helpers::OptionalWrapper::<#ty>::new().to_option(&#field).as_ref().map(|#var| -> std::boxed::Box<dyn std::fmt::Display + 'a> { std::boxed::Box::new(format!("{}", #var)) })
+                        },
+                    })
+                }
+            }
+        })
+    }
+
+    pub(crate) fn gen_struct(&self, fields: &Fields, which: WhichFn) -> Option<TokenStream> {
+        let (display_pat, display_members) = display_pat_members(fields);
+        let signature = which.signature();
+        match self {
+            DisplayAttr::Display(display) => {
+                let (fmt, args) = display.expand_shorthand_cloned(&display_members);
+                Some(quote! {
+                    #signature {
+                        #[allow(unused_variables, deprecated)]
+                        let Self #display_pat = self;
+                        std::option::Option::Some(std::boxed::Box::new(format!(#fmt #args)))
+                    }
+                })
+            }
+            DisplayAttr::Field(member, ty) => {
+                let var = quote! { __miette_internal_var };
+                Some(quote! {
+                    #signature {
+                        #[allow(unused_variables, deprecated)]
+                        let Self #display_pat = self;
+                        use miette::macro_helpers::ToOption;
+                        miette::macro_helpers::OptionalWrapper::<#ty>::new().to_option(&self.#member).as_ref().map(|#var| -> std::boxed::Box<dyn std::fmt::Display + 'a> { std::boxed::Box::new(format!("{}", #var)) })
+                    }
+                })
+            }
+        }
+    }
+}
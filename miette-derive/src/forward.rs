@@ -33,12 +33,21 @@ impl Parse for Forward {
 pub enum WhichFn {
     Code,
     Help,
+    Note,
+    Expected,
+    Found,
+    Suggestion,
     Url,
     Severity,
     Labels,
+    Fixes,
+    Tags,
+    Metadata,
+    Comparison,
     SourceCode,
     Related,
     DiagnosticSource,
+    Backtrace,
 }
 
 impl WhichFn {
@@ -46,12 +55,21 @@ impl WhichFn {
         match self {
             Self::Code => quote! { code() },
             Self::Help => quote! { help() },
+            Self::Note => quote! { note() },
+            Self::Expected => quote! { expected() },
+            Self::Found => quote! { found() },
+            Self::Suggestion => quote! { suggestion() },
             Self::Url => quote! { url() },
             Self::Severity => quote! { severity() },
             Self::Labels => quote! { labels() },
+            Self::Fixes => quote! { fixes() },
+            Self::Tags => quote! { tags() },
+            Self::Metadata => quote! { metadata() },
+            Self::Comparison => quote! { comparison() },
             Self::SourceCode => quote! { source_code() },
             Self::Related => quote! { related() },
             Self::DiagnosticSource => quote! { diagnostic_source() },
+            Self::Backtrace => quote! { backtrace() },
         }
     }
 
@@ -63,6 +81,18 @@ impl WhichFn {
             Self::Help => quote! {
                 fn help<'a>(&'a self) -> std::option::Option<std::boxed::Box<dyn std::fmt::Display + 'a>>
             },
+            Self::Note => quote! {
+                fn note<'a>(&'a self) -> std::option::Option<std::boxed::Box<dyn std::fmt::Display + 'a>>
+            },
+            Self::Expected => quote! {
+                fn expected<'a>(&'a self) -> std::option::Option<std::boxed::Box<dyn std::fmt::Display + 'a>>
+            },
+            Self::Found => quote! {
+                fn found<'a>(&'a self) -> std::option::Option<std::boxed::Box<dyn std::fmt::Display + 'a>>
+            },
+            Self::Suggestion => quote! {
+                fn suggestion<'a>(&'a self) -> std::option::Option<std::boxed::Box<dyn std::fmt::Display + 'a>>
+            },
             Self::Url => quote! {
                 fn url<'a>(&'a self) -> std::option::Option<std::boxed::Box<dyn std::fmt::Display + 'a>>
             },
@@ -75,12 +105,27 @@ impl WhichFn {
             Self::Labels => quote! {
                 fn labels(&self) -> std::option::Option<std::boxed::Box<dyn std::iter::Iterator<Item = miette::LabeledSpan> + '_>>
             },
+            Self::Fixes => quote! {
+                fn fixes(&self) -> std::option::Option<std::boxed::Box<dyn std::iter::Iterator<Item = miette::Fix> + '_>>
+            },
+            Self::Tags => quote! {
+                fn tags(&self) -> std::option::Option<std::boxed::Box<dyn std::iter::Iterator<Item = miette::DiagnosticTag> + '_>>
+            },
+            Self::Metadata => quote! {
+                fn metadata(&self) -> std::option::Option<&miette::DiagnosticMetadata>
+            },
+            Self::Comparison => quote! {
+                fn comparison(&self) -> std::option::Option<&miette::SourceComparison>
+            },
             Self::SourceCode => quote! {
                 fn source_code(&self) -> std::option::Option<&dyn miette::SourceCode>
             },
             Self::DiagnosticSource => quote! {
                 fn diagnostic_source(&self) -> std::option::Option<&dyn miette::Diagnostic>
             },
+            Self::Backtrace => quote! {
+                fn backtrace(&self) -> std::option::Option<&miette::DiagnosticBacktrace>
+            },
         }
     }
 
@@ -20,20 +20,36 @@ struct Label {
     label: Option<Display>,
     ty: syn::Type,
     span: syn::Member,
+    primary: bool,
 }
 
 struct LabelAttr {
     label: Option<Display>,
+    primary: bool,
 }
 
 impl Parse for LabelAttr {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let la = input.lookahead1();
+        let mut primary = false;
         let label = if la.peek(syn::token::Paren) {
             // #[label("{}", x)]
             let content;
             parenthesized!(content in input);
-            if content.peek(syn::LitStr) {
+            // #[label(primary, "{}", x)]
+            if content.peek(syn::Ident) {
+                let ident = content.parse::<syn::Ident>()?;
+                if ident != "primary" {
+                    return Err(syn::Error::new(ident.span(), "Invalid argument to label() attribute. Expected `primary` or a literal string."));
+                }
+                primary = true;
+                if !content.is_empty() {
+                    content.parse::<Token![,]>()?;
+                }
+            }
+            if content.is_empty() && primary {
+                None
+            } else if content.peek(syn::LitStr) {
                 let fmt = content.parse()?;
                 let args = if content.is_empty() {
                     TokenStream::new()
@@ -60,7 +76,7 @@ impl Parse for LabelAttr {
         } else {
             None
         };
-        Ok(LabelAttr { label })
+        Ok(LabelAttr { label, primary })
     }
 }
 
@@ -88,11 +104,13 @@ impl Labels {
                             span: field.span(),
                         })
                     };
-                    let LabelAttr { label } = syn::parse2::<LabelAttr>(attr.tokens.clone())?;
+                    let LabelAttr { label, primary } =
+                        syn::parse2::<LabelAttr>(attr.tokens.clone())?;
                     labels.push(Label {
                         label,
                         span,
                         ty: field.ty.clone(),
+                        primary,
                     });
                 }
             }
@@ -107,13 +125,23 @@ impl Labels {
     pub(crate) fn gen_struct(&self, fields: &syn::Fields) -> Option<TokenStream> {
         let (display_pat, display_members) = display_pat_members(fields);
         let labels = self.0.iter().map(|highlight| {
-            let Label { span, label, ty } = highlight;
+            let Label {
+                span,
+                label,
+                ty,
+                primary,
+            } = highlight;
+            let ctor = if *primary {
+                quote! { new_primary_with_span }
+            } else {
+                quote! { new_with_span }
+            };
             let var = quote! { __miette_internal_var };
             if let Some(display) = label {
                 let (fmt, args) = display.expand_shorthand_cloned(&display_members);
                 quote! {
                     miette::macro_helpers::OptionalWrapper::<#ty>::new().to_option(&self.#span)
-                    .map(|#var| miette::LabeledSpan::new_with_span(
+                    .map(|#var| miette::LabeledSpan::#ctor(
                         std::option::Option::Some(format!(#fmt #args)),
                         #var.clone(),
                     ))
@@ -121,7 +149,7 @@ impl Labels {
             } else {
                 quote! {
                     miette::macro_helpers::OptionalWrapper::<#ty>::new().to_option(&self.#span)
-                    .map(|#var| miette::LabeledSpan::new_with_span(
+                    .map(|#var| miette::LabeledSpan::#ctor(
                         std::option::Option::None,
                         #var.clone(),
                     ))
@@ -148,7 +176,17 @@ impl Labels {
                 let (display_pat, display_members) = display_pat_members(fields);
                 labels.as_ref().and_then(|labels| {
                     let variant_labels = labels.0.iter().map(|label| {
-                        let Label { span, label, ty } = label;
+                        let Label {
+                            span,
+                            label,
+                            ty,
+                            primary,
+                        } = label;
+                        let ctor = if *primary {
+                            quote! { new_primary_with_span }
+                        } else {
+                            quote! { new_with_span }
+                        };
                         let field = match &span {
                             syn::Member::Named(ident) => ident.clone(),
                             syn::Member::Unnamed(syn::Index { index, .. }) => {
@@ -160,7 +198,7 @@ impl Labels {
                             let (fmt, args) = display.expand_shorthand_cloned(&display_members);
                             quote! {
                                 miette::macro_helpers::OptionalWrapper::<#ty>::new().to_option(#field)
-                                .map(|#var| miette::LabeledSpan::new_with_span(
+                                .map(|#var| miette::LabeledSpan::#ctor(
                                     std::option::Option::Some(format!(#fmt #args)),
                                     #var.clone(),
                                 ))
@@ -168,7 +206,7 @@ impl Labels {
                         } else {
                             quote! {
                                 miette::macro_helpers::OptionalWrapper::<#ty>::new().to_option(#field)
-                                .map(|#var| miette::LabeledSpan::new_with_span(
+                                .map(|#var| miette::LabeledSpan::#ctor(
                                     std::option::Option::None,
                                     #var.clone(),
                                 ))
@@ -4,22 +4,38 @@ use syn::{parse_macro_input, DeriveInput};
 use diagnostic::Diagnostic;
 
 mod code;
+mod comparison;
 mod diagnostic;
 mod diagnostic_arg;
 mod diagnostic_source;
+mod display_attr;
 mod fmt;
 mod forward;
-mod help;
 mod label;
+mod metadata;
 mod related;
 mod severity;
 mod source_code;
+mod tags;
 mod url;
 mod utils;
 
 #[proc_macro_derive(
     Diagnostic,
-    attributes(diagnostic, source_code, label, related, help, diagnostic_source)
+    attributes(
+        diagnostic,
+        source_code,
+        label,
+        related,
+        help,
+        note,
+        expected,
+        found,
+        suggestion,
+        diagnostic_source,
+        metadata,
+        comparison
+    )
 )]
 pub fn derive_diagnostic(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
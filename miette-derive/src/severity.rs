@@ -48,13 +48,19 @@ impl Parse for Severity {
 }
 
 fn get_severity(input: &str, span: Span) -> syn::Result<String> {
+    // `info` was an alias for `Advice` long before there was an `Info` level,
+    // so only the exact name of the variant picks `Info`.
+    if input == "Info" {
+        return Ok("Info".into());
+    }
     match input.to_lowercase().as_ref() {
+        "bug" => Ok("Bug".into()),
         "error" | "err" => Ok("Error".into()),
         "warning" | "warn" => Ok("Warning".into()),
         "advice" | "adv" | "info" => Ok("Advice".into()),
         _ => Err(syn::Error::new(
             span,
-            "Invalid severity level. Only Error, Warning, and Advice are supported.",
+            "Invalid severity level. Only Bug, Error, Warning, Advice, and Info are supported.",
         )),
     }
 }
@@ -0,0 +1,86 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Token,
+};
+
+use crate::{
+    diagnostic::{DiagnosticConcreteArgs, DiagnosticDef},
+    forward::WhichFn,
+    utils::gen_all_variants_with,
+};
+
+pub struct Tags(pub Vec<syn::Ident>);
+
+impl Parse for Tags {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse::<syn::Ident>()?;
+        if ident == "tags" {
+            let content;
+            parenthesized!(content in input);
+            let tags = Punctuated::<syn::Ident, Token![,]>::parse_terminated(&content)?
+                .into_iter()
+                .map(|tag| {
+                    let name = get_tag(&tag.to_string(), tag.span())?;
+                    Ok(syn::Ident::new(&name, tag.span()))
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            Ok(Tags(tags))
+        } else {
+            Err(syn::Error::new(
+                ident.span(),
+                "MIETTE BUG: not a tags option",
+            ))
+        }
+    }
+}
+
+fn get_tag(input: &str, span: Span) -> syn::Result<String> {
+    match input.to_lowercase().as_ref() {
+        "unnecessary" | "unused" => Ok("Unnecessary".into()),
+        "deprecated" => Ok("Deprecated".into()),
+        _ => Err(syn::Error::new(
+            span,
+            "Invalid diagnostic tag. Only Unnecessary and Deprecated are supported.",
+        )),
+    }
+}
+
+impl Tags {
+    fn gen_body(&self) -> TokenStream {
+        let tags = &self.0;
+        quote! {
+            std::option::Option::Some(std::boxed::Box::new(
+                vec![#(miette::DiagnosticTag::#tags),*].into_iter()
+            ))
+        }
+    }
+
+    pub(crate) fn gen_enum(variants: &[DiagnosticDef]) -> Option<TokenStream> {
+        gen_all_variants_with(
+            variants,
+            WhichFn::Tags,
+            |ident, fields, DiagnosticConcreteArgs { tags, .. }| {
+                let body = tags.as_ref()?.gen_body();
+                let fields = match fields {
+                    syn::Fields::Named(_) => quote! { { .. } },
+                    syn::Fields::Unnamed(_) => quote! { (..) },
+                    syn::Fields::Unit => quote! {},
+                };
+                Some(quote! { Self::#ident #fields => #body, })
+            },
+        )
+    }
+
+    pub(crate) fn gen_struct(&self) -> Option<TokenStream> {
+        let body = self.gen_body();
+        Some(quote! {
+            fn tags(&self) -> std::option::Option<std::boxed::Box<dyn std::iter::Iterator<Item = miette::DiagnosticTag> + '_>> {
+                #body
+            }
+        })
+    }
+}
@@ -0,0 +1,203 @@
+use std::{fmt, future::Future, pin::Pin};
+
+use crate::{Diagnostic, MietteError, MietteSpanContents, SourceCode, SourceSpan, SpanContents};
+
+/// The future returned by [`AsyncSource::read_span`].
+pub type ReadSpanFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Box<dyn SpanContents<'a> + 'a>, MietteError>> + Send + 'a>>;
+
+/**
+Like [`SourceCode`], but for source code that has to be fetched
+asynchronously, such as from a database or object storage, without blocking
+while it does.
+
+Report handlers render synchronously, so an `AsyncSource` isn't rendered from
+directly. Instead, [`FetchedSource::fetch`] reads the snippets a diagnostic
+needs from it ahead of time, and the [`FetchedSource`] it returns is used as
+the diagnostic's source code.
+
+Requires the `async` feature.
+*/
+pub trait AsyncSource: Send + Sync {
+    /// Read the bytes for a specific span from this `AsyncSource`, keeping a
+    /// certain number of lines before and after the span as context, the same
+    /// way as [`SourceCode::read_span`].
+    fn read_span<'a>(
+        &'a self,
+        span: &'a SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> ReadSpanFuture<'a>;
+}
+
+/**
+[`SourceCode`] made up of the snippets of an [`AsyncSource`] that a diagnostic
+needs in order to be rendered, fetched ahead of time.
+
+## Example
+
+```
+# async fn example(source: &dyn miette::AsyncSource) -> miette::Result<()> {
+use miette::{FetchedSource, LabeledSpan, MietteDiagnostic, Report};
+
+let diagnostic = MietteDiagnostic::new("oops!")
+    .with_label(LabeledSpan::new_with_span(Some("here".into()), (0, 3)));
+let fetched = FetchedSource::fetch(source, &diagnostic, 1).await?;
+let report = Report::new(diagnostic).with_source_code(fetched);
+# Ok(())
+# }
+```
+
+Requires the `async` feature.
+*/
+#[derive(Clone, Default)]
+pub struct FetchedSource {
+    snippets: Vec<Snippet>,
+}
+
+#[derive(Clone)]
+struct Snippet {
+    data: Vec<u8>,
+    span: SourceSpan,
+    line: usize,
+    column: usize,
+    name: Option<String>,
+    language: Option<String>,
+    binary: bool,
+}
+
+impl Snippet {
+    fn end(&self) -> usize {
+        self.span.offset() + self.span.len()
+    }
+}
+
+impl FetchedSource {
+    /// Fetches the snippets of `source` needed to render `diagnostic`: one
+    /// for each of its labels, with `context_lines` lines of context on
+    /// either side, and one for each of its fixes. Use the same number of
+    /// context lines as the report handler that will render it.
+    pub async fn fetch(
+        source: &dyn AsyncSource,
+        diagnostic: &dyn Diagnostic,
+        context_lines: usize,
+    ) -> Result<Self, MietteError> {
+        let mut spans = Vec::new();
+        if let Some(labels) = diagnostic.labels() {
+            spans.extend(labels.map(|label| (*label.inner(), context_lines)));
+        }
+        if let Some(fixes) = diagnostic.fixes() {
+            // Fixes are always rendered with a line of context.
+            spans.extend(fixes.map(|fix| (*fix.span(), 1)));
+        }
+        spans.sort_unstable_by_key(|(span, lines)| (span.offset(), span.len(), *lines));
+        spans.dedup();
+
+        let mut snippets = Vec::with_capacity(spans.len());
+        for (span, lines) in &spans {
+            let contents = source.read_span(span, *lines, *lines).await?;
+            snippets.push(Snippet {
+                data: contents.data().to_vec(),
+                span: *contents.span(),
+                line: contents.line(),
+                column: contents.column(),
+                name: contents.name().map(String::from),
+                language: contents.language().map(String::from),
+                binary: contents.is_binary(),
+            });
+        }
+        Ok(Self::merge(snippets))
+    }
+
+    /// Joins overlapping snippets together, so that labels close enough to
+    /// share lines can still be rendered as one snippet.
+    fn merge(mut snippets: Vec<Snippet>) -> Self {
+        snippets.sort_unstable_by_key(|snippet| snippet.span.offset());
+        let mut merged: Vec<Snippet> = Vec::with_capacity(snippets.len());
+        for snippet in snippets {
+            match merged.last_mut() {
+                Some(last) if snippet.span.offset() <= last.end() => {
+                    if snippet.end() > last.end() {
+                        let overlap = last.end() - snippet.span.offset();
+                        last.data.extend_from_slice(&snippet.data[overlap..]);
+                        last.span = (last.span.offset(), last.data.len()).into();
+                    }
+                }
+                _ => merged.push(snippet),
+            }
+        }
+        Self { snippets: merged }
+    }
+}
+
+impl fmt::Debug for FetchedSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FetchedSource")
+            .field(
+                "spans",
+                &self
+                    .snippets
+                    .iter()
+                    .map(|snippet| snippet.span)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl SourceCode for FetchedSource {
+    fn read_span<'a>(
+        &'a self,
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        let snippet = self
+            .snippets
+            .iter()
+            .find(|snippet| {
+                snippet.span.offset() <= span.offset()
+                    && span.offset() + span.len() <= snippet.end()
+            })
+            .ok_or(MietteError::OutOfBounds)?;
+        // Read the span out of the snippet the same way it would be read out
+        // of the whole source, as far as the context that was fetched goes.
+        let relative_span = (span.offset() - snippet.span.offset(), span.len()).into();
+        let contents = <[u8] as SourceCode>::read_span(
+            &snippet.data,
+            &relative_span,
+            context_lines_before,
+            context_lines_after,
+        )?;
+        let span = (
+            snippet.span.offset() + contents.span().offset(),
+            contents.span().len(),
+        )
+            .into();
+        // Snippets fetched without context lines before them can start partway
+        // through their first line.
+        let column = if contents.line() == 0 {
+            snippet.column + contents.column()
+        } else {
+            contents.column()
+        };
+        let line = snippet.line + contents.line();
+        let line_count = snippet.line + contents.line_count();
+        let contents = match &snippet.name {
+            Some(name) => MietteSpanContents::new_named(
+                name.clone(),
+                contents.data(),
+                span,
+                line,
+                column,
+                line_count,
+            ),
+            None => MietteSpanContents::new(contents.data(), span, line, column, line_count),
+        }
+        .with_binary(snippet.binary);
+        Ok(Box::new(match &snippet.language {
+            Some(language) => contents.with_language(language.clone()),
+            None => contents,
+        }))
+    }
+}
@@ -0,0 +1,154 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::Diagnostic;
+
+/**
+Registry of the [`Diagnostic::code`]s an application can emit, each with a
+title and a long-form explanation, for `--explain CODE` flags and for checking
+that every emitted code is documented.
+
+Explanations are free-form text, usually markdown, and are printed as-is.
+
+## Example
+
+```
+use miette::{CodeRegistry, Diagnostic};
+use thiserror::Error;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("oops!")]
+#[diagnostic(code(my_app::oops))]
+struct MyBad;
+
+let registry = CodeRegistry::new().with_code(
+    "my_app::oops",
+    "Something went wrong",
+    "This happens when something goes wrong.\n\nTry doing it right instead.",
+);
+
+// `my-app --explain my_app::oops`
+let explanation = registry.explain("my_app::oops").unwrap();
+assert!(explanation.to_string().starts_with("my_app::oops: Something went wrong"));
+
+assert!(registry.unregistered_codes(&MyBad).is_empty());
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct CodeRegistry {
+    codes: BTreeMap<String, CodeExplanation>,
+}
+
+impl CodeRegistry {
+    /// Create a new, empty [`CodeRegistry`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a code with a short title and a long-form explanation.
+    /// Registering a code again replaces its previous entry.
+    pub fn with_code(
+        mut self,
+        code: impl Into<String>,
+        title: impl Into<String>,
+        explanation: impl Into<String>,
+    ) -> Self {
+        self.register(code, title, explanation);
+        self
+    }
+
+    /// Registers a code with a short title and a long-form explanation,
+    /// returning its previous entry, if any.
+    pub fn register(
+        &mut self,
+        code: impl Into<String>,
+        title: impl Into<String>,
+        explanation: impl Into<String>,
+    ) -> Option<CodeExplanation> {
+        let code = code.into();
+        let entry = CodeExplanation {
+            code: code.clone(),
+            title: title.into(),
+            explanation: explanation.into(),
+        };
+        self.codes.insert(code, entry)
+    }
+
+    /// Looks up the explanation for a code.
+    pub fn explain(&self, code: &str) -> Option<&CodeExplanation> {
+        self.codes.get(code)
+    }
+
+    /// Whether a code has been registered.
+    pub fn contains(&self, code: &str) -> bool {
+        self.codes.contains_key(code)
+    }
+
+    /// Iterates over all registered codes, ordered by code.
+    pub fn iter(&self) -> impl Iterator<Item = &CodeExplanation> {
+        self.codes.values()
+    }
+
+    /// Returns the codes used by a [`Diagnostic`] that haven't been
+    /// registered, including those of its related diagnostics and of any
+    /// diagnostics in its cause chain. Each code is listed once, in the order
+    /// it's first found.
+    pub fn unregistered_codes(&self, diagnostic: &(dyn Diagnostic)) -> Vec<String> {
+        let mut unregistered = Vec::new();
+        self.collect_unregistered(diagnostic, &mut unregistered);
+        unregistered
+    }
+
+    fn collect_unregistered(&self, diagnostic: &(dyn Diagnostic), unregistered: &mut Vec<String>) {
+        if let Some(code) = diagnostic.code() {
+            let code = code.to_string();
+            if !self.contains(&code) && !unregistered.contains(&code) {
+                unregistered.push(code);
+            }
+        }
+        if let Some(related) = diagnostic.related() {
+            for rel in related {
+                self.collect_unregistered(rel, unregistered);
+            }
+        }
+        if let Some(source) = diagnostic.diagnostic_source() {
+            self.collect_unregistered(source, unregistered);
+        }
+    }
+}
+
+/**
+A code registered in a [`CodeRegistry`], with its title and explanation.
+
+Displays as `code: title`, followed by a blank line and the explanation.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeExplanation {
+    code: String,
+    title: String,
+    explanation: String,
+}
+
+impl CodeExplanation {
+    /// The code being explained.
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// A short, one-line title for the code.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The long-form explanation of the code.
+    pub fn explanation(&self) -> &str {
+        &self.explanation
+    }
+}
+
+impl fmt::Display for CodeExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.code, self.title)?;
+        writeln!(f)?;
+        write!(f, "{}", self.explanation)
+    }
+}
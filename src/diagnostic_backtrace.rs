@@ -0,0 +1,118 @@
+use std::fmt;
+
+use once_cell::sync::OnceCell;
+
+/**
+A stack backtrace, captured where a [`Diagnostic`](crate::Diagnostic) was
+created, so that reporters can show where it originated.
+
+Capturing a backtrace is expensive, so it's opt-in:
+[`DiagnosticBacktrace::capture`] only captures one if the `RUST_LIB_BACKTRACE`
+or `RUST_BACKTRACE` environment variable is set to something other than `0`,
+the same way `std`, `anyhow` and `eyre` do.
+
+Backtraces are only ever captured with the `fancy` feature enabled. Without
+it, every `DiagnosticBacktrace` is empty.
+*/
+#[derive(Clone)]
+pub struct DiagnosticBacktrace {
+    #[cfg(feature = "fancy")]
+    inner: Option<backtrace::Backtrace>,
+}
+
+impl DiagnosticBacktrace {
+    /// Captures a backtrace of the current thread, if backtraces have been
+    /// enabled through the environment.
+    pub fn capture() -> Self {
+        static ENABLED: OnceCell<bool> = OnceCell::new();
+        let enabled = *ENABLED.get_or_init(|| {
+            let var = std::env::var("RUST_LIB_BACKTRACE")
+                .or_else(|_| std::env::var("RUST_BACKTRACE"))
+                .unwrap_or_default();
+            !var.is_empty() && var != "0"
+        });
+        if enabled {
+            Self::force_capture()
+        } else {
+            Self::disabled()
+        }
+    }
+
+    /// Captures a backtrace of the current thread, regardless of the
+    /// environment.
+    pub fn force_capture() -> Self {
+        Self {
+            #[cfg(feature = "fancy")]
+            inner: Some(backtrace::Backtrace::new()),
+        }
+    }
+
+    /// Makes an empty backtrace, with no frames.
+    pub fn disabled() -> Self {
+        Self {
+            #[cfg(feature = "fancy")]
+            inner: None,
+        }
+    }
+
+    /// Whether any frames were captured.
+    pub fn is_captured(&self) -> bool {
+        #[cfg(feature = "fancy")]
+        {
+            self.inner.is_some()
+        }
+        #[cfg(not(feature = "fancy"))]
+        {
+            false
+        }
+    }
+}
+
+impl fmt::Debug for DiagnosticBacktrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_captured() {
+            write!(f, "DiagnosticBacktrace [captured]")
+        } else {
+            write!(f, "DiagnosticBacktrace [disabled]")
+        }
+    }
+}
+
+/// Writes one frame per line, starting with the frame the backtrace was
+/// captured from, followed by its source location on a line of its own.
+#[cfg(feature = "fancy")]
+impl fmt::Display for DiagnosticBacktrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(inner) = &self.inner {
+            // Skip the frames of the backtrace crate and of miette itself,
+            // which are just noise when looking for where an error came from.
+            let frames = inner.frames().iter().skip_while(|frame| {
+                frame.symbols().iter().all(|symbol| {
+                    symbol.name().map_or(false, |name| {
+                        let name = name.to_string();
+                        name.starts_with("backtrace::") || name.starts_with("miette::")
+                    })
+                })
+            });
+            for (idx, frame) in frames.enumerate() {
+                for symbol in frame.symbols() {
+                    match symbol.name() {
+                        Some(name) => writeln!(f, "{:4}: {}", idx, name)?,
+                        None => writeln!(f, "{:4}: <unknown>", idx)?,
+                    }
+                    if let (Some(file), Some(line)) = (symbol.filename(), symbol.lineno()) {
+                        writeln!(f, "          at {}:{}", file.display(), line)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "fancy"))]
+impl fmt::Display for DiagnosticBacktrace {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{handlers::primary_label, Diagnostic, SourceSpan};
+
+/**
+Identifies a [`Diagnostic`] by its code, message and primary span, so that
+identical diagnostics, such as those emitted by several passes over the same
+code, can be compared and deduplicated.
+
+`Diagnostic`s themselves don't implement `Eq` or `Hash`, since most of what
+they carry (help text, related diagnostics, source code) doesn't matter for
+telling them apart. A `DiagnosticKey` is a snapshot of just the parts that do.
+The primary span is the diagnostic's primary label, or its first label if none
+is marked as primary.
+
+## Example
+
+```
+use std::collections::HashSet;
+
+use miette::{Diagnostic, DiagnosticKey};
+use thiserror::Error;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("unused variable")]
+#[diagnostic(code(lint::unused))]
+struct Unused {
+    #[label]
+    span: (usize, usize),
+}
+
+let emitted = vec![
+    Unused { span: (4, 1) },
+    Unused { span: (4, 1) },
+    Unused { span: (10, 1) },
+];
+let mut seen = HashSet::new();
+let unique = emitted
+    .iter()
+    .filter(|diag| seen.insert(DiagnosticKey::new(*diag)))
+    .count();
+assert_eq!(unique, 2);
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiagnosticKey {
+    code: Option<String>,
+    message: String,
+    span: Option<SourceSpan>,
+}
+
+impl DiagnosticKey {
+    /// Create a new [`DiagnosticKey`] for a [`Diagnostic`].
+    pub fn new(diagnostic: &(dyn Diagnostic)) -> Self {
+        Self {
+            code: diagnostic.code().map(|code| code.to_string()),
+            message: diagnostic.to_string(),
+            span: primary_label(diagnostic).map(|label| *label.inner()),
+        }
+    }
+
+    /// The code of the diagnostic, if any.
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// The message of the diagnostic.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The span of the diagnostic's primary label, if it has any labels.
+    pub fn span(&self) -> Option<&SourceSpan> {
+        self.span.as_ref()
+    }
+}
@@ -0,0 +1,92 @@
+use std::{collections::BTreeMap, iter::FromIterator};
+
+/**
+Structured key-value metadata attached to a [`Diagnostic`](crate::Diagnostic),
+for things like request IDs, rule categories or lint groups.
+
+Reporters that don't know about a key can still pass it along: the JSON
+reporter, for example, renders every entry as-is. Keys are kept in sorted
+order, and each key has a single value.
+
+## Example
+
+```
+use miette::{Diagnostic, DiagnosticMetadata};
+use thiserror::Error;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("oops!")]
+struct MyBad {
+    #[metadata]
+    metadata: DiagnosticMetadata,
+}
+
+let err = MyBad {
+    metadata: DiagnosticMetadata::new()
+        .with("request_id", "b2c7")
+        .with("lint_group", "style"),
+};
+let metadata = err.metadata().unwrap();
+assert_eq!(metadata.get("request_id"), Some("b2c7"));
+```
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiagnosticMetadata {
+    entries: BTreeMap<String, String>,
+}
+
+impl DiagnosticMetadata {
+    /// Create a new, empty [`DiagnosticMetadata`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value of a key, replacing any previous value.
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Sets the value of a key, returning its previous value, if any.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        self.entries.insert(key.into(), value.into())
+    }
+
+    /// Returns the value of a key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    /// Iterates over all entries, ordered by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> Extend<(K, V)> for DiagnosticMetadata {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, entries: I) {
+        for (key, value) in entries {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for DiagnosticMetadata {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(entries: I) -> Self {
+        let mut metadata = Self::new();
+        metadata.extend(entries);
+        metadata
+    }
+}
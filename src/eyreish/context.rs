@@ -4,7 +4,10 @@ use core::fmt::{self, Debug, Display, Write};
 
 use std::error::Error as StdError;
 
-use crate::{Diagnostic, LabeledSpan};
+use crate::{
+    Diagnostic, DiagnosticBacktrace, DiagnosticMetadata, DiagnosticTag, Fix, LabeledSpan,
+    SourceComparison,
+};
 
 mod ext {
     use super::*;
@@ -137,6 +140,22 @@ where
         self.error.help()
     }
 
+    fn note<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.note()
+    }
+
+    fn expected<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.expected()
+    }
+
+    fn found<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.found()
+    }
+
+    fn suggestion<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.suggestion()
+    }
+
     fn url<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
         self.error.url()
     }
@@ -145,6 +164,26 @@ where
         self.error.labels()
     }
 
+    fn fixes<'a>(&'a self) -> Option<Box<dyn Iterator<Item = Fix> + 'a>> {
+        self.error.fixes()
+    }
+
+    fn tags<'a>(&'a self) -> Option<Box<dyn Iterator<Item = DiagnosticTag> + 'a>> {
+        self.error.tags()
+    }
+
+    fn metadata(&self) -> Option<&DiagnosticMetadata> {
+        self.error.metadata()
+    }
+
+    fn comparison(&self) -> Option<&SourceComparison> {
+        self.error.comparison()
+    }
+
+    fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+        self.error.backtrace()
+    }
+
     fn source_code(&self) -> Option<&dyn crate::SourceCode> {
         self.error.source_code()
     }
@@ -170,6 +209,22 @@ where
         unsafe { ErrorImpl::diagnostic(self.error.inner.by_ref()).help() }
     }
 
+    fn note<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        unsafe { ErrorImpl::diagnostic(self.error.inner.by_ref()).note() }
+    }
+
+    fn expected<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        unsafe { ErrorImpl::diagnostic(self.error.inner.by_ref()).expected() }
+    }
+
+    fn found<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        unsafe { ErrorImpl::diagnostic(self.error.inner.by_ref()).found() }
+    }
+
+    fn suggestion<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        unsafe { ErrorImpl::diagnostic(self.error.inner.by_ref()).suggestion() }
+    }
+
     fn url<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
         unsafe { ErrorImpl::diagnostic(self.error.inner.by_ref()).url() }
     }
@@ -178,6 +233,26 @@ where
         unsafe { ErrorImpl::diagnostic(self.error.inner.by_ref()).labels() }
     }
 
+    fn fixes<'a>(&'a self) -> Option<Box<dyn Iterator<Item = Fix> + 'a>> {
+        unsafe { ErrorImpl::diagnostic(self.error.inner.by_ref()).fixes() }
+    }
+
+    fn tags<'a>(&'a self) -> Option<Box<dyn Iterator<Item = DiagnosticTag> + 'a>> {
+        unsafe { ErrorImpl::diagnostic(self.error.inner.by_ref()).tags() }
+    }
+
+    fn metadata(&self) -> Option<&DiagnosticMetadata> {
+        unsafe { ErrorImpl::diagnostic(self.error.inner.by_ref()).metadata() }
+    }
+
+    fn comparison(&self) -> Option<&SourceComparison> {
+        unsafe { ErrorImpl::diagnostic(self.error.inner.by_ref()).comparison() }
+    }
+
+    fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+        unsafe { ErrorImpl::diagnostic(self.error.inner.by_ref()).backtrace() }
+    }
+
     fn source_code(&self) -> Option<&dyn crate::SourceCode> {
         self.error.source_code()
     }
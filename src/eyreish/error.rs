@@ -8,8 +8,9 @@ use super::ptr::{Mut, Own, Ref};
 use super::Report;
 use super::ReportHandler;
 use crate::chain::Chain;
-use crate::eyreish::wrapper::WithSourceCode;
-use crate::{Diagnostic, SourceCode};
+use crate::eyreish::into_diagnostic::DiagnosticError;
+use crate::eyreish::wrapper::{MessageError, WithSeverity, WithSourceCode, WithSourceMap};
+use crate::{Diagnostic, DiagnosticBacktrace, Severity, SourceCode, SourceMap};
 use core::ops::{Deref, DerefMut};
 
 impl Report {
@@ -28,6 +29,23 @@ impl Report {
         Report::from_std(error)
     }
 
+    /// Create a new error object from an error type that doesn't implement
+    /// [`Diagnostic`], such as one from a third-party crate, like what
+    /// [`IntoDiagnostic`](crate::IntoDiagnostic) does for `Result`s.
+    ///
+    /// The error's message and cause chain are kept, but it's wrapped in an
+    /// anonymous [`Diagnostic`], so it can't be downcast back to `E`.
+    #[cfg_attr(track_caller, track_caller)]
+    pub fn from_err<E>(error: E) -> Self
+    where
+        E: StdError + Send + Sync + 'static,
+    {
+        Report::new(DiagnosticError {
+            error: Box::new(error),
+            code: None,
+        })
+    }
+
     /// Create a new error object from a printable error message.
     ///
     /// If the argument implements std::error::Error, prefer `Report::new`
@@ -36,6 +54,10 @@ impl Report {
     /// now or in the future, use `miette!(err)` which handles either way
     /// correctly.
     ///
+    /// If backtraces are enabled through the environment, a
+    /// [`DiagnosticBacktrace`] is captured here, and returned by the error's
+    /// [`Diagnostic::backtrace`].
+    ///
     /// `Report::msg("...")` is equivalent to `miette!("...")` but occasionally
     /// convenient in places where a function is preferable over a macro, such
     /// as iterator or stream combinators:
@@ -111,20 +133,21 @@ impl Report {
     where
         M: Display + Debug + Send + Sync + 'static,
     {
-        use super::wrapper::MessageError;
-        let error: MessageError<M> = MessageError(message);
+        let error: MessageError<M> = MessageError {
+            message,
+            backtrace: DiagnosticBacktrace::capture(),
+        };
         let vtable = &ErrorVTable {
             object_drop: object_drop::<MessageError<M>>,
             object_ref: object_ref::<MessageError<M>>,
             object_ref_stderr: object_ref_stderr::<MessageError<M>>,
             object_boxed: object_boxed::<MessageError<M>>,
             object_boxed_stderr: object_boxed_stderr::<MessageError<M>>,
-            object_downcast: object_downcast::<M>,
-            object_drop_rest: object_drop_front::<M>,
+            object_downcast: message_downcast::<M>,
+            object_drop_rest: message_drop_rest::<M>,
         };
 
-        // Safety: MessageError is repr(transparent) so it is okay for the
-        // vtable to allow casting the MessageError<M> to M.
+        // Safety: passing vtable that operates on the right type.
         let handler = Some(super::capture_handler(&error));
 
         unsafe { Report::construct(error, vtable, handler) }
@@ -409,6 +432,29 @@ impl Report {
         }
     }
 
+    /// Renders this report with its handler, the same way its `Debug` output
+    /// does, into any [`std::io::Write`].
+    pub fn write_report(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        write!(writer, "{:?}", self)
+    }
+
+    /// Renders this report with its handler, the same way its `Debug` output
+    /// does, and returns the result as a `String`.
+    pub fn render_to_string(&self) -> Result<String, fmt::Error> {
+        use fmt::Write as _;
+
+        let mut out = String::new();
+        write!(out, "{:?}", self)?;
+        Ok(out)
+    }
+
+    /// Returns a wrapper whose [`Display`] output is this report as rendered
+    /// by its handler, the same as its `Debug` output. See
+    /// [`Rendered`](crate::Rendered).
+    pub fn rendered(&self) -> crate::Rendered<'_> {
+        crate::Rendered::new(self.handler(), self.as_ref())
+    }
+
     /// Provide source code for this error
     pub fn with_source_code(self, source_code: impl SourceCode + Send + Sync + 'static) -> Report {
         WithSourceCode {
@@ -417,6 +463,29 @@ impl Report {
         }
         .into()
     }
+
+    /// Overrides the [`Severity`] of this error, for
+    /// example to demote an error from a library to a warning, or to promote
+    /// a warning to an error. Everything else about the error stays the same.
+    pub fn with_severity(self, severity: Severity) -> Report {
+        WithSeverity {
+            error: self,
+            severity,
+        }
+        .into()
+    }
+
+    /// Maps this error's labels and fixes from the generated code they point
+    /// into back to the original source code it was generated from, using a
+    /// [`SourceMap`], which also becomes its source code. Labels and fixes
+    /// that don't map back to anything are dropped.
+    pub fn with_source_map(self, source_map: SourceMap) -> Report {
+        WithSourceMap {
+            error: self,
+            source_map,
+        }
+        .into()
+    }
 }
 
 impl<E> From<E> for Report
@@ -568,6 +637,31 @@ where
     }
 }
 
+// Safety: requires layout of *e to match ErrorImpl<MessageError<M>>.
+unsafe fn message_downcast<M>(e: Ref<'_, ErasedErrorImpl>, target: TypeId) -> Option<Ref<'_, ()>>
+where
+    M: 'static,
+{
+    if TypeId::of::<M>() == target {
+        let unerased = e.cast::<ErrorImpl<MessageError<M>>>().deref();
+        Some(Ref::new(&unerased._object.message).cast::<()>())
+    } else {
+        None
+    }
+}
+
+// Safety: requires layout of *e to match ErrorImpl<MessageError<M>>.
+unsafe fn message_drop_rest<M>(e: Own<ErasedErrorImpl>, target: TypeId)
+where
+    M: 'static,
+{
+    // Called after downcasting by value to the M and doing a ptr::read to
+    // take ownership of that value.
+    let _ = target;
+    let unerased = e.cast::<ErrorImpl<MessageError<ManuallyDrop<M>>>>().boxed();
+    drop(unerased);
+}
+
 // Safety: requires layout of *e to match ErrorImpl<ContextError<D, E>>.
 unsafe fn context_downcast<D, E>(e: Ref<'_, ErasedErrorImpl>, target: TypeId) -> Option<Ref<'_, ()>>
 where
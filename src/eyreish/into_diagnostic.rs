@@ -1,13 +1,35 @@
-use thiserror::Error;
+use std::fmt;
 
 use crate::{Diagnostic, Report};
 
 /// Convenience [`Diagnostic`] that can be used as an "anonymous" wrapper for
-/// Errors. This is intended to be paired with [`IntoDiagnostic`].
-#[derive(Debug, Error)]
-#[error(transparent)]
-struct DiagnosticError(Box<dyn std::error::Error + Send + Sync + 'static>);
-impl Diagnostic for DiagnosticError {}
+/// Errors, optionally with a code. This is intended to be paired with
+/// [`IntoDiagnostic`].
+#[derive(Debug)]
+pub(crate) struct DiagnosticError {
+    pub(crate) error: Box<dyn std::error::Error + Send + Sync + 'static>,
+    pub(crate) code: Option<String>,
+}
+
+impl fmt::Display for DiagnosticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl std::error::Error for DiagnosticError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+impl Diagnostic for DiagnosticError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.code
+            .as_ref()
+            .map(|code| Box::new(code) as Box<dyn fmt::Display>)
+    }
+}
 
 /**
 Convenience trait that adds a [`.into_diagnostic()`](IntoDiagnostic::into_diagnostic) method that converts a type implementing
@@ -24,10 +46,42 @@ pub trait IntoDiagnostic<T, E> {
     /// Converts [`Result`] types that return regular [`std::error::Error`]s
     /// into a [`Result`] that returns a [`Diagnostic`].
     fn into_diagnostic(self) -> Result<T, Report>;
+
+    /// Like [`into_diagnostic`](IntoDiagnostic::into_diagnostic), but gives
+    /// the resulting [`Diagnostic`] a [code](Diagnostic::code), so errors
+    /// from other crates can be told apart, and looked up, like any other.
+    ///
+    /// ```
+    /// use miette::{IntoDiagnostic, Result};
+    ///
+    /// fn read_config() -> Result<String> {
+    ///     std::fs::read_to_string("config.toml").into_diagnostic_with_code("config::read")
+    /// }
+    /// ```
+    fn into_diagnostic_with_code(self, code: impl Into<String>) -> Result<T, Report>
+    where
+        Self: Sized,
+    {
+        self.into_diagnostic().map_err(|report| {
+            Report::new(DiagnosticError {
+                error: report.into(),
+                code: Some(code.into()),
+            })
+        })
+    }
 }
 
 impl<T, E: std::error::Error + Send + Sync + 'static> IntoDiagnostic<T, E> for Result<T, E> {
     fn into_diagnostic(self) -> Result<T, Report> {
-        self.map_err(|e| DiagnosticError(Box::new(e)).into())
+        self.map_err(Report::from_err)
+    }
+
+    fn into_diagnostic_with_code(self, code: impl Into<String>) -> Result<T, Report> {
+        self.map_err(|error| {
+            Report::new(DiagnosticError {
+                error: Box::new(error),
+                code: Some(code.into()),
+            })
+        })
     }
 }
@@ -196,17 +196,92 @@ pub trait ReportHandler: core::any::Any + Send + Sync {
         Ok(())
     }
 
+    /// Renders a diagnostic the same way [`debug`](ReportHandler::debug)
+    /// does, but into any [`std::io::Write`], such as a file, a socket, or a
+    /// byte buffer.
+    ///
+    /// The report is written out as it's rendered, without being collected
+    /// into a `String` first.
+    fn write_report(
+        &self,
+        error: &(dyn Diagnostic),
+        writer: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        write!(writer, "{}", Rendered::new(self, error))
+    }
+
+    /// Renders a diagnostic the same way [`debug`](ReportHandler::debug)
+    /// does, and returns the result as a `String`, for logging, testing, or
+    /// sending it elsewhere.
+    fn render_to_string(&self, error: &(dyn Diagnostic)) -> Result<String, core::fmt::Error> {
+        use core::fmt::Write as _;
+
+        let mut out = String::new();
+        write!(out, "{}", Rendered::new(self, error))?;
+        Ok(out)
+    }
+
     /// Store the location of the caller who constructed this error report
     #[allow(unused_variables)]
     fn track_caller(&mut self, location: &'static std::panic::Location<'static>) {}
 }
 
+/**
+Wraps a [`Diagnostic`] so that its [`Display`] output is the full report
+rendered by a [`ReportHandler`], instead of just the diagnostic's message.
+
+This is useful when `{}` formatting is all that's available, such as when
+handing a report off to a logging library.
+
+```rust
+use miette::{miette, NarratableReportHandler, Rendered, Report};
+
+let report: Report = miette!("oh no!");
+let handler = NarratableReportHandler::new();
+let rendered = format!("{}", Rendered::new(&handler, report.as_ref()));
+assert!(rendered.starts_with("oh no!"));
+
+// A `Report` can also be rendered with its own handler.
+println!("{}", report.rendered());
+```
+*/
+pub struct Rendered<'a, H: ?Sized = dyn ReportHandler> {
+    handler: &'a H,
+    error: &'a dyn Diagnostic,
+}
+
+impl<'a, H: ReportHandler + ?Sized> Rendered<'a, H> {
+    /// Wraps `error` so that it's displayed as rendered by `handler`.
+    pub fn new(handler: &'a H, error: &'a dyn Diagnostic) -> Self {
+        Self { handler, error }
+    }
+}
+
+impl<H: ReportHandler + ?Sized> Display for Rendered<'_, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.handler.debug(self.error, f)
+    }
+}
+
+impl<H: ReportHandler + ?Sized> core::fmt::Debug for Rendered<'_, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Rendered")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
 /// type alias for `Result<T, Report>`
 ///
 /// This is a reasonable return type to use throughout your application but also
 /// for `main()`. If you do, failures will be printed along with a backtrace if
 /// one was captured.
 ///
+/// When an error is returned from `main()`, Rust prints it using its `Debug`
+/// implementation, which for a [`Report`] renders it with the [`ReportHandler`]
+/// installed with [`set_hook`], or the default one, so it looks the same as
+/// anywhere else the report is printed, rather than like a derived `Debug`.
+///
 /// `miette::Result` may be used with one *or* two type parameters.
 ///
 /// ```rust
@@ -2,15 +2,23 @@ use core::fmt::{self, Debug, Display};
 
 use std::error::Error as StdError;
 
-use crate::{Diagnostic, LabeledSpan, Report, SourceCode};
+use crate::{
+    Diagnostic, DiagnosticBacktrace, DiagnosticMetadata, DiagnosticTag, Fix, LabeledSpan, Report,
+    Severity, SourceCode, SourceComparison, SourceMap,
+};
 
 use crate as miette;
 
 #[repr(transparent)]
 pub(crate) struct DisplayError<M>(pub(crate) M);
 
-#[repr(transparent)]
-pub(crate) struct MessageError<M>(pub(crate) M);
+// repr C to ensure that MessageError<M> has the same layout as
+// MessageError<ManuallyDrop<M>>.
+#[repr(C)]
+pub(crate) struct MessageError<M> {
+    pub(crate) message: M,
+    pub(crate) backtrace: DiagnosticBacktrace,
+}
 
 pub(crate) struct NoneError;
 
@@ -40,7 +48,7 @@ where
     M: Display + Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        Debug::fmt(&self.0, f)
+        Debug::fmt(&self.message, f)
     }
 }
 
@@ -49,12 +57,19 @@ where
     M: Display + Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        Display::fmt(&self.0, f)
+        Display::fmt(&self.message, f)
     }
 }
 
 impl<M> StdError for MessageError<M> where M: Display + Debug + 'static {}
-impl<M> Diagnostic for MessageError<M> where M: Display + Debug + 'static {}
+impl<M> Diagnostic for MessageError<M>
+where
+    M: Display + Debug + 'static,
+{
+    fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+        Some(&self.backtrace).filter(|backtrace| backtrace.is_captured())
+    }
+}
 
 impl Debug for NoneError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -87,6 +102,22 @@ impl Diagnostic for BoxedError {
         self.0.help()
     }
 
+    fn note<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.0.note()
+    }
+
+    fn expected<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.0.expected()
+    }
+
+    fn found<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.0.found()
+    }
+
+    fn suggestion<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.0.suggestion()
+    }
+
     fn url<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
         self.0.url()
     }
@@ -95,6 +126,26 @@ impl Diagnostic for BoxedError {
         self.0.labels()
     }
 
+    fn fixes<'a>(&'a self) -> Option<Box<dyn Iterator<Item = Fix> + 'a>> {
+        self.0.fixes()
+    }
+
+    fn tags<'a>(&'a self) -> Option<Box<dyn Iterator<Item = DiagnosticTag> + 'a>> {
+        self.0.tags()
+    }
+
+    fn metadata(&self) -> Option<&DiagnosticMetadata> {
+        self.0.metadata()
+    }
+
+    fn comparison(&self) -> Option<&SourceComparison> {
+        self.0.comparison()
+    }
+
+    fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+        self.0.backtrace()
+    }
+
     fn source_code(&self) -> Option<&dyn miette::SourceCode> {
         self.0.source_code()
     }
@@ -154,6 +205,22 @@ impl<E: Diagnostic, C: SourceCode> Diagnostic for WithSourceCode<E, C> {
         self.error.help()
     }
 
+    fn note<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.note()
+    }
+
+    fn expected<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.expected()
+    }
+
+    fn found<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.found()
+    }
+
+    fn suggestion<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.suggestion()
+    }
+
     fn url<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
         self.error.url()
     }
@@ -162,6 +229,26 @@ impl<E: Diagnostic, C: SourceCode> Diagnostic for WithSourceCode<E, C> {
         self.error.labels()
     }
 
+    fn fixes<'a>(&'a self) -> Option<Box<dyn Iterator<Item = Fix> + 'a>> {
+        self.error.fixes()
+    }
+
+    fn tags<'a>(&'a self) -> Option<Box<dyn Iterator<Item = DiagnosticTag> + 'a>> {
+        self.error.tags()
+    }
+
+    fn metadata(&self) -> Option<&DiagnosticMetadata> {
+        self.error.metadata()
+    }
+
+    fn comparison(&self) -> Option<&SourceComparison> {
+        self.error.comparison()
+    }
+
+    fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+        self.error.backtrace()
+    }
+
     fn source_code(&self) -> Option<&dyn miette::SourceCode> {
         Some(&self.source_code)
     }
@@ -188,6 +275,22 @@ impl<C: SourceCode> Diagnostic for WithSourceCode<Report, C> {
         self.error.help()
     }
 
+    fn note<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.note()
+    }
+
+    fn expected<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.expected()
+    }
+
+    fn found<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.found()
+    }
+
+    fn suggestion<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.suggestion()
+    }
+
     fn url<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
         self.error.url()
     }
@@ -196,6 +299,26 @@ impl<C: SourceCode> Diagnostic for WithSourceCode<Report, C> {
         self.error.labels()
     }
 
+    fn fixes<'a>(&'a self) -> Option<Box<dyn Iterator<Item = Fix> + 'a>> {
+        self.error.fixes()
+    }
+
+    fn tags<'a>(&'a self) -> Option<Box<dyn Iterator<Item = DiagnosticTag> + 'a>> {
+        self.error.tags()
+    }
+
+    fn metadata(&self) -> Option<&DiagnosticMetadata> {
+        self.error.metadata()
+    }
+
+    fn comparison(&self) -> Option<&SourceComparison> {
+        self.error.comparison()
+    }
+
+    fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+        self.error.backtrace()
+    }
+
     fn source_code(&self) -> Option<&dyn miette::SourceCode> {
         Some(&self.source_code)
     }
@@ -232,3 +355,367 @@ impl<C> StdError for WithSourceCode<Report, C> {
         self.error.source()
     }
 }
+
+pub(crate) struct WithSeverity<E> {
+    pub(crate) error: E,
+    pub(crate) severity: Severity,
+}
+
+impl<E: Diagnostic> Diagnostic for WithSeverity<E> {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.code()
+    }
+
+    fn severity(&self) -> Option<miette::Severity> {
+        Some(self.severity)
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.help()
+    }
+
+    fn note<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.note()
+    }
+
+    fn expected<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.expected()
+    }
+
+    fn found<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.found()
+    }
+
+    fn suggestion<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.suggestion()
+    }
+
+    fn url<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.url()
+    }
+
+    fn labels<'a>(&'a self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + 'a>> {
+        self.error.labels()
+    }
+
+    fn fixes<'a>(&'a self) -> Option<Box<dyn Iterator<Item = Fix> + 'a>> {
+        self.error.fixes()
+    }
+
+    fn tags<'a>(&'a self) -> Option<Box<dyn Iterator<Item = DiagnosticTag> + 'a>> {
+        self.error.tags()
+    }
+
+    fn metadata(&self) -> Option<&DiagnosticMetadata> {
+        self.error.metadata()
+    }
+
+    fn comparison(&self) -> Option<&SourceComparison> {
+        self.error.comparison()
+    }
+
+    fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+        self.error.backtrace()
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        self.error.source_code()
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        self.error.related()
+    }
+
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        self.error.diagnostic_source()
+    }
+}
+
+impl Diagnostic for WithSeverity<Report> {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.code()
+    }
+
+    fn severity(&self) -> Option<miette::Severity> {
+        Some(self.severity)
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.help()
+    }
+
+    fn note<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.note()
+    }
+
+    fn expected<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.expected()
+    }
+
+    fn found<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.found()
+    }
+
+    fn suggestion<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.suggestion()
+    }
+
+    fn url<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.url()
+    }
+
+    fn labels<'a>(&'a self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + 'a>> {
+        self.error.labels()
+    }
+
+    fn fixes<'a>(&'a self) -> Option<Box<dyn Iterator<Item = Fix> + 'a>> {
+        self.error.fixes()
+    }
+
+    fn tags<'a>(&'a self) -> Option<Box<dyn Iterator<Item = DiagnosticTag> + 'a>> {
+        self.error.tags()
+    }
+
+    fn metadata(&self) -> Option<&DiagnosticMetadata> {
+        self.error.metadata()
+    }
+
+    fn comparison(&self) -> Option<&SourceComparison> {
+        self.error.comparison()
+    }
+
+    fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+        self.error.backtrace()
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        self.error.source_code()
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        self.error.related()
+    }
+
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        self.error.diagnostic_source()
+    }
+}
+
+impl<E: Debug> Debug for WithSeverity<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.error, f)
+    }
+}
+
+impl<E: Display> Display for WithSeverity<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl<E: StdError> StdError for WithSeverity<E> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.error.source()
+    }
+}
+
+impl StdError for WithSeverity<Report> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.error.source()
+    }
+}
+
+pub(crate) struct WithSourceMap<E> {
+    pub(crate) error: E,
+    pub(crate) source_map: SourceMap,
+}
+
+impl<E: Diagnostic> Diagnostic for WithSourceMap<E> {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.code()
+    }
+
+    fn severity(&self) -> Option<miette::Severity> {
+        self.error.severity()
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.help()
+    }
+
+    fn note<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.note()
+    }
+
+    fn expected<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.expected()
+    }
+
+    fn found<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.found()
+    }
+
+    fn suggestion<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.suggestion()
+    }
+
+    fn url<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.url()
+    }
+
+    fn labels<'a>(&'a self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + 'a>> {
+        let labels = self.error.labels()?;
+        Some(Box::new(labels.filter_map(move |label| {
+            let span = self.source_map.map_span(label.inner())?;
+            let text = label.label().map(String::from);
+            Some(if label.primary() {
+                LabeledSpan::new_primary_with_span(text, span)
+            } else {
+                LabeledSpan::new_with_span(text, span)
+            })
+        })))
+    }
+
+    fn fixes<'a>(&'a self) -> Option<Box<dyn Iterator<Item = Fix> + 'a>> {
+        let fixes = self.error.fixes()?;
+        Some(Box::new(fixes.filter_map(move |fix| {
+            let span = self.source_map.map_span(fix.span())?;
+            Some(Fix::new(span, fix.replacement()).with_applicability(fix.applicability()))
+        })))
+    }
+
+    fn tags<'a>(&'a self) -> Option<Box<dyn Iterator<Item = DiagnosticTag> + 'a>> {
+        self.error.tags()
+    }
+
+    fn metadata(&self) -> Option<&DiagnosticMetadata> {
+        self.error.metadata()
+    }
+
+    fn comparison(&self) -> Option<&SourceComparison> {
+        self.error.comparison()
+    }
+
+    fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+        self.error.backtrace()
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        Some(&self.source_map)
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        self.error.related()
+    }
+
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        self.error.diagnostic_source()
+    }
+}
+
+impl Diagnostic for WithSourceMap<Report> {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.code()
+    }
+
+    fn severity(&self) -> Option<miette::Severity> {
+        self.error.severity()
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.help()
+    }
+
+    fn note<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.note()
+    }
+
+    fn expected<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.expected()
+    }
+
+    fn found<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.found()
+    }
+
+    fn suggestion<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.suggestion()
+    }
+
+    fn url<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.error.url()
+    }
+
+    fn labels<'a>(&'a self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + 'a>> {
+        let labels = self.error.labels()?;
+        Some(Box::new(labels.filter_map(move |label| {
+            let span = self.source_map.map_span(label.inner())?;
+            let text = label.label().map(String::from);
+            Some(if label.primary() {
+                LabeledSpan::new_primary_with_span(text, span)
+            } else {
+                LabeledSpan::new_with_span(text, span)
+            })
+        })))
+    }
+
+    fn fixes<'a>(&'a self) -> Option<Box<dyn Iterator<Item = Fix> + 'a>> {
+        let fixes = self.error.fixes()?;
+        Some(Box::new(fixes.filter_map(move |fix| {
+            let span = self.source_map.map_span(fix.span())?;
+            Some(Fix::new(span, fix.replacement()).with_applicability(fix.applicability()))
+        })))
+    }
+
+    fn tags<'a>(&'a self) -> Option<Box<dyn Iterator<Item = DiagnosticTag> + 'a>> {
+        self.error.tags()
+    }
+
+    fn metadata(&self) -> Option<&DiagnosticMetadata> {
+        self.error.metadata()
+    }
+
+    fn comparison(&self) -> Option<&SourceComparison> {
+        self.error.comparison()
+    }
+
+    fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+        self.error.backtrace()
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        Some(&self.source_map)
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        self.error.related()
+    }
+
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        self.error.diagnostic_source()
+    }
+}
+
+impl<E: Debug> Debug for WithSourceMap<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.error, f)
+    }
+}
+
+impl<E: Display> Display for WithSourceMap<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl<E: StdError> StdError for WithSourceMap<E> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.error.source()
+    }
+}
+
+impl StdError for WithSourceMap<Report> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.error.source()
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    fmt,
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use crate::file_windows::FileWindows;
+use crate::{MietteError, SourceCode, SourceSpan, SpanContents};
+
+/**
+[`SourceCode`] for a file on disk, which is only read once a span is first
+read from it, and is named after its path.
+
+Constructing a `FileSource` doesn't touch the file system, so diagnostics can
+point into any number of files without reading those that are never rendered.
+Reading a span opens the file and reads just the span and its context lines,
+the same way [`StreamingSource`](crate::StreamingSource) does, except that the
+file isn't kept open between reads. If opening or reading it fails, the
+[`MietteError::IoError`] is returned, and the next read tries again.
+
+## Example
+
+```no_run
+use miette::{miette, FileSource};
+
+let report = miette!("oops!").with_source_code(FileSource::new("src/main.rs"));
+```
+*/
+pub struct FileSource {
+    path: PathBuf,
+    windows: FileWindows,
+}
+
+impl FileSource {
+    /// Create a new [`FileSource`] for the file at `path`. The file isn't
+    /// opened until a span is read from it.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            windows: FileWindows::default(),
+        }
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether any of the file has been read yet.
+    pub fn is_loaded(&self) -> bool {
+        !self.windows.is_empty()
+    }
+}
+
+impl fmt::Debug for FileSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileSource")
+            .field("path", &self.path)
+            .field("loaded", &self.is_loaded())
+            .finish()
+    }
+}
+
+impl SourceCode for FileSource {
+    fn read_span<'a>(
+        &'a self,
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        let mut file = File::open(&self.path)?;
+        self.windows.read_span(
+            self.path.display().to_string(),
+            &mut file,
+            span,
+            context_lines_before,
+            context_lines_after,
+        )
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Seek, SeekFrom},
+    sync::Mutex,
+};
+
+use crate::snippet_cache::SnippetCache;
+use crate::{MietteError, MietteSpanContents, SourceCode, SourceSpan, SpanContents};
+
+/// How much of a file is read at a time while scanning it.
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// How many lines apart the line starts remembered between reads are.
+const LINES_PER_CHECKPOINT: usize = 1024;
+
+/// Reads spans out of a file on disk a window at a time, rather than reading
+/// all of it, for the [`SourceCode`] impls that point into files by path.
+///
+/// A read scans the file up to the span to find which line it's on and where
+/// its context starts, and then reads just the span and its context lines,
+/// which are kept in a [`SnippetCache`] to be borrowed from.
+#[derive(Default)]
+pub(crate) struct FileWindows {
+    lines: Mutex<LineIndex>,
+    snippets: SnippetCache,
+}
+
+/// Where some of the lines of a file start, remembered between reads so
+/// that each one only has to scan the file from the nearest one before it
+/// rather than from the very start.
+struct LineIndex {
+    /// The starts of every [`LINES_PER_CHECKPOINT`]th line, as far as the
+    /// file has been scanned.
+    checkpoints: Vec<usize>,
+    /// How far into the file has been scanned.
+    scanned: usize,
+    /// Whether the last byte scanned was a `\r`, whose line start moves past
+    /// the `\n` if that's what comes next.
+    prev_cr: bool,
+}
+
+impl Default for LineIndex {
+    fn default() -> Self {
+        Self {
+            checkpoints: vec![0],
+            scanned: 0,
+            prev_cr: false,
+        }
+    }
+}
+
+impl FileWindows {
+    /// Reads `span` and its context lines out of `file`, naming the contents
+    /// `name`.
+    pub(crate) fn read_span<'a>(
+        &'a self,
+        name: String,
+        file: &mut (impl Read + Seek),
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        let (window, start, start_line) =
+            self.read_window(file, span, context_lines_before, context_lines_after)?;
+        let window = self.snippets.keep(start, window);
+        let relative_span = (span.offset() - start.min(span.offset()), span.len()).into();
+        let contents = <[u8] as SourceCode>::read_span(
+            window,
+            &relative_span,
+            context_lines_before,
+            context_lines_after,
+        )?;
+        Ok(Box::new(MietteSpanContents::new_named(
+            name,
+            contents.data(),
+            (start + contents.span().offset(), contents.span().len()).into(),
+            start_line + contents.line(),
+            contents.column(),
+            start_line + contents.line_count(),
+        )))
+    }
+
+    /// Whether any of the file has been read yet.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.snippets.is_empty()
+    }
+
+    /// Reads the part of `file` that a span and its context lines are in,
+    /// returning it along with its offset and the line it starts on.
+    fn read_window(
+        &self,
+        file: &mut (impl Read + Seek),
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> io::Result<(Vec<u8>, usize, usize)> {
+        let mut lines = self.lines.lock().unwrap_or_else(|err| err.into_inner());
+
+        // Find the starts of the line the span is on and the lines before it,
+        // counting `\r\n`, `\n` and a lone `\r` as line endings. The scan
+        // starts from the last line start remembered from earlier reads that's
+        // far enough back to cover the context lines before the span.
+        let nearest = lines
+            .checkpoints
+            .partition_point(|&start| start <= span.offset())
+            - 1;
+        let first = (nearest * LINES_PER_CHECKPOINT).saturating_sub(context_lines_before)
+            / LINES_PER_CHECKPOINT;
+        let mut pos = lines.checkpoints[first];
+        let mut line = first * LINES_PER_CHECKPOINT;
+        let mut prev_cr = pos == lines.scanned && lines.prev_cr;
+        let mut line_starts = VecDeque::from(vec![pos]);
+        let mut chunk = vec![0; CHUNK_LEN];
+        file.seek(SeekFrom::Start(pos as u64))?;
+        while pos < span.offset() {
+            let len = CHUNK_LEN.min(span.offset() - pos);
+            let read = file.read(&mut chunk[..len])?;
+            if read == 0 {
+                break;
+            }
+            for byte in &chunk[..read] {
+                pos += 1;
+                // Line starts past what earlier reads scanned are new, and
+                // are remembered if they start a checkpointed line.
+                let unscanned = pos > lines.scanned;
+                match byte {
+                    b'\n' if prev_cr => {
+                        *line_starts.back_mut().unwrap() = pos;
+                        if unscanned && line % LINES_PER_CHECKPOINT == 0 {
+                            *lines.checkpoints.last_mut().unwrap() = pos;
+                        }
+                    }
+                    b'\r' | b'\n' => {
+                        line += 1;
+                        line_starts.push_back(pos);
+                        if line_starts.len() > context_lines_before + 1 {
+                            line_starts.pop_front();
+                        }
+                        if unscanned && line % LINES_PER_CHECKPOINT == 0 {
+                            lines.checkpoints.push(pos);
+                        }
+                    }
+                    _ => {}
+                }
+                prev_cr = *byte == b'\r';
+            }
+        }
+        if pos > lines.scanned {
+            lines.scanned = pos;
+            lines.prev_cr = prev_cr;
+        }
+        let mut start = line_starts[0];
+        let mut start_line = line - (line_starts.len() - 1);
+        if context_lines_before == 0 && start > 0 {
+            // Include the line ending before the span's line, so that empty
+            // spans at the start of a line are read the same as they would be
+            // from memory.
+            start -= 1;
+            start_line -= 1;
+        }
+
+        // Then read from there until a couple of lines past the context after
+        // the span, which is trimmed down to exactly what was asked for later.
+        file.seek(SeekFrom::Start(start as u64))?;
+        let last = span.offset() + span.len().saturating_sub(1);
+        let mut window = Vec::new();
+        let mut line_endings = 0;
+        let mut prev_cr = false;
+        'read: loop {
+            let read = file.read(&mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            for (idx, byte) in chunk[..read].iter().enumerate() {
+                let pos = start + window.len() + idx;
+                let ends_line = *byte == b'\r' || (*byte == b'\n' && !prev_cr);
+                prev_cr = *byte == b'\r';
+                if ends_line && pos >= last {
+                    line_endings += 1;
+                    if line_endings > context_lines_after + 1 {
+                        window.extend_from_slice(&chunk[..=idx]);
+                        break 'read;
+                    }
+                }
+            }
+            window.extend_from_slice(&chunk[..read]);
+        }
+        Ok((window, start, start_line))
+    }
+}
@@ -2,13 +2,17 @@ use std::fmt;
 
 use atty::Stream;
 
+use crate::highlighters::Highlighter;
+use crate::highlighters::MietteHighlighter;
 use crate::protocol::Diagnostic;
 use crate::GraphicalReportHandler;
 use crate::GraphicalTheme;
 use crate::NarratableReportHandler;
+use crate::QuietReportHandler;
 use crate::ReportHandler;
 use crate::ThemeCharacters;
 use crate::ThemeStyles;
+use crate::VerboseReportHandler;
 
 /// Settings to control the color format used for graphical rendering.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -50,6 +54,8 @@ pub struct MietteHandlerOpts {
     pub(crate) theme: Option<GraphicalTheme>,
     pub(crate) force_graphical: Option<bool>,
     pub(crate) force_narrated: Option<bool>,
+    pub(crate) quiet: Option<bool>,
+    pub(crate) verbose: Option<bool>,
     pub(crate) rgb_colors: RgbColors,
     pub(crate) color: Option<bool>,
     pub(crate) unicode: Option<bool>,
@@ -57,6 +63,10 @@ pub struct MietteHandlerOpts {
     pub(crate) context_lines: Option<usize>,
     pub(crate) tab_width: Option<usize>,
     pub(crate) with_cause_chain: Option<bool>,
+    pub(crate) highlighter: Option<MietteHighlighter>,
+    pub(crate) docs_url: Option<String>,
+    pub(crate) zero_based_line_numbers: Option<bool>,
+    pub(crate) max_snippet_lines: Option<usize>,
 }
 
 impl MietteHandlerOpts {
@@ -73,6 +83,35 @@ impl MietteHandlerOpts {
         self
     }
 
+    /// Sets a URL template used to link diagnostic codes that don't have a
+    /// [`Diagnostic::url()`](crate::Diagnostic::url) of their own. Every
+    /// `{code}` in the template is replaced with the diagnostic's code.
+    pub fn docs_url(mut self, template: impl Into<String>) -> Self {
+        self.docs_url = Some(template.into());
+        self
+    }
+
+    /// If true, line and column numbers are displayed starting from 0 instead
+    /// of 1.
+    ///
+    /// This only affects the [`GraphicalReportHandler`] and
+    /// [`NarratableReportHandler`] that this builds. The other handlers, such
+    /// as [`RustcReportHandler`](crate::RustcReportHandler) and
+    /// [`SarifReportHandler`](crate::SarifReportHandler), always use 1-based
+    /// numbers, since that's what the formats they write expect.
+    pub fn zero_based_line_numbers(mut self, zero_based: bool) -> Self {
+        self.zero_based_line_numbers = Some(zero_based);
+        self
+    }
+
+    /// Sets the maximum number of lines to render for a single snippet in
+    /// graphical mode. Longer snippets are cut down to their first and last
+    /// lines.
+    pub fn max_snippet_lines(mut self, lines: usize) -> Self {
+        self.max_snippet_lines = Some(lines);
+        self
+    }
+
     /// Set a graphical theme for the handler when rendering in graphical mode.
     /// Use [`force_graphical()`](`MietteHandlerOpts::force_graphical) to force
     /// graphical mode. This option overrides
@@ -105,7 +144,10 @@ impl MietteHandlerOpts {
     ///
     /// If false, colors will never be used.
     ///
-    /// If unspecified, colors will be used only if the terminal supports them.
+    /// If unspecified, colors will be used only if the terminal supports them,
+    /// following the [`NO_COLOR`](https://no-color.org/) and
+    /// [`CLICOLOR`/`CLICOLOR_FORCE`](https://bixense.com/clicolors/)
+    /// conventions.
     ///
     /// The actual format depends on the value of
     /// [`MietteHandlerOpts::rgb_colors`].
@@ -148,6 +190,22 @@ impl MietteHandlerOpts {
         self
     }
 
+    /// If true, only the severity, code and message of each diagnostic are
+    /// rendered, using a [`QuietReportHandler`]. This takes precedence over
+    /// all other rendering options.
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.quiet = Some(quiet);
+        self
+    }
+
+    /// If true, everything the [`Diagnostic`] protocol exposes is rendered,
+    /// using a [`VerboseReportHandler`]. This takes precedence over all other
+    /// rendering options except [`MietteHandlerOpts::quiet`].
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = Some(verbose);
+        self
+    }
+
     /// Set a footer to be displayed at the bottom of the report.
     pub fn footer(mut self, footer: String) -> Self {
         self.footer = Some(footer);
@@ -166,8 +224,39 @@ impl MietteHandlerOpts {
         self
     }
 
+    /// Set a syntax highlighter for source code snippets. See the
+    /// [`highlighters`](crate::highlighters) module for more details.
+    ///
+    /// With the `syntect-highlighter` feature enabled,
+    /// [`SyntectHighlighter`](crate::highlighters::SyntectHighlighter) is
+    /// used by default. Snippets are never highlighted when colors are
+    /// disabled.
+    pub fn with_syntax_highlighting(
+        mut self,
+        highlighter: impl Highlighter + Send + Sync + 'static,
+    ) -> Self {
+        self.highlighter = Some(MietteHighlighter::from(highlighter));
+        self
+    }
+
+    /// Disable syntax highlighting of source code snippets.
+    pub fn without_syntax_highlighting(mut self) -> Self {
+        self.highlighter = Some(MietteHighlighter::nocolor());
+        self
+    }
+
     /// Builds a [`MietteHandler`] from this builder.
     pub fn build(self) -> MietteHandler {
+        if self.quiet == Some(true) {
+            return MietteHandler {
+                inner: Box::new(QuietReportHandler::new()),
+            };
+        }
+        if self.verbose == Some(true) {
+            return MietteHandler {
+                inner: Box::new(VerboseReportHandler::new()),
+            };
+        }
         let graphical = self.is_graphical();
         let width = self.get_width();
         if !graphical {
@@ -178,6 +267,9 @@ impl MietteHandlerOpts {
             if let Some(context_lines) = self.context_lines {
                 handler = handler.with_context_lines(context_lines);
             }
+            if let Some(zero_based) = self.zero_based_line_numbers {
+                handler = handler.with_zero_based_line_numbers(zero_based);
+            }
             if let Some(with_cause_chain) = self.with_cause_chain {
                 if with_cause_chain {
                     handler = handler.with_cause_chain();
@@ -196,27 +288,35 @@ impl MietteHandlerOpts {
                 None if supports_unicode::on(Stream::Stderr) => ThemeCharacters::unicode(),
                 None => ThemeCharacters::ascii(),
             };
-            let styles = if self.color == Some(false) {
-                ThemeStyles::none()
+            let color = self.use_color();
+            let styles = if color == Some(false) {
+                None
             } else if let Some(color) = supports_color::on(Stream::Stderr) {
                 match self.rgb_colors {
-                    RgbColors::Always => ThemeStyles::rgb(),
-                    RgbColors::Preferred if color.has_16m => ThemeStyles::rgb(),
-                    _ => ThemeStyles::ansi(),
+                    RgbColors::Always => Some(ThemeStyles::rgb()),
+                    RgbColors::Preferred if color.has_16m => Some(ThemeStyles::rgb()),
+                    _ => Some(ThemeStyles::ansi()),
                 }
-            } else if self.color == Some(true) {
+            } else if color == Some(true) {
                 match self.rgb_colors {
-                    RgbColors::Always => ThemeStyles::rgb(),
-                    _ => ThemeStyles::ansi(),
+                    RgbColors::Always => Some(ThemeStyles::rgb()),
+                    _ => Some(ThemeStyles::ansi()),
                 }
             } else {
-                ThemeStyles::none()
+                None
             };
+            let highlighter = if styles.is_some() {
+                self.highlighter.unwrap_or_default()
+            } else {
+                MietteHighlighter::nocolor()
+            };
+            let styles = styles.unwrap_or_else(ThemeStyles::none);
             let theme = self.theme.unwrap_or(GraphicalTheme { characters, styles });
             let mut handler = GraphicalReportHandler::new()
                 .with_width(width)
                 .with_links(linkify)
                 .with_theme(theme);
+            handler.highlighter = highlighter;
             if let Some(with_cause_chain) = self.with_cause_chain {
                 if with_cause_chain {
                     handler = handler.with_cause_chain();
@@ -233,6 +333,15 @@ impl MietteHandlerOpts {
             if let Some(w) = self.tab_width {
                 handler = handler.tab_width(w);
             }
+            if let Some(docs_url) = self.docs_url {
+                handler = handler.with_docs_url(docs_url);
+            }
+            if let Some(zero_based) = self.zero_based_line_numbers {
+                handler = handler.with_zero_based_line_numbers(zero_based);
+            }
+            if let Some(max_snippet_lines) = self.max_snippet_lines {
+                handler = handler.with_max_snippet_lines(max_snippet_lines);
+            }
             MietteHandler {
                 inner: Box::new(handler),
             }
@@ -251,6 +360,12 @@ impl MietteHandlerOpts {
         }
     }
 
+    // Returns whether colors were explicitly enabled or disabled, either
+    // through `color()` or through the environment.
+    pub(crate) fn use_color(&self) -> Option<bool> {
+        self.color.or_else(env_color)
+    }
+
     // Detects known terminal apps based on env variables and returns true if
     // they support rendering links.
     pub(crate) fn use_links(&self) -> bool {
@@ -280,6 +395,31 @@ impl MietteHandlerOpts {
     }
 }
 
+/// Checks the `NO_COLOR`, `FORCE_COLOR`, `CLICOLOR_FORCE` and `CLICOLOR`
+/// environment variables, in that order, and returns whether they ask for
+/// colors to be enabled or disabled.
+pub(crate) fn env_color() -> Option<bool> {
+    let var = |name| std::env::var(name).ok();
+    match var("NO_COLOR") {
+        Some(no_color) if !no_color.is_empty() && no_color != "0" => return Some(false),
+        _ => {}
+    }
+    // Either one forces colors on, and setting one to `0` is the same as
+    // leaving it unset rather than a request to turn colors off.
+    for force in ["FORCE_COLOR", "CLICOLOR_FORCE"]
+        .iter()
+        .filter_map(|name| var(name))
+    {
+        if force != "0" && force != "false" {
+            return Some(true);
+        }
+    }
+    match var("CLICOLOR") {
+        Some(clicolor) if clicolor == "0" => Some(false),
+        _ => None,
+    }
+}
+
 /**
 A [`ReportHandler`] that displays a given [`Report`](crate::Report) in a
 quasi-graphical way, using terminal colors, unicode drawing characters, and
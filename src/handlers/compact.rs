@@ -0,0 +1,110 @@
+use std::fmt;
+
+use crate::{
+    handlers::{primary_label, Location},
+    protocol::Diagnostic,
+    ReportHandler, Severity, SourceCode, SourceSpan,
+};
+
+/**
+[`ReportHandler`] that renders each diagnostic as a single
+`severity[code] file:line:col: message` line, with no snippets, for use in
+dense logs and editor integrations where full snippet rendering is too noisy.
+
+The location is taken from the primary label. Lines and columns are
+1-based, and columns count characters rather than bytes. Related diagnostics
+are rendered on lines of their own.
+*/
+#[derive(Debug, Clone)]
+pub struct CompactReportHandler;
+
+impl CompactReportHandler {
+    /// Create a new [`CompactReportHandler`].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CompactReportHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompactReportHandler {
+    /// Render a [`Diagnostic`]. This function is mostly internal and meant to
+    /// be called by the toplevel [`ReportHandler`] handler, but is made public
+    /// to make it easier (possible) to test in isolation from global state.
+    pub fn render_report(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+    ) -> fmt::Result {
+        self.render_diagnostic(f, diagnostic, None)
+    }
+
+    fn render_diagnostic(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+        parent_src: Option<&dyn SourceCode>,
+    ) -> fmt::Result {
+        let severity = match diagnostic.severity() {
+            Some(Severity::Bug) => "bug",
+            Some(Severity::Error) | None => "error",
+            Some(Severity::Warning) => "warning",
+            Some(Severity::Advice) => "advice",
+            Some(Severity::Info) => "info",
+        };
+        write!(f, "{}", severity)?;
+        if let Some(code) = diagnostic.code() {
+            write!(f, "[{}]", code)?;
+        }
+        let src = diagnostic.source_code().or(parent_src);
+        let span = primary_label(diagnostic).map(|label| *label.inner());
+        if let (Some(src), Some(span)) = (src, span) {
+            self.render_location(f, src, &span)?;
+        }
+        write!(f, ": ")?;
+        let message = diagnostic.to_string();
+        let mut lines = message.lines();
+        write!(f, "{}", lines.next().unwrap_or_default())?;
+        for line in lines {
+            write!(f, " {}", line.trim_start())?;
+        }
+        if let Some(related) = diagnostic.related() {
+            for rel in related {
+                writeln!(f)?;
+                self.render_diagnostic(f, rel, src)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn render_location(
+        &self,
+        f: &mut impl fmt::Write,
+        source: &dyn SourceCode,
+        span: &SourceSpan,
+    ) -> fmt::Result {
+        let location = match Location::new(source, span) {
+            Some(location) => location,
+            None => return Ok(()),
+        };
+        write!(f, " ")?;
+        if let Some(name) = &location.name {
+            write!(f, "{}:", name)?;
+        }
+        write!(f, "{}:{}", location.line, location.column)
+    }
+}
+
+impl ReportHandler for CompactReportHandler {
+    fn debug(&self, diagnostic: &(dyn Diagnostic), f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return fmt::Debug::fmt(diagnostic, f);
+        }
+
+        self.render_report(f, diagnostic)
+    }
+}
@@ -44,6 +44,9 @@ impl DebugReportHandler {
         if let Some(url) = diagnostic.url() {
             diag.field("url", &url.to_string());
         }
+        if let Some(note) = diagnostic.note() {
+            diag.field("note", &note.to_string());
+        }
         if let Some(help) = diagnostic.help() {
             diag.field("help", &help.to_string());
         }
@@ -1,12 +1,20 @@
+use std::collections::HashMap;
 use std::fmt::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 
 use owo_colors::{OwoColorize, Style};
-use unicode_width::UnicodeWidthChar;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
-use crate::diagnostic_chain::DiagnosticChain;
 use crate::handlers::theme::*;
+use crate::handlers::{cause_messages, find_backtrace, grapheme_width, lossy_chars, primary_label};
+use crate::highlighters::{Highlighter, HighlighterState, MietteHighlighter};
 use crate::protocol::{Diagnostic, Severity};
-use crate::{LabeledSpan, MietteError, ReportHandler, SourceCode, SourceSpan, SpanContents};
+use crate::{
+    ComparisonLayout, DiagnosticTag, Fix, LabeledSpan, MietteError, ReportHandler, SourceCode,
+    SourceSpan, SpanContents,
+};
 
 /**
 A [`ReportHandler`] that displays a given [`Report`](crate::Report) in a
@@ -30,6 +38,19 @@ pub struct GraphicalReportHandler {
     pub(crate) context_lines: usize,
     pub(crate) tab_width: usize,
     pub(crate) with_cause_chain: bool,
+    pub(crate) highlighter: MietteHighlighter,
+    pub(crate) docs_url: Option<String>,
+    pub(crate) file_links: bool,
+    pub(crate) zero_based_line_numbers: bool,
+    pub(crate) max_snippet_lines: Option<usize>,
+    /// Name of the source that snippet headers can leave out, because it's
+    /// already in a heading of [`render_grouped_reports`] output.
+    ///
+    /// [`render_grouped_reports`]: GraphicalReportHandler::render_grouped_reports
+    grouped_source: Option<String>,
+    /// The `file://` URLs of the sources named so far in the current render,
+    /// if file links are enabled and a render is underway.
+    file_urls: Option<FileUrls>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -51,6 +72,13 @@ impl GraphicalReportHandler {
             context_lines: 1,
             tab_width: 4,
             with_cause_chain: true,
+            highlighter: MietteHighlighter::nocolor(),
+            docs_url: None,
+            file_links: false,
+            zero_based_line_numbers: false,
+            max_snippet_lines: None,
+            grouped_source: None,
+            file_urls: None,
         }
     }
 
@@ -64,6 +92,13 @@ impl GraphicalReportHandler {
             context_lines: 1,
             tab_width: 4,
             with_cause_chain: true,
+            highlighter: MietteHighlighter::nocolor(),
+            docs_url: None,
+            file_links: false,
+            zero_based_line_numbers: false,
+            max_snippet_lines: None,
+            grouped_source: None,
+            file_urls: None,
         }
     }
 
@@ -97,6 +132,25 @@ impl GraphicalReportHandler {
         self
     }
 
+    /// Sets a URL template used to link diagnostic codes that don't provide
+    /// their own [`Diagnostic::url()`]. Every `{code}` in the template is
+    /// replaced with the diagnostic's code, for example
+    /// `"https://example.com/errors/{code}"`.
+    pub fn with_docs_url(mut self, template: impl Into<String>) -> Self {
+        self.docs_url = Some(template.into());
+        self
+    }
+
+    /// Whether to render the names of snippet sources that exist on disk as
+    /// `file://` links. This only has an effect when terminal links are
+    /// enabled with [`with_links`](GraphicalReportHandler::with_links), and
+    /// defaults to `false`, since it checks the file system for every source
+    /// name it renders.
+    pub fn with_file_links(mut self, file_links: bool) -> Self {
+        self.file_links = file_links;
+        self
+    }
+
     /// Whether to include [`Diagnostic::url()`] in the output.
     ///
     /// Disabling this is not recommended, but can be useful for more easily
@@ -133,6 +187,39 @@ impl GraphicalReportHandler {
         self.context_lines = lines;
         self
     }
+
+    /// Whether to display line and column numbers starting from 0 instead of
+    /// 1. Defaults to `false`.
+    pub fn with_zero_based_line_numbers(mut self, zero_based: bool) -> Self {
+        self.zero_based_line_numbers = zero_based;
+        self
+    }
+
+    /// Sets the maximum number of lines to render for a single snippet. Longer
+    /// snippets only show their first and last lines, with a marker in between
+    /// saying how many lines were left out. Defaults to no limit.
+    pub fn with_max_snippet_lines(mut self, lines: usize) -> Self {
+        self.max_snippet_lines = Some(lines);
+        self
+    }
+
+    /// Enable syntax highlighting of source code snippets using the given
+    /// [`Highlighter`]. See the [`highlighters`](crate::highlighters) module
+    /// for more details.
+    pub fn with_syntax_highlighting(
+        mut self,
+        highlighter: impl Highlighter + Send + Sync + 'static,
+    ) -> Self {
+        self.highlighter = MietteHighlighter::from(highlighter);
+        self
+    }
+
+    /// Disable syntax highlighting of source code snippets. This is the
+    /// default.
+    pub fn without_syntax_highlighting(mut self) -> Self {
+        self.highlighter = MietteHighlighter::nocolor();
+        self
+    }
 }
 
 impl Default for GraphicalReportHandler {
@@ -150,12 +237,18 @@ impl GraphicalReportHandler {
         f: &mut impl fmt::Write,
         diagnostic: &(dyn Diagnostic),
     ) -> fmt::Result {
+        if self.file_links && self.file_urls.is_none() {
+            return self.with_file_urls().render_report(f, diagnostic);
+        }
         self.render_header(f, diagnostic)?;
         writeln!(f)?;
         self.render_causes(f, diagnostic)?;
         let src = diagnostic.source_code();
         self.render_snippets(f, diagnostic, src)?;
+        self.render_comparison(f, diagnostic)?;
+        self.render_fixes(f, diagnostic, src)?;
         self.render_footer(f, diagnostic)?;
+        self.render_backtrace(f, diagnostic)?;
         self.render_related(f, diagnostic, src)?;
         if let Some(footer) = &self.footer {
             writeln!(f)?;
@@ -168,44 +261,133 @@ impl GraphicalReportHandler {
         Ok(())
     }
 
+    /// Render any number of [`Diagnostic`]s, grouped by the name of the
+    /// source their primary label points into. Each group gets a single
+    /// heading with the source name, which the snippet headers of its
+    /// diagnostics then leave out. Diagnostics without a named source are
+    /// rendered as usual.
+    pub fn render_grouped_reports<'a>(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostics: impl IntoIterator<Item = &'a (dyn Diagnostic + 'a)>,
+    ) -> fmt::Result {
+        if self.file_links && self.file_urls.is_none() {
+            return self.with_file_urls().render_grouped_reports(f, diagnostics);
+        }
+        let mut groups: Vec<(Option<String>, Vec<&dyn Diagnostic>)> = Vec::new();
+        for diagnostic in diagnostics {
+            let name = primary_label(diagnostic).and_then(|label| {
+                let contents = diagnostic
+                    .source_code()?
+                    .read_span(label.inner(), 0, 0)
+                    .ok()?;
+                contents.name().map(String::from)
+            });
+            match groups.iter_mut().find(|(group, _)| *group == name) {
+                Some((_, group)) => group.push(diagnostic),
+                None => groups.push((name, vec![diagnostic])),
+            }
+        }
+
+        // Everything gets separated by exactly one blank line, no matter
+        // whether a report starts with one of its own.
+        let mut first = true;
+        for (name, diagnostics) in groups {
+            let grouped;
+            let handler = match name {
+                Some(name) => {
+                    if !first {
+                        writeln!(f)?;
+                    }
+                    first = false;
+                    writeln!(
+                        f,
+                        "{}{}[{}]",
+                        self.theme.characters.ltop,
+                        self.theme.characters.hbar,
+                        self.source_name(&name)
+                    )?;
+                    grouped = Self {
+                        grouped_source: Some(name),
+                        ..self.clone()
+                    };
+                    &grouped
+                }
+                None => self,
+            };
+            for diagnostic in diagnostics {
+                let mut report = String::new();
+                handler.render_report(&mut report, diagnostic)?;
+                if !first {
+                    writeln!(f)?;
+                }
+                first = false;
+                write!(f, "{}", report.trim_start_matches('\n'))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of this handler for a single render, which looks up the
+    /// `file://` URL of each source name it renders only once. Nothing is
+    /// kept between renders, so files that have since been created, moved or
+    /// removed are linked accordingly.
+    fn with_file_urls(&self) -> Self {
+        Self {
+            file_urls: Some(FileUrls::default()),
+            ..self.clone()
+        }
+    }
+
     fn render_header(&self, f: &mut impl fmt::Write, diagnostic: &(dyn Diagnostic)) -> fmt::Result {
         let severity_style = match diagnostic.severity() {
+            Some(Severity::Bug) => self.theme.styles.bug,
             Some(Severity::Error) | None => self.theme.styles.error,
             Some(Severity::Warning) => self.theme.styles.warning,
             Some(Severity::Advice) => self.theme.styles.advice,
+            Some(Severity::Info) => self.theme.styles.info,
         };
+        let url = diagnostic.url().map(|url| url.to_string()).or_else(|| {
+            let code = diagnostic.code()?.to_string();
+            Some(self.docs_url.as_ref()?.replace("{code}", &code))
+        });
         let mut header = String::new();
-        if self.links == LinkStyle::Link && diagnostic.url().is_some() {
-            let url = diagnostic.url().unwrap(); // safe
-            let code = if let Some(code) = diagnostic.code() {
-                format!("{} ", code)
-            } else {
-                "".to_string()
-            };
-            let link = format!(
-                "\u{1b}]8;;{}\u{1b}\\{}{}\u{1b}]8;;\u{1b}\\",
-                url,
-                code.style(severity_style),
-                "(link)".style(self.theme.styles.link)
-            );
-            write!(header, "{}", link)?;
-            writeln!(f, "{}", header)?;
-        } else if let Some(code) = diagnostic.code() {
-            write!(header, "{}", code.style(severity_style),)?;
-            if self.links == LinkStyle::Text && diagnostic.url().is_some() {
-                let url = diagnostic.url().unwrap(); // safe
-                write!(header, " ({})", url.style(self.theme.styles.link))?;
+        match (self.links, url) {
+            (LinkStyle::Link, Some(url)) => {
+                let code = if let Some(code) = diagnostic.code() {
+                    format!("{} ", code)
+                } else {
+                    "".to_string()
+                };
+                let link = format!(
+                    "\u{1b}]8;;{}\u{1b}\\{}{}\u{1b}]8;;\u{1b}\\",
+                    url,
+                    code.style(severity_style),
+                    "(link)".style(self.theme.styles.link)
+                );
+                write!(header, "{}", link)?;
+                writeln!(f, "{}", header)?;
+            }
+            (links, url) => {
+                if let Some(code) = diagnostic.code() {
+                    write!(header, "{}", code.style(severity_style),)?;
+                    if let (LinkStyle::Text, Some(url)) = (links, url) {
+                        write!(header, " ({})", url.style(self.theme.styles.link))?;
+                    }
+                    writeln!(f, "{}", header)?;
+                }
             }
-            writeln!(f, "{}", header)?;
         }
         Ok(())
     }
 
     fn render_causes(&self, f: &mut impl fmt::Write, diagnostic: &(dyn Diagnostic)) -> fmt::Result {
         let (severity_style, severity_icon) = match diagnostic.severity() {
+            Some(Severity::Bug) => (self.theme.styles.bug, &self.theme.characters.bug),
             Some(Severity::Error) | None => (self.theme.styles.error, &self.theme.characters.error),
             Some(Severity::Warning) => (self.theme.styles.warning, &self.theme.characters.warning),
             Some(Severity::Advice) => (self.theme.styles.advice, &self.theme.characters.advice),
+            Some(Severity::Info) => (self.theme.styles.info, &self.theme.characters.info),
         };
 
         let initial_indent = format!("  {} ", severity_icon.style(severity_style));
@@ -221,54 +403,109 @@ impl GraphicalReportHandler {
             return Ok(());
         }
 
-        if let Some(mut cause_iter) = diagnostic
-            .diagnostic_source()
-            .map(DiagnosticChain::from_diagnostic)
-            .or_else(|| diagnostic.source().map(DiagnosticChain::from_stderror))
-            .map(|it| it.peekable())
-        {
-            while let Some(error) = cause_iter.next() {
-                let is_last = cause_iter.peek().is_none();
-                let char = if !is_last {
-                    self.theme.characters.lcross
+        let causes = cause_messages(diagnostic);
+        let mut cause_iter = causes.iter().peekable();
+        while let Some(error) = cause_iter.next() {
+            let is_last = cause_iter.peek().is_none();
+            let char = if !is_last {
+                self.theme.characters.lcross
+            } else {
+                self.theme.characters.lbot
+            };
+            let initial_indent = format!(
+                "  {}{}{} ",
+                char, self.theme.characters.hbar, self.theme.characters.rarrow
+            )
+            .style(severity_style)
+            .to_string();
+            let rest_indent = format!(
+                "  {}   ",
+                if is_last {
+                    ' '
                 } else {
-                    self.theme.characters.lbot
-                };
-                let initial_indent = format!(
-                    "  {}{}{} ",
-                    char, self.theme.characters.hbar, self.theme.characters.rarrow
-                )
-                .style(severity_style)
-                .to_string();
-                let rest_indent = format!(
-                    "  {}   ",
-                    if is_last {
-                        ' '
-                    } else {
-                        self.theme.characters.vbar
-                    }
-                )
-                .style(severity_style)
-                .to_string();
-                let opts = textwrap::Options::new(width)
-                    .initial_indent(&initial_indent)
-                    .subsequent_indent(&rest_indent);
-                writeln!(f, "{}", textwrap::fill(&error.to_string(), opts))?;
-            }
+                    self.theme.characters.vbar
+                }
+            )
+            .style(severity_style)
+            .to_string();
+            let opts = textwrap::Options::new(width)
+                .initial_indent(&initial_indent)
+                .subsequent_indent(&rest_indent);
+            writeln!(f, "{}", textwrap::fill(error, opts))?;
         }
 
         Ok(())
     }
 
     fn render_footer(&self, f: &mut impl fmt::Write, diagnostic: &(dyn Diagnostic)) -> fmt::Result {
+        let width = self.termwidth.saturating_sub(4);
+        self.render_expected_found(f, diagnostic)?;
+        if let Some(note) = diagnostic.note() {
+            let initial_indent = "  note: ".style(self.theme.styles.note).to_string();
+            let opts = textwrap::Options::new(width)
+                .initial_indent(&initial_indent)
+                .subsequent_indent("        ");
+            writeln!(f, "{}", textwrap::fill(&note.to_string(), opts))?;
+        }
         if let Some(help) = diagnostic.help() {
-            let width = self.termwidth.saturating_sub(4);
             let initial_indent = "  help: ".style(self.theme.styles.help).to_string();
             let opts = textwrap::Options::new(width)
                 .initial_indent(&initial_indent)
                 .subsequent_indent("        ");
             writeln!(f, "{}", textwrap::fill(&help.to_string(), opts))?;
         }
+        if let Some(suggestion) = diagnostic.suggestion() {
+            writeln!(
+                f,
+                "{} did you mean `{}`?",
+                "  help:".style(self.theme.styles.help),
+                suggestion.style(self.theme.styles.suggestion)
+            )?;
+        }
+        Ok(())
+    }
+
+    fn render_expected_found(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+    ) -> fmt::Result {
+        // Right-align the titles so that both values start in the same column,
+        // and indent any further lines of a value to that column too.
+        let rows = [
+            ("expected:", diagnostic.expected()),
+            ("   found:", diagnostic.found()),
+        ];
+        for (title, value) in rows.iter() {
+            if let Some(value) = value {
+                let value = value.to_string();
+                let mut lines = value.lines();
+                writeln!(
+                    f,
+                    "  {} {}",
+                    title.style(self.theme.styles.note),
+                    lines.next().unwrap_or("")
+                )?;
+                for line in lines {
+                    writeln!(f, "{}{}", " ".repeat(title.len() + 3), line)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn render_backtrace(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+    ) -> fmt::Result {
+        if let Some(backtrace) = find_backtrace(diagnostic) {
+            writeln!(f)?;
+            writeln!(f, "  {}", "backtrace:".style(self.theme.styles.note))?;
+            for line in backtrace.to_string().lines() {
+                writeln!(f, "  {}", line.style(self.theme.styles.linum))?;
+            }
+        }
         Ok(())
     }
 
@@ -281,16 +518,19 @@ impl GraphicalReportHandler {
         if let Some(related) = diagnostic.related() {
             writeln!(f)?;
             for rel in related {
-                match diagnostic.severity() {
+                match rel.severity() {
+                    Some(Severity::Bug) => write!(f, "Bug: ")?,
                     Some(Severity::Error) | None => write!(f, "Error: ")?,
                     Some(Severity::Warning) => write!(f, "Warning: ")?,
                     Some(Severity::Advice) => write!(f, "Advice: ")?,
+                    Some(Severity::Info) => write!(f, "Info: ")?,
                 };
                 self.render_header(f, rel)?;
                 writeln!(f)?;
                 self.render_causes(f, rel)?;
                 let src = rel.source_code().or(parent_src);
                 self.render_snippets(f, rel, src)?;
+                self.render_fixes(f, rel, src)?;
                 self.render_footer(f, rel)?;
                 self.render_related(f, rel, src)?;
             }
@@ -306,7 +546,7 @@ impl GraphicalReportHandler {
     ) -> fmt::Result {
         if let Some(source) = opt_source {
             if let Some(labels) = diagnostic.labels() {
-                let mut labels = labels.collect::<Vec<_>>();
+                let mut labels = self.style_tagged_labels(diagnostic, labels.collect());
                 labels.sort_unstable_by_key(|l| l.inner().offset());
                 if !labels.is_empty() {
                     let contents = labels
@@ -367,6 +607,392 @@ impl GraphicalReportHandler {
         Ok(())
     }
 
+    fn render_fixes(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+        opt_source: Option<&dyn SourceCode>,
+    ) -> fmt::Result {
+        if let (Some(source), Some(fixes)) = (opt_source, diagnostic.fixes()) {
+            for fix in fixes {
+                self.render_fix(f, source, &fix)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders a fix as a diff of the lines it touches: the lines as they
+    /// are now, each marked with a `-`, followed by the lines as they would
+    /// be with the fix applied, each marked with a `+`.
+    fn render_fix(
+        &self,
+        f: &mut impl fmt::Write,
+        source: &dyn SourceCode,
+        fix: &Fix,
+    ) -> fmt::Result {
+        // Reading with a line of context on either side gets us whole lines,
+        // which are then trimmed down to the ones the fix touches. A fix whose
+        // span can't be read is left out rather than failing the whole report.
+        let contents = match source.read_span(fix.span(), 1, 1) {
+            Ok(contents) => contents,
+            Err(_) => return Ok(()),
+        };
+        let data = contents.data();
+        let start = match fix.span().offset().checked_sub(contents.span().offset()) {
+            Some(start) if start <= data.len() => start,
+            _ => return Ok(()),
+        };
+        let end = (start + fix.span().len()).min(data.len());
+        let line_start = data[..start]
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map_or(0, |idx| idx + 1);
+        let line_end = data[end..]
+            .iter()
+            .position(|b| *b == b'\n')
+            .map_or(data.len(), |idx| end + idx);
+        let old = String::from_utf8_lossy(&data[line_start..line_end]);
+        let new = format!(
+            "{}{}{}",
+            String::from_utf8_lossy(&data[line_start..start]),
+            fix.replacement(),
+            String::from_utf8_lossy(&data[end..line_end])
+        );
+        let old_lines = old.lines().collect::<Vec<_>>();
+        let new_lines = new.lines().collect::<Vec<_>>();
+
+        let first_line =
+            contents.line() + data[..line_start].iter().filter(|b| **b == b'\n').count() + 1;
+        let line_count = old_lines.len().max(new_lines.len()).max(1);
+        let linum_width = self
+            .display_number(first_line + line_count - 1)
+            .to_string()
+            .len();
+
+        write!(
+            f,
+            "{}{}{}",
+            " ".repeat(linum_width + 2),
+            self.theme.characters.ltop,
+            self.theme.characters.hbar,
+        )?;
+        let name = contents
+            .name()
+            .filter(|name| self.grouped_source.as_deref() != Some(*name));
+        if let Some(name) = name {
+            write!(f, "[{}:", self.source_name(name))?;
+        } else {
+            write!(f, "[")?;
+        }
+        let column = source
+            .visual_column(fix.span(), self.tab_width)
+            .unwrap_or_else(|_| {
+                String::from_utf8_lossy(&data[line_start..start])
+                    .chars()
+                    .count()
+            });
+        writeln!(
+            f,
+            "{}:{}]",
+            self.display_number(first_line),
+            self.display_number(column + 1)
+        )?;
+
+        for (marker, style, lines) in [
+            ('-', self.theme.styles.removed, &old_lines),
+            ('+', self.theme.styles.added, &new_lines),
+        ] {
+            for (idx, line) in lines.iter().enumerate() {
+                self.write_linum(f, linum_width, first_line + idx)?;
+                let text = format!("{} {}", marker, self.expand_tabs(line));
+                writeln!(f, "{}", text.trim_end().style(style))?;
+            }
+        }
+
+        writeln!(
+            f,
+            "{}{}{}",
+            " ".repeat(linum_width + 2),
+            self.theme.characters.lbot,
+            self.theme.characters.hbar.to_string().repeat(4),
+        )?;
+        Ok(())
+    }
+
+    /// Returns the column a snippet starts at, as an editor would display it,
+    /// with tabs as wide as [`GraphicalReportHandler::tab_width`] makes them.
+    fn header_column(&self, source: &dyn SourceCode, contents: &dyn SpanContents<'_>) -> usize {
+        if contents.column() == 0 {
+            return 0;
+        }
+        let start = (contents.span().offset(), 0).into();
+        source
+            .visual_column(&start, self.tab_width)
+            .unwrap_or_else(|_| contents.column())
+    }
+
+    /// Renders a diagnostic's [`SourceComparison`](crate::SourceComparison),
+    /// with a snippet of each source for every pair of spans in it, both spans
+    /// highlighted in the same style.
+    fn render_comparison(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+    ) -> fmt::Result {
+        let comparison = match diagnostic.comparison() {
+            Some(comparison) => comparison,
+            None => return Ok(()),
+        };
+        let styles = self.theme.styles.highlights.iter().cloned().cycle();
+        for (spans, style) in comparison.spans().iter().zip(styles) {
+            let old = self.compared_snippet(comparison.old_source(), spans.old_span())?;
+            let new = self.compared_snippet(comparison.new_source(), spans.new_span())?;
+            let linum_width = old
+                .last_line_number()
+                .max(new.last_line_number())
+                .to_string()
+                .len();
+            let footer = Cell::new(
+                format!(
+                    "{}{}{}",
+                    " ".repeat(linum_width + 2),
+                    self.theme.characters.lbot,
+                    self.theme.characters.hbar.to_string().repeat(4),
+                ),
+                linum_width + 7,
+            );
+
+            let side_by_side = comparison.layout() == ComparisonLayout::SideBySide;
+            let markers = if side_by_side {
+                [None, None]
+            } else {
+                [
+                    Some(('-', self.theme.styles.removed)),
+                    Some(('+', self.theme.styles.added)),
+                ]
+            };
+            let mut left = self.compared_rows(
+                self.theme.characters.ltop,
+                comparison.old_name(),
+                &old,
+                linum_width,
+                markers[0],
+                None,
+                style,
+            );
+            let mut right = self.compared_rows(
+                if side_by_side {
+                    self.theme.characters.ltop
+                } else {
+                    self.theme.characters.lcross
+                },
+                comparison.new_name(),
+                &new,
+                linum_width,
+                markers[1],
+                spans.label(),
+                style,
+            );
+            let left_width = left.iter().map(|cell| cell.width).max().unwrap_or(0) + 2;
+            let right_width = right.iter().map(|cell| cell.width).max().unwrap_or(0);
+
+            if side_by_side && left_width + right_width <= self.termwidth {
+                let rows = left.len().max(right.len());
+                left.resize_with(rows, Default::default);
+                right.resize_with(rows, Default::default);
+                left.push(footer.clone());
+                right.push(footer);
+                for (left, right) in left.iter().zip(&right) {
+                    let row = format!(
+                        "{}{}{}",
+                        left.text,
+                        " ".repeat(left_width - left.width),
+                        right.text
+                    );
+                    writeln!(f, "{}", row.trim_end())?;
+                }
+            } else {
+                if side_by_side {
+                    // There's no room, so fall back to one above the other.
+                    right[0] = self.compared_rows(
+                        self.theme.characters.lcross,
+                        comparison.new_name(),
+                        &new,
+                        linum_width,
+                        None,
+                        None,
+                        style,
+                    )[0]
+                    .clone();
+                }
+                for cell in left.iter().chain(&right).chain(Some(&footer)) {
+                    writeln!(f, "{}", cell.text)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the lines of `source` that `span` is on, along with which part
+    /// of each line it covers.
+    fn compared_snippet(
+        &self,
+        source: &dyn SourceCode,
+        span: &SourceSpan,
+    ) -> Result<ComparedSnippet, fmt::Error> {
+        let contents = source.read_span(span, 0, 0).map_err(|_| fmt::Error)?;
+        let (line, column) = (contents.line(), contents.column());
+        // Read again from the start of the line, so the lines are whole.
+        let line_start = span.offset().saturating_sub(column);
+        let whole = (line_start, span.len() + (span.offset() - line_start)).into();
+        let contents = source.read_span(&whole, 0, 0).map_err(|_| fmt::Error)?;
+        let data = contents.data();
+        let span_start = span.offset() - line_start;
+        let span_end = span_start + span.len();
+
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        for (idx, raw) in data.split_inclusive(|b| *b == b'\n').enumerate() {
+            let line_offset = offset;
+            offset += raw.len();
+            let raw = raw.strip_suffix(b"\n").unwrap_or(raw);
+            let raw = raw.strip_suffix(b"\r").unwrap_or(raw);
+            if line_offset > span_end || (line_offset == span_end && !span.is_empty()) {
+                break;
+            }
+            let text = String::from_utf8_lossy(raw);
+            let width_of = |end: usize| -> usize {
+                let end = end.saturating_sub(line_offset).min(raw.len());
+                self.line_visual_char_width(&String::from_utf8_lossy(&raw[..end]))
+                    .sum()
+            };
+            let start = width_of(span_start.max(line_offset));
+            let end = width_of(span_end);
+            lines.push(ComparedLine {
+                number: line + idx,
+                text: self.expand_tabs(&text),
+                underline: (start, (end - start).max(1)),
+            });
+        }
+        Ok(ComparedSnippet {
+            line,
+            column: source.visual_column(span, self.tab_width).unwrap_or(column),
+            lines,
+        })
+    }
+
+    /// Lays out a header, each line of a snippet, and the underlines beneath
+    /// them, as cells of a comparison.
+    #[allow(clippy::too_many_arguments)]
+    fn compared_rows(
+        &self,
+        corner: char,
+        name: &str,
+        snippet: &ComparedSnippet,
+        linum_width: usize,
+        marker: Option<(char, Style)>,
+        label: Option<&str>,
+        style: Style,
+    ) -> Vec<Cell> {
+        let location = format!(
+            "[{}:{}:{}]",
+            name,
+            self.display_number(snippet.line + 1),
+            self.display_number(snippet.column + 1)
+        );
+        let mut rows = vec![Cell::new(
+            format!(
+                "{}{}{}[{}:{}:{}]",
+                " ".repeat(linum_width + 2),
+                corner,
+                self.theme.characters.hbar,
+                self.source_name(name),
+                self.display_number(snippet.line + 1),
+                self.display_number(snippet.column + 1)
+            ),
+            linum_width + 4 + location.width(),
+        )];
+        let marker_width = if marker.is_some() { 2 } else { 0 };
+        let last = snippet.lines.len().saturating_sub(1);
+        for (idx, line) in snippet.lines.iter().enumerate() {
+            let marker_text = marker
+                .map(|(marker, style)| format!("{} ", marker.style(style)))
+                .unwrap_or_default();
+            rows.push(Cell::new(
+                format!(
+                    " {:width$} {} {}{}",
+                    self.display_number(line.number + 1)
+                        .style(self.theme.styles.linum),
+                    self.theme.characters.vbar,
+                    marker_text,
+                    line.text,
+                    width = linum_width
+                ),
+                linum_width + 3 + marker_width + line.text.width(),
+            ));
+            let (start, len) = line.underline;
+            let label = label.filter(|_| idx == last);
+            rows.push(Cell::new(
+                format!(
+                    " {:width$} {} {}{}{}",
+                    "",
+                    self.theme.characters.vbar_break,
+                    " ".repeat(marker_width + start),
+                    self.theme
+                        .characters
+                        .underline
+                        .to_string()
+                        .repeat(len)
+                        .style(style),
+                    label.map(|label| format!(" {}", label)).unwrap_or_default(),
+                    width = linum_width
+                ),
+                linum_width
+                    + 3
+                    + marker_width
+                    + start
+                    + len
+                    + label.map_or(0, |label| label.width() + 1),
+            ));
+        }
+        rows
+    }
+
+    /// Styles the text of labels according to the [`DiagnosticTag`]s of
+    /// their diagnostic, such as striking through the labels of deprecated
+    /// usages.
+    fn style_tagged_labels(
+        &self,
+        diagnostic: &(dyn Diagnostic),
+        labels: Vec<LabeledSpan>,
+    ) -> Vec<LabeledSpan> {
+        let tags = match diagnostic.tags() {
+            Some(tags) => tags.collect::<Vec<_>>(),
+            None => return labels,
+        };
+        labels
+            .into_iter()
+            .map(|label| {
+                let mut text = match label.label() {
+                    Some(text) => text.to_string(),
+                    None => return label,
+                };
+                for tag in &tags {
+                    let style = match tag {
+                        DiagnosticTag::Unnecessary => self.theme.styles.unnecessary,
+                        DiagnosticTag::Deprecated => self.theme.styles.deprecated,
+                    };
+                    text = text.style(style).to_string();
+                }
+                if label.primary() {
+                    LabeledSpan::new_primary_with_span(Some(text), *label.inner())
+                } else {
+                    LabeledSpan::new_with_span(Some(text), *label.inner())
+                }
+            })
+            .collect()
+    }
+
     fn render_context<'a>(
         &self,
         f: &mut impl fmt::Write,
@@ -374,7 +1000,14 @@ impl GraphicalReportHandler {
         context: &LabeledSpan,
         labels: &[LabeledSpan],
     ) -> fmt::Result {
-        let (contents, lines) = self.get_lines(source, context.inner())?;
+        let contents = source
+            .read_span(context.inner(), self.context_lines, self.context_lines)
+            .map_err(|_| fmt::Error)?;
+        if contents.is_binary() || looks_binary(contents.data()) {
+            return self.render_hexdump(f, &*contents, context.inner(), labels);
+        }
+        let mut lines = self.get_lines(&*contents);
+        self.add_end_of_source_line(&mut lines, &*contents, labels);
 
         // sorting is your friend
         let labels = labels
@@ -383,6 +1016,8 @@ impl GraphicalReportHandler {
             .map(|(label, st)| FancySpan::new(label.label().map(String::from), *label.inner(), st))
             .collect::<Vec<_>>();
 
+        let (lines, omitted) = self.omit_lines(lines, &labels);
+
         // The max number of gutter-lines that will be active at any given
         // point. We need this to figure out indentation, so we do one loop
         // over the lines to see what the damage is gonna be.
@@ -401,7 +1036,7 @@ impl GraphicalReportHandler {
         // numbers need!
         let linum_width = lines[..]
             .last()
-            .map(|line| line.line_number)
+            .map(|line| self.display_number(line.line_number))
             // It's possible for the source to be an empty string.
             .unwrap_or(0)
             .to_string()
@@ -416,23 +1051,44 @@ impl GraphicalReportHandler {
             self.theme.characters.hbar,
         )?;
 
-        if let Some(source_name) = contents.name() {
-            let source_name = source_name.style(self.theme.styles.link);
+        let name = contents
+            .name()
+            .filter(|name| self.grouped_source.as_deref() != Some(*name));
+        let column = self.header_column(source, &*contents);
+        if let Some(name) = name {
             writeln!(
                 f,
                 "[{}:{}:{}]",
-                source_name,
-                contents.line() + 1,
-                contents.column() + 1
+                self.source_name(name),
+                self.display_number(contents.line() + 1),
+                self.display_number(column + 1)
             )?;
-        } else if lines.len() <= 1 {
+        } else if lines.len() <= 1 && contents.name().is_none() {
             writeln!(f, "{}", self.theme.characters.hbar.to_string().repeat(3))?;
         } else {
-            writeln!(f, "[{}:{}]", contents.line() + 1, contents.column() + 1)?;
+            writeln!(
+                f,
+                "[{}:{}]",
+                self.display_number(contents.line() + 1),
+                self.display_number(column + 1)
+            )?;
         }
 
         // Now it's time for the fun part--actually rendering everything!
-        for line in &lines {
+        let mut highlighter_state = self.highlighter.start_highlighter_state(&*contents);
+        let mut omitted = omitted.iter().peekable();
+        for (idx, line) in lines.iter().enumerate() {
+            if let Some(gap) = omitted.next_if(|gap| gap.before == idx) {
+                self.render_omitted_lines(
+                    f,
+                    linum_width,
+                    max_gutter,
+                    gap,
+                    &labels,
+                    &mut *highlighter_state,
+                )?;
+            }
+
             // Line number, appropriately padded.
             self.write_linum(f, linum_width, line.line_number)?;
 
@@ -442,7 +1098,7 @@ impl GraphicalReportHandler {
             self.render_line_gutter(f, max_gutter, line, &labels)?;
 
             // And _now_ we can print out the line text itself!
-            self.render_line_text(f, &line.text)?;
+            self.render_line_text(f, &line.text, &mut *highlighter_state)?;
 
             // Next, we write all the highlights that apply to this particular line.
             let (single_line, multi_line): (Vec<_>, Vec<_>) = labels
@@ -473,6 +1129,16 @@ impl GraphicalReportHandler {
                 }
             }
         }
+        for gap in omitted {
+            self.render_omitted_lines(
+                f,
+                linum_width,
+                max_gutter,
+                gap,
+                &labels,
+                &mut *highlighter_state,
+            )?;
+        }
         writeln!(
             f,
             "{}{}{}",
@@ -483,6 +1149,185 @@ impl GraphicalReportHandler {
         Ok(())
     }
 
+    /// Renders a snippet of binary source code as a hexdump, sixteen bytes to
+    /// a row, with the bytes each label covers underlined. Only the rows
+    /// around the labels are shown, with `context_lines` rows on either side.
+    fn render_hexdump(
+        &self,
+        f: &mut impl fmt::Write,
+        contents: &dyn SpanContents<'_>,
+        context: &SourceSpan,
+        labels: &[LabeledSpan],
+    ) -> fmt::Result {
+        const ROW_LEN: usize = 16;
+        // Eight hex digits for the offset of each row.
+        const OFFSET_WIDTH: usize = 8;
+
+        let data = contents.data();
+        let data_start = contents.span().offset();
+        let data_end = data_start + data.len();
+        let context_end = context.offset() + context.len();
+        let labels = labels
+            .iter()
+            .zip(self.theme.styles.highlights.iter().cloned().cycle())
+            .filter(|(label, _)| {
+                label.offset() <= context_end && label.offset() + label.len() >= context.offset()
+            })
+            .collect::<Vec<_>>();
+
+        // Rows are aligned to multiples of ROW_LEN in the source, so the same
+        // bytes always end up in the same columns.
+        let row_of = |offset: usize| offset / ROW_LEN;
+        let mut rows = Vec::new();
+        for (label, _) in &labels {
+            let last = label.offset() + label.len().max(1) - 1;
+            let first_row = row_of(label.offset())
+                .saturating_sub(self.context_lines)
+                .max(row_of(data_start));
+            let last_row =
+                (row_of(last) + self.context_lines).min(row_of(data_end.saturating_sub(1)));
+            rows.extend(first_row..=last_row);
+        }
+        rows.sort_unstable();
+        rows.dedup();
+
+        // Header
+        write!(
+            f,
+            "{}{}{}",
+            " ".repeat(OFFSET_WIDTH + 2),
+            self.theme.characters.ltop,
+            self.theme.characters.hbar,
+        )?;
+        if let Some(name) = contents
+            .name()
+            .filter(|name| self.grouped_source.as_deref() != Some(*name))
+        {
+            writeln!(f, "[{}:{:#x}]", self.source_name(name), context.offset())?;
+        } else {
+            writeln!(f, "[{:#x}]", context.offset())?;
+        }
+
+        // The column each byte of a row starts at, with an extra space
+        // halfway through the row to make it easier to count along.
+        let column = |idx: usize| idx * 3 + idx / (ROW_LEN / 2);
+        let mut prev_row = None;
+        for row in rows {
+            if prev_row.map_or(false, |prev| prev + 1 != row) {
+                self.write_no_linum(f, OFFSET_WIDTH)?;
+                writeln!(f, "...")?;
+            }
+            prev_row = Some(row);
+
+            let row_start = row * ROW_LEN;
+            let style_at = |offset: usize| {
+                labels
+                    .iter()
+                    .find(|(label, _)| {
+                        offset >= label.offset() && offset < label.offset() + label.len()
+                    })
+                    .map(|(_, style)| *style)
+            };
+            let mut hex = String::new();
+            let mut ascii = String::new();
+            for idx in 0..ROW_LEN {
+                if idx > 0 {
+                    hex.push(' ');
+                }
+                if idx == ROW_LEN / 2 {
+                    hex.push(' ');
+                }
+                let offset = row_start + idx;
+                let byte = match offset.checked_sub(data_start).and_then(|i| data.get(i)) {
+                    Some(byte) => *byte,
+                    None => {
+                        hex.push_str("  ");
+                        ascii.push(' ');
+                        continue;
+                    }
+                };
+                let shown = if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                };
+                match style_at(offset) {
+                    Some(style) => {
+                        hex.push_str(&format!("{:02x}", byte).style(style).to_string());
+                        ascii.push_str(&shown.style(style).to_string());
+                    }
+                    None => {
+                        hex.push_str(&format!("{:02x}", byte));
+                        ascii.push(shown);
+                    }
+                }
+            }
+            writeln!(
+                f,
+                " {} {} {} {}{}{}",
+                format!("{:0width$x}", row_start, width = OFFSET_WIDTH)
+                    .style(self.theme.styles.linum),
+                self.theme.characters.vbar,
+                hex,
+                self.theme.characters.vbar,
+                ascii,
+                self.theme.characters.vbar,
+            )?;
+
+            // One line under the row for each label on it, underlining the
+            // bytes it covers, and ending with its text on the row it ends.
+            let row_end = row_start + ROW_LEN;
+            for (label, style) in &labels {
+                let label_end = label.offset() + label.len().max(1);
+                if label.offset() >= row_end || label_end <= row_start {
+                    continue;
+                }
+                let first = label.offset().max(row_start) - row_start;
+                let last = label_end.min(row_end) - row_start - 1;
+                self.write_no_linum(f, OFFSET_WIDTH)?;
+                write!(
+                    f,
+                    "{}{}",
+                    " ".repeat(column(first)),
+                    self.theme
+                        .characters
+                        .underline
+                        .to_string()
+                        .repeat(column(last) + 2 - column(first))
+                        .style(*style),
+                )?;
+                match label.label() {
+                    Some(text) if label_end <= row_end => writeln!(f, " {}", text)?,
+                    _ => writeln!(f)?,
+                }
+            }
+        }
+
+        writeln!(
+            f,
+            "{}{}{}",
+            " ".repeat(OFFSET_WIDTH + 2),
+            self.theme.characters.lbot,
+            self.theme.characters.hbar.to_string().repeat(4),
+        )?;
+        Ok(())
+    }
+
+    /// Styles a source name, linking it to the file it refers to if enabled.
+    fn source_name(&self, name: &str) -> String {
+        let source_name = name.style(self.theme.styles.link).to_string();
+        if self.links == LinkStyle::Link && self.file_links {
+            let url = match &self.file_urls {
+                Some(urls) => urls.get(name),
+                None => file_url(name),
+            };
+            if let Some(url) = url {
+                return format!("\u{1b}]8;;{}\u{1b}\\{}\u{1b}]8;;\u{1b}\\", url, source_name);
+            }
+        }
+        source_name
+    }
+
     fn render_line_gutter(
         &self,
         f: &mut impl fmt::Write,
@@ -495,6 +1340,9 @@ impl GraphicalReportHandler {
         }
         let chars = &self.theme.characters;
         let mut gutter = String::new();
+        // The gutter is full of styles, so its visible width is counted as
+        // it's built rather than from its length.
+        let mut width = 0;
         let applicable = highlights.iter().filter(|hl| line.span_applies(hl));
         let mut arrow = false;
         for (i, hl) in applicable.enumerate() {
@@ -509,6 +1357,7 @@ impl GraphicalReportHandler {
                         .to_string(),
                 );
                 gutter.push_str(&chars.rarrow.style(hl.style).to_string());
+                width += max_gutter.saturating_sub(i) + 2;
                 arrow = true;
                 break;
             } else if line.span_ends(hl) {
@@ -526,25 +1375,157 @@ impl GraphicalReportHandler {
                         .to_string(),
                 );
                 gutter.push_str(&chars.rarrow.style(hl.style).to_string());
+                width += max_gutter.saturating_sub(i) + 2;
                 arrow = true;
                 break;
             } else if line.span_flyby(hl) {
                 gutter.push_str(&chars.vbar.style(hl.style).to_string());
+                width += 1;
             } else {
                 gutter.push(' ');
+                width += 1;
             }
         }
         write!(
             f,
             "{}{}",
             gutter,
-            " ".repeat(
-                if arrow { 1 } else { 3 } + max_gutter.saturating_sub(gutter.chars().count())
-            )
+            " ".repeat(if arrow { 1 } else { 3 } + max_gutter.saturating_sub(width))
         )?;
         Ok(())
     }
 
+    fn render_omitted_lines(
+        &self,
+        f: &mut impl fmt::Write,
+        linum_width: usize,
+        max_gutter: usize,
+        omitted: &OmittedLines,
+        highlights: &[FancySpan],
+        highlighter_state: &mut dyn HighlighterState,
+    ) -> fmt::Result {
+        let (first, last) = match (omitted.lines.first(), omitted.lines.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Ok(()),
+        };
+        // Keep the highlighter in sync with the source, even though these
+        // lines don't get printed.
+        for line in &omitted.lines {
+            highlighter_state.highlight_line(&line.text);
+        }
+        // Treat the omitted lines as one big line, so that multiline
+        // highlights running through them keep their gutter going.
+        let skipped = Line {
+            line_number: first.line_number,
+            offset: first.offset,
+            length: last.offset + last.length - first.offset,
+            text: String::new(),
+            replaced: Vec::new(),
+            ends_source: false,
+        };
+        self.write_no_linum(f, linum_width)?;
+        if max_gutter > 0 {
+            let mut gutter = String::new();
+            let mut width = 0;
+            for hl in highlights.iter().filter(|hl| skipped.span_applies(hl)) {
+                if skipped.span_line_only(hl) {
+                    gutter.push(' ');
+                } else {
+                    gutter.push_str(&self.theme.characters.vbar.style(hl.style).to_string());
+                }
+                width += 1;
+            }
+            write!(
+                f,
+                "{}{}",
+                gutter,
+                " ".repeat(3 + max_gutter.saturating_sub(width))
+            )?;
+        }
+        let marker = if omitted.counted {
+            format!("... {} lines omitted ...", omitted.lines.len())
+        } else {
+            "...".to_string()
+        };
+        writeln!(f, "{}", marker.style(self.theme.styles.linum))?;
+        Ok(())
+    }
+
+    /// Picks out the lines of a snippet that won't be rendered: runs of lines
+    /// that are more than `context_lines` away from the start or end of any
+    /// highlight, and, if the snippet is still taller than
+    /// `max_snippet_lines`, everything between its head and tail.
+    fn omit_lines(
+        &self,
+        lines: Vec<Line>,
+        highlights: &[FancySpan],
+    ) -> (Vec<Line>, Vec<OmittedLines>) {
+        let anchors = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| {
+                highlights
+                    .iter()
+                    .any(|hl| line.span_applies(hl) && !line.span_flyby(hl))
+            })
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        let near_anchor = |idx: usize| {
+            anchors
+                .iter()
+                .any(|anchor| idx.max(*anchor) - idx.min(*anchor) <= self.context_lines)
+        };
+
+        let keep = (0..lines.len())
+            .map(|idx| anchors.is_empty() || near_anchor(idx))
+            .collect::<Vec<_>>();
+        let mut kept = Vec::new();
+        let mut omitted = Vec::new();
+        let mut run = Vec::new();
+        for (idx, line) in lines.into_iter().enumerate() {
+            if keep[idx] {
+                kept.push(line);
+                continue;
+            }
+            run.push(line);
+            if keep.get(idx + 1) != Some(&false) {
+                // A single line is no taller than the marker replacing it.
+                if run.len() == 1 {
+                    kept.append(&mut run);
+                } else {
+                    omitted.push(OmittedLines {
+                        before: kept.len(),
+                        lines: std::mem::take(&mut run),
+                        counted: false,
+                    });
+                }
+            }
+        }
+
+        if let Some(max) = self.max_snippet_lines.filter(|max| kept.len() > *max) {
+            let head = (max + 1) / 2;
+            let tail = kept.len() - (max - head);
+            let mut dropped = kept.drain(head..tail).collect::<Vec<_>>();
+            for gap in omitted.iter_mut() {
+                if gap.before >= head && gap.before <= tail {
+                    dropped.append(&mut gap.lines);
+                } else if gap.before > tail {
+                    gap.before -= tail - head;
+                }
+            }
+            omitted.retain(|gap| !gap.lines.is_empty());
+            dropped.sort_by_key(|line| line.offset);
+            omitted.push(OmittedLines {
+                before: head,
+                lines: dropped,
+                counted: true,
+            });
+            omitted.sort_by_key(|gap| gap.before);
+        }
+
+        (kept, omitted)
+    }
+
     fn render_highlight_gutter(
         &self,
         f: &mut impl fmt::Write,
@@ -582,13 +1563,22 @@ impl GraphicalReportHandler {
         write!(
             f,
             " {:width$} {} ",
-            linum.style(self.theme.styles.linum),
+            self.display_number(linum).style(self.theme.styles.linum),
             self.theme.characters.vbar,
             width = width
         )?;
         Ok(())
     }
 
+    /// Converts a 1-based line or column number to the configured base.
+    fn display_number(&self, number: usize) -> usize {
+        if self.zero_based_line_numbers {
+            number.saturating_sub(1)
+        } else {
+            number
+        }
+    }
+
     fn write_no_linum(&self, f: &mut impl fmt::Write, width: usize) -> fmt::Result {
         write!(
             f,
@@ -601,18 +1591,23 @@ impl GraphicalReportHandler {
     }
 
     /// Returns an iterator over the visual width of each character in a line.
+    ///
+    /// Widths are computed per grapheme cluster: the first character of a
+    /// cluster carries the width of the whole cluster, and the rest of its
+    /// characters are zero-width.
     fn line_visual_char_width<'a>(&self, text: &'a str) -> impl Iterator<Item = usize> + 'a {
         let mut column = 0;
         let tab_width = self.tab_width;
-        text.chars().map(move |c| {
-            let width = if c == '\t' {
+        text.graphemes(true).flat_map(move |grapheme| {
+            let width = if grapheme == "\t" {
                 // Round up to the next multiple of tab_width
                 tab_width - column % tab_width
             } else {
-                c.width().unwrap_or(0)
+                grapheme_width(grapheme)
             };
             column += width;
-            width
+            let rest = grapheme.chars().count() - 1;
+            std::iter::once(width).chain(std::iter::repeat(0).take(rest))
         })
     }
 
@@ -621,20 +1616,44 @@ impl GraphicalReportHandler {
         let line_range = line.offset..=(line.offset + line.length);
         assert!(line_range.contains(&offset));
 
-        let text = &line.text[..offset - line.offset];
+        let text = slice_text(&line.text, 0, line.text_offset(offset));
         self.line_visual_char_width(text).sum()
     }
 
-    /// Renders a line to the output formatter, replacing tabs with spaces.
-    fn render_line_text(&self, f: &mut impl fmt::Write, text: &str) -> fmt::Result {
+    /// Replaces the tabs in a line with spaces.
+    fn expand_tabs(&self, text: &str) -> String {
+        let mut expanded = String::with_capacity(text.len());
         for (c, width) in text.chars().zip(self.line_visual_char_width(text)) {
             if c == '\t' {
-                for _ in 0..width {
-                    f.write_char(' ')?
-                }
+                expanded.push_str(&" ".repeat(width));
             } else {
-                f.write_char(c)?
+                expanded.push(c);
+            }
+        }
+        expanded
+    }
+
+    /// Renders a line to the output formatter, replacing tabs with spaces.
+    fn render_line_text(
+        &self,
+        f: &mut impl fmt::Write,
+        text: &str,
+        highlighter_state: &mut dyn HighlighterState,
+    ) -> fmt::Result {
+        let mut widths = self.line_visual_char_width(text);
+        for styled in highlighter_state.highlight_line(text) {
+            let mut chunk = String::with_capacity(styled.inner().len());
+            for c in styled.inner().chars() {
+                let width = widths.next().unwrap_or(0);
+                if c == '\t' {
+                    for _ in 0..width {
+                        chunk.push(' ');
+                    }
+                } else {
+                    chunk.push(c);
+                }
             }
+            write!(f, "{}", styled.style.style(chunk))?;
         }
         f.write_char('\n')?;
         Ok(())
@@ -649,49 +1668,119 @@ impl GraphicalReportHandler {
         single_liners: &[&FancySpan],
         all_highlights: &[FancySpan],
     ) -> fmt::Result {
-        let mut underlines = String::new();
-        let mut highest = 0;
-
         let chars = &self.theme.characters;
-        let vbar_offsets: Vec<_> = single_liners
-            .iter()
-            .map(|hl| {
-                let byte_start = hl.offset();
-                let byte_end = hl.offset() + hl.len();
-                let start = self.visual_offset(line, byte_start).max(highest);
-                let end = self.visual_offset(line, byte_end).max(start + 1);
-
-                let vbar_offset = (start + end) / 2;
-                let num_left = vbar_offset - start;
-                let num_right = end - vbar_offset - 1;
-                if start < end {
-                    underlines.push_str(
-                        &format!(
-                            "{:width$}{}{}{}",
-                            "",
-                            chars.underline.to_string().repeat(num_left),
-                            if hl.len() == 0 {
-                                chars.uarrow
-                            } else if hl.label().is_some() {
-                                chars.underbar
-                            } else {
-                                chars.underline
-                            },
-                            chars.underline.to_string().repeat(num_right),
-                            width = start.saturating_sub(highest),
-                        )
-                        .style(hl.style)
-                        .to_string(),
-                    );
-                }
-                highest = std::cmp::max(highest, end);
 
-                (hl, vbar_offset)
+        // Layout pass: assign every highlight to the first underline row
+        // (level) where it doesn't overlap anything, so overlapping
+        // highlights get stacked instead of colliding.
+        let mut levels: Vec<Vec<(&FancySpan, usize, usize)>> = Vec::new();
+        for hl in single_liners {
+            let start = self.visual_offset(line, hl.offset());
+            let end = self
+                .visual_offset(line, hl.offset() + hl.len())
+                .max(start + 1);
+            match levels
+                .iter_mut()
+                .find(|level| level.last().map_or(true, |(_, _, end)| *end <= start))
+            {
+                Some(level) => level.push((hl, start, end)),
+                None => levels.push(vec![(hl, start, end)]),
+            }
+        }
+
+        // Every labeled highlight gets a connector column, which must not be
+        // shared with any other label's. We pick the column closest to the
+        // middle of the highlight, preferring ones that don't have to cross
+        // the underlines of the rows below it.
+        let mut vbar_offsets: Vec<(&FancySpan, usize)> = Vec::new();
+        for (level_idx, level) in levels.iter().enumerate() {
+            for (hl, start, end) in level {
+                if hl.label().is_none() {
+                    continue;
+                }
+                let mid = (start + end) / 2;
+                let mut candidates = (*start..*end).collect::<Vec<_>>();
+                candidates
+                    .sort_by_key(|offset| (mid.max(*offset) - mid.min(*offset), *offset < mid));
+                let free = |offset: &usize| vbar_offsets.iter().all(|(_, o)| o != offset);
+                let uncovered = |offset: &usize| {
+                    levels[level_idx + 1..]
+                        .iter()
+                        .flatten()
+                        .all(|(_, start, end)| offset < start || offset >= end)
+                };
+                let vbar_offset = candidates
+                    .iter()
+                    .copied()
+                    .find(|offset| free(offset) && uncovered(offset))
+                    .or_else(|| candidates.iter().copied().find(free))
+                    .unwrap_or(mid);
+                vbar_offsets.push((hl, vbar_offset));
+            }
+        }
+        vbar_offsets.sort_by_key(|(_, offset)| *offset);
+        let vbar_at = |level: usize, offset: usize| {
+            levels[..level].iter().flatten().find_map(|(hl, _, _)| {
+                vbar_offsets
+                    .iter()
+                    .find(|(vbar_hl, vbar_offset)| vbar_hl == hl && *vbar_offset == offset)
+                    .map(|(hl, _)| hl)
             })
-            .collect();
-        writeln!(f, "{}", underlines)?;
+        };
+
+        for (level_idx, level) in levels.iter().enumerate() {
+            if level_idx > 0 {
+                self.write_no_linum(f, linum_width)?;
+                self.render_highlight_gutter(f, max_gutter, line, all_highlights)?;
+            }
+            let mut underlines = String::new();
+            let mut column = 0;
+            let pad = |underlines: &mut String, column: &mut usize, until: usize| {
+                while *column < until {
+                    match vbar_at(level_idx, *column) {
+                        Some(hl) => {
+                            underlines.push_str(&chars.vbar.to_string().style(hl.style).to_string())
+                        }
+                        None => underlines.push(' '),
+                    }
+                    *column += 1;
+                }
+            };
+            for (hl, start, end) in level {
+                pad(&mut underlines, &mut column, *start);
+                let vbar_offset = vbar_offsets
+                    .iter()
+                    .find(|(vbar_hl, _)| vbar_hl == hl)
+                    .map(|(_, offset)| *offset);
+                let mut run = String::new();
+                for offset in *start..*end {
+                    // Connectors from the rows above cross this underline.
+                    if let Some(crossing) = vbar_at(level_idx, offset) {
+                        underlines.push_str(&run.style(hl.style).to_string());
+                        run.clear();
+                        underlines
+                            .push_str(&chars.xbar.to_string().style(crossing.style).to_string());
+                    } else if hl.len() == 0 {
+                        run.push(chars.uarrow);
+                    } else if Some(offset) == vbar_offset {
+                        run.push(chars.underbar);
+                    } else {
+                        run.push(chars.underline);
+                    }
+                }
+                underlines.push_str(&run.style(hl.style).to_string());
+                column = *end;
+            }
+            let last_vbar = vbar_offsets
+                .iter()
+                .map(|(_, offset)| offset + 1)
+                .max()
+                .unwrap_or(0);
+            pad(&mut underlines, &mut column, last_vbar);
+            writeln!(f, "{}", underlines.trim_end())?;
+        }
 
-        for hl in single_liners.iter().rev() {
+        for (hl, _) in vbar_offsets.iter().rev() {
             if let Some(label) = hl.label() {
                 self.write_no_linum(f, linum_width)?;
                 self.render_highlight_gutter(f, max_gutter, line, all_highlights)?;
@@ -701,7 +1790,7 @@ impl GraphicalReportHandler {
                         write!(f, " ")?;
                         curr_offset += 1;
                     }
-                    if *offset_hl != hl {
+                    if offset_hl != hl {
                         write!(f, "{}", chars.vbar.to_string().style(offset_hl.style))?;
                         curr_offset += 1;
                     } else {
@@ -730,28 +1819,58 @@ impl GraphicalReportHandler {
         Ok(())
     }
 
-    fn get_lines<'a>(
-        &'a self,
-        source: &'a dyn SourceCode,
-        context_span: &'a SourceSpan,
-    ) -> Result<(Box<dyn SpanContents<'a> + 'a>, Vec<Line>), fmt::Error> {
-        let context_data = source
-            .read_span(context_span, self.context_lines, self.context_lines)
-            .map_err(|_| fmt::Error)?;
-        let context = std::str::from_utf8(context_data.data()).expect("Bad utf8 detected");
+    /// Zero-length labels at the very end of a snippet that ends with a line
+    /// ending, or that's empty, point at the start of a line that isn't in
+    /// it, such as to say something's missing at the end of a file. This adds
+    /// that line, empty, for them to be rendered on. When the snippet ends
+    /// without a line ending instead, they point just past the end of its last
+    /// line, and are rendered there.
+    fn add_end_of_source_line(
+        &self,
+        lines: &mut Vec<Line>,
+        contents: &dyn SpanContents<'_>,
+        labels: &[LabeledSpan],
+    ) {
+        let data = contents.data();
+        let end = contents.span().offset() + data.len();
+        let at_line_start = matches!(data.last(), None | Some(b'\n') | Some(b'\r'));
+        let needed = labels
+            .iter()
+            .any(|label| label.inner().is_empty() && label.offset() == end);
+        if !needed {
+            return;
+        }
+        match lines.last_mut() {
+            Some(last) if !at_line_start => last.ends_source = true,
+            last => {
+                let line_number = last.map_or(contents.line(), |line| line.line_number) + 1;
+                lines.push(Line {
+                    line_number,
+                    offset: end,
+                    length: 0,
+                    text: String::new(),
+                    replaced: Vec::new(),
+                    ends_source: true,
+                });
+            }
+        }
+    }
+
+    fn get_lines(&self, context_data: &dyn SpanContents<'_>) -> Vec<Line> {
         let mut line = context_data.line();
         let mut column = context_data.column();
         let mut offset = context_data.span().offset();
         let mut line_offset = offset;
-        let mut iter = context.chars().peekable();
+        let mut iter = lossy_chars(context_data.data()).into_iter().peekable();
         let mut line_str = String::new();
+        let mut replaced = Vec::new();
         let mut lines = Vec::new();
-        while let Some(char) = iter.next() {
-            offset += char.len_utf8();
+        while let Some((char, len)) = iter.next() {
+            offset += len;
             let mut at_end_of_file = false;
             match char {
                 '\r' => {
-                    if iter.next_if_eq(&'\n').is_some() {
+                    if iter.next_if(|&(c, _)| c == '\n').is_some() {
                         offset += 1;
                         line += 1;
                         column = 0;
@@ -769,6 +1888,9 @@ impl GraphicalReportHandler {
                 _ => {
                     line_str.push(char);
                     column += 1;
+                    if len != char.len_utf8() {
+                        replaced.push((offset - line_offset, line_str.len()));
+                    }
                 }
             }
 
@@ -782,13 +1904,82 @@ impl GraphicalReportHandler {
                     offset: line_offset,
                     length: offset - line_offset,
                     text: line_str.clone(),
+                    replaced: std::mem::take(&mut replaced),
+                    ends_source: false,
                 });
                 line_str.clear();
                 line_offset = offset;
             }
         }
-        Ok((context_data, lines))
+        lines
+    }
+}
+
+/// Slices `text` by byte offsets, clamped to its length and moved back to the
+/// nearest char boundaries, since spans aren't guaranteed to fall on them.
+fn slice_text(text: &str, start: usize, end: usize) -> &str {
+    let floor = |mut idx: usize| {
+        idx = idx.min(text.len());
+        while !text.is_char_boundary(idx) {
+            idx -= 1;
+        }
+        idx
+    };
+    let end = floor(end);
+    &text[floor(start).min(end)..end]
+}
+
+/// Whether a snippet looks like binary data rather than text: that is, it isn't
+/// valid UTF-8 and has control characters in it that text wouldn't. Text with
+/// the odd invalid byte in it is still rendered as text.
+fn looks_binary(data: &[u8]) -> bool {
+    std::str::from_utf8(data).is_err()
+        && data
+            .iter()
+            .any(|b| b.is_ascii_control() && !b.is_ascii_whitespace() && *b != 0x1b)
+}
+
+/// Returns a `file://` URL for a source name, if it refers to a file that
+/// exists on disk.
+fn file_url(name: &str) -> Option<String> {
+    let path = Path::new(name);
+    if !path.is_file() {
+        return None;
+    }
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().ok()?.join(path)
+    };
+    Some(path_url(&path.to_str()?.replace('\\', "/")))
+}
+
+/// Turns an absolute path, with forward slashes, into a `file://` URL,
+/// percent-encoding everything but unreserved characters and slashes.
+fn path_url(path: &str) -> String {
+    let mut url = String::from("file://");
+    let mut rest = path;
+    if !rest.starts_with('/') {
+        // Windows paths start with a drive letter rather than a slash, and
+        // the colon after it stays as it is: `file:///C:/...`.
+        url.push('/');
+        let bytes = rest.as_bytes();
+        if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+            url.push_str(&rest[..2]);
+            rest = &rest[2..];
+        }
+    }
+    for byte in rest.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                url.push(byte as char)
+            }
+            _ => {
+                let _ = write!(url, "%{:02X}", byte);
+            }
+        }
     }
+    url
 }
 
 impl ReportHandler for GraphicalReportHandler {
@@ -805,15 +1996,78 @@ impl ReportHandler for GraphicalReportHandler {
 Support types
 */
 
+/// The lines of one of the sources of a comparison that one of its spans is
+/// on.
+struct ComparedSnippet {
+    line: usize,
+    column: usize,
+    lines: Vec<ComparedLine>,
+}
+
+impl ComparedSnippet {
+    fn last_line_number(&self) -> usize {
+        self.lines.last().map_or(self.line, |line| line.number) + 1
+    }
+}
+
+struct ComparedLine {
+    number: usize,
+    text: String,
+    /// The visual column the span starts at on this line, and how wide it is.
+    underline: (usize, usize),
+}
+
+/// Rendered text, along with how wide it is once escape codes are left out.
+#[derive(Clone, Default)]
+struct Cell {
+    text: String,
+    width: usize,
+}
+
+impl Cell {
+    fn new(text: String, width: usize) -> Self {
+        Self { text, width }
+    }
+}
+
+/// The `file://` URLs of the sources named in headers during a single render,
+/// worked out once per name, since doing so checks the file system.
+#[derive(Debug, Clone, Default)]
+struct FileUrls(Arc<Mutex<HashMap<String, Option<String>>>>);
+
+impl FileUrls {
+    fn get(&self, name: &str) -> Option<String> {
+        let mut urls = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        urls.entry(name.to_string())
+            .or_insert_with(|| file_url(name))
+            .clone()
+    }
+}
+
 #[derive(Debug)]
 struct Line {
     line_number: usize,
     offset: usize,
     length: usize,
     text: String,
+    /// Where invalid UTF-8 in the source was replaced with U+FFFD, as the
+    /// offsets just past each replacement in the source and in `text`.
+    replaced: Vec<(usize, usize)>,
+    /// Whether this is the last line of the source, so that zero-length spans
+    /// just past its end apply to it.
+    ends_source: bool,
 }
 
 impl Line {
+    /// Converts an offset into the source to one into `text`.
+    fn text_offset(&self, offset: usize) -> usize {
+        let offset = offset - self.offset;
+        match self.replaced.iter().rev().find(|(src, _)| *src <= offset) {
+            Some((src, text)) => text + (offset - src),
+            None => offset,
+        }
+    }
+
     fn span_line_only(&self, span: &FancySpan) -> bool {
         span.offset() >= self.offset && span.offset() + span.len() <= self.offset + self.length
     }
@@ -826,6 +2080,8 @@ impl Line {
         || (span.offset() < self.offset && span.offset() + spanlen > self.offset + self.length) //todo
         // Span ends on this line
         || (span.offset() + spanlen > self.offset && span.offset() + spanlen <= self.offset + self.length)
+        // Span is a point just past the end of the source
+        || (self.ends_source && span.len() == 0 && span.offset() == self.offset + self.length)
     }
 
     // A 'flyby' is a multi-line span that technically covers this line, but
@@ -853,6 +2109,16 @@ impl Line {
     }
 }
 
+/// Lines of a snippet that get replaced by a single marker line.
+#[derive(Debug)]
+struct OmittedLines {
+    /// Index of the rendered line the marker goes in front of.
+    before: usize,
+    lines: Vec<Line>,
+    /// Whether the marker says how many lines it stands for.
+    counted: bool,
+}
+
 #[derive(Debug, Clone)]
 struct FancySpan {
     label: Option<String>,
@@ -889,3 +2155,16 @@ impl FancySpan {
         self.span.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_urls() {
+        assert_eq!("file:///tmp/a%20b.rs", path_url("/tmp/a b.rs"));
+        assert_eq!("file:///tmp/a%3Ab.rs", path_url("/tmp/a:b.rs"));
+        assert_eq!("file:///C:/Users/a%20b.rs", path_url("C:/Users/a b.rs"));
+        assert_eq!("file:///C:/a%3Ab.rs", path_url("C:/a:b.rs"));
+    }
+}
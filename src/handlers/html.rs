@@ -0,0 +1,354 @@
+use std::fmt::{self, Write};
+
+use crate::handlers::{cause_messages, lossy_slice, snippet_lines, snippet_spans, Line};
+use crate::protocol::{Diagnostic, Severity};
+use crate::{LabeledSpan, ReportHandler, SourceCode, SourceSpan};
+
+/**
+[`ReportHandler`] that renders a [`Diagnostic`] as semantic HTML, for
+embedding in web playgrounds, documentation generators and the like.
+
+No styling is included: everything is tagged with `miette-*` CSS classes so
+that the embedding page can provide its own stylesheet. Each diagnostic is
+wrapped in a `div.miette-diagnostic`, with a
+`miette-severity-{bug,error,warning,advice,info}` class, and each highlighted
+span is a `mark.miette-highlight` with a numbered `miette-highlight-N` class
+matching its entry in the list of labels below the snippet.
+*/
+#[derive(Debug, Clone)]
+pub struct HtmlReportHandler {
+    context_lines: usize,
+    with_cause_chain: bool,
+    footer: Option<String>,
+}
+
+impl HtmlReportHandler {
+    /// Create a new [`HtmlReportHandler`].
+    pub fn new() -> Self {
+        Self {
+            footer: None,
+            context_lines: 1,
+            with_cause_chain: true,
+        }
+    }
+
+    /// Include the cause chain of the top-level error in the report, if
+    /// available.
+    pub fn with_cause_chain(mut self) -> Self {
+        self.with_cause_chain = true;
+        self
+    }
+
+    /// Do not include the cause chain of the top-level error in the report.
+    pub fn without_cause_chain(mut self) -> Self {
+        self.with_cause_chain = false;
+        self
+    }
+
+    /// Set the footer to be displayed at the end of the report.
+    pub fn with_footer(mut self, footer: String) -> Self {
+        self.footer = Some(footer);
+        self
+    }
+
+    /// Sets the number of lines of context to show around each error.
+    pub fn with_context_lines(mut self, lines: usize) -> Self {
+        self.context_lines = lines;
+        self
+    }
+}
+
+impl Default for HtmlReportHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) struct Escape<'a>(&'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&#39;")?,
+                _ => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn escape(input: &'_ str) -> Escape<'_> {
+    Escape(input)
+}
+
+impl HtmlReportHandler {
+    /// Render a [`Diagnostic`]. This function is mostly internal and meant to
+    /// be called by the toplevel [`ReportHandler`] handler, but is made public
+    /// to make it easier (possible) to test in isolation from global state.
+    pub fn render_report(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+    ) -> fmt::Result {
+        self.render_diagnostic(f, diagnostic, None, self.with_cause_chain)?;
+        if let Some(footer) = &self.footer {
+            writeln!(
+                f,
+                r#"<footer class="miette-footer">{}</footer>"#,
+                escape(footer)
+            )?;
+        }
+        Ok(())
+    }
+
+    fn render_diagnostic(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+        parent_src: Option<&dyn SourceCode>,
+        with_cause_chain: bool,
+    ) -> fmt::Result {
+        let severity = match diagnostic.severity() {
+            Some(Severity::Bug) => "bug",
+            Some(Severity::Error) | None => "error",
+            Some(Severity::Warning) => "warning",
+            Some(Severity::Advice) => "advice",
+            Some(Severity::Info) => "info",
+        };
+        writeln!(
+            f,
+            r#"<div class="miette-diagnostic miette-severity-{}">"#,
+            severity
+        )?;
+        self.render_header(f, diagnostic, severity)?;
+        if with_cause_chain {
+            self.render_causes(f, diagnostic)?;
+        }
+        let src = diagnostic.source_code().or(parent_src);
+        self.render_snippets(f, diagnostic, src)?;
+        self.render_footer(f, diagnostic)?;
+        if let Some(related) = diagnostic.related() {
+            writeln!(f, r#"<div class="miette-related">"#)?;
+            for rel in related {
+                self.render_diagnostic(f, rel, src, true)?;
+            }
+            writeln!(f, "</div>")?;
+        }
+        writeln!(f, "</div>")
+    }
+
+    fn render_header(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+        severity: &str,
+    ) -> fmt::Result {
+        write!(
+            f,
+            r#"<p class="miette-header"><span class="miette-severity">{}</span>"#,
+            severity
+        )?;
+        if let Some(code) = diagnostic.code() {
+            write!(
+                f,
+                r#"<span class="miette-code">[{}]</span>"#,
+                escape(&code.to_string())
+            )?;
+        }
+        writeln!(
+            f,
+            r#": <span class="miette-message">{}</span></p>"#,
+            escape(&diagnostic.to_string())
+        )
+    }
+
+    fn render_causes(&self, f: &mut impl fmt::Write, diagnostic: &(dyn Diagnostic)) -> fmt::Result {
+        let causes = cause_messages(diagnostic);
+        if !causes.is_empty() {
+            writeln!(f, r#"<ul class="miette-causes">"#)?;
+            for error in causes {
+                writeln!(f, "<li>{}</li>", escape(&error))?;
+            }
+            writeln!(f, "</ul>")?;
+        }
+        Ok(())
+    }
+
+    fn render_footer(&self, f: &mut impl fmt::Write, diagnostic: &(dyn Diagnostic)) -> fmt::Result {
+        if let Some(note) = diagnostic.note() {
+            writeln!(
+                f,
+                r#"<p class="miette-note">note: {}</p>"#,
+                escape(&note.to_string())
+            )?;
+        }
+        if let Some(help) = diagnostic.help() {
+            writeln!(
+                f,
+                r#"<p class="miette-help">help: {}</p>"#,
+                escape(&help.to_string())
+            )?;
+        }
+        if let Some(url) = diagnostic.url() {
+            let url = url.to_string();
+            writeln!(
+                f,
+                r#"<p class="miette-url">For more details, see <a href="{0}">{0}</a></p>"#,
+                escape(&url)
+            )?;
+        }
+        Ok(())
+    }
+
+    fn render_snippets(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+        source_code: Option<&dyn SourceCode>,
+    ) -> fmt::Result {
+        let (source, labels) = match (source_code, diagnostic.labels()) {
+            (Some(source), Some(labels)) => (source, labels),
+            _ => return Ok(()),
+        };
+        let mut labels = labels.collect::<Vec<_>>();
+        labels.sort_unstable_by_key(|l| l.inner().offset());
+
+        let spans = snippet_spans(source, labels.iter().map(|l| l.inner()), self.context_lines)?;
+        for span in spans {
+            self.render_context(f, source, &span, &labels)?;
+        }
+        Ok(())
+    }
+
+    fn render_context(
+        &self,
+        f: &mut impl fmt::Write,
+        source: &dyn SourceCode,
+        context: &SourceSpan,
+        labels: &[LabeledSpan],
+    ) -> fmt::Result {
+        let (contents, lines) = snippet_lines(source, context, self.context_lines)?;
+        let labels = labels
+            .iter()
+            .enumerate()
+            .filter(|(_, label)| lines.iter().any(|line| line.intersects(label.inner())))
+            .collect::<Vec<_>>();
+
+        writeln!(f, r#"<figure class="miette-snippet">"#)?;
+        write!(f, r#"<figcaption class="miette-source">["#)?;
+        if let Some(name) = contents.name() {
+            write!(f, "{}:", escape(name))?;
+        }
+        writeln!(
+            f,
+            "{}:{}]</figcaption>",
+            contents.line() + 1,
+            contents.column() + 1
+        )?;
+        write!(f, r#"<pre class="miette-source-code"><code>"#)?;
+        for line in &lines {
+            write!(
+                f,
+                r#"<span class="miette-line"><span class="miette-linum">{}</span>"#,
+                line.line_number
+            )?;
+            self.render_line(f, line, &labels)?;
+            writeln!(f, "</span>")?;
+        }
+        writeln!(f, "</code></pre>")?;
+        if labels.iter().any(|(_, label)| label.label().is_some()) {
+            writeln!(f, r#"<ul class="miette-labels">"#)?;
+            for (i, label) in &labels {
+                if let Some(text) = label.label() {
+                    writeln!(
+                        f,
+                        r#"<li class="miette-label miette-highlight-{}">{}</li>"#,
+                        i,
+                        escape(text)
+                    )?;
+                }
+            }
+            writeln!(f, "</ul>")?;
+        }
+        writeln!(f, "</figure>")
+    }
+
+    /// Writes the text of a single line, wrapping every highlighted range in
+    /// a `<mark>`. Overlapping labels are split into non-overlapping runs,
+    /// each of which carries the classes of all the labels covering it.
+    fn render_line(
+        &self,
+        f: &mut impl fmt::Write,
+        line: &Line,
+        labels: &[(usize, &LabeledSpan)],
+    ) -> fmt::Result {
+        let line_end = line.end();
+        let clamp = |offset: usize| offset.max(line.offset).min(line_end);
+        let mut boundaries = vec![line.offset, line_end];
+        for (_, label) in labels {
+            boundaries.push(clamp(label.offset()));
+            boundaries.push(clamp(label.offset() + label.len()));
+        }
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        let write_points = |f: &mut dyn fmt::Write, at: usize| -> fmt::Result {
+            for (i, label) in labels {
+                if label.is_empty() && label.offset() == at {
+                    write!(
+                        f,
+                        r#"<mark class="miette-highlight miette-highlight-{} miette-point"></mark>"#,
+                        i
+                    )?;
+                }
+            }
+            Ok(())
+        };
+
+        for window in boundaries.windows(2) {
+            let (start, end) = (window[0], window[1]);
+            write_points(f, start)?;
+            let text = lossy_slice(&line.source, start - line.offset, end - line.offset);
+            let covering = labels
+                .iter()
+                .filter(|(_, label)| {
+                    !label.is_empty()
+                        && label.offset() <= start
+                        && label.offset() + label.len() >= end
+                })
+                .map(|(i, _)| format!(" miette-highlight-{}", i))
+                .collect::<String>();
+            if covering.is_empty() {
+                write!(f, "{}", escape(&text))?;
+            } else {
+                write!(
+                    f,
+                    r#"<mark class="miette-highlight{}">{}</mark>"#,
+                    covering,
+                    escape(&text)
+                )?;
+            }
+        }
+        if line_end > line.offset {
+            write_points(f, line_end)?;
+        } else {
+            write_points(f, line.offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl ReportHandler for HtmlReportHandler {
+    fn debug(&self, diagnostic: &(dyn Diagnostic), f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return fmt::Debug::fmt(diagnostic, f);
+        }
+
+        self.render_report(f, diagnostic)
+    }
+}
@@ -1,7 +1,8 @@
 use std::fmt::{self, Write};
 
 use crate::{
-    diagnostic_chain::DiagnosticChain, protocol::Diagnostic, ReportHandler, Severity, SourceCode,
+    diagnostic_chain::DiagnosticChain, handlers::primary_label, protocol::Diagnostic,
+    ReportHandler, Severity, SourceCode,
 };
 
 /**
@@ -24,7 +25,7 @@ impl Default for JSONReportHandler {
     }
 }
 
-struct Escape<'a>(&'a str);
+pub(crate) struct Escape<'a>(&'a str);
 
 impl fmt::Display for Escape<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -41,6 +42,8 @@ impl fmt::Display for Escape<'_> {
             };
             if let Some(escape) = escape {
                 f.write_str(escape)?;
+            } else if c < ' ' {
+                write!(f, "\\u{:04x}", c as u32)?;
             } else {
                 f.write_char(c)?;
             }
@@ -49,7 +52,7 @@ impl fmt::Display for Escape<'_> {
     }
 }
 
-fn escape(input: &'_ str) -> Escape<'_> {
+pub(crate) fn escape(input: &'_ str) -> Escape<'_> {
     Escape(input)
 }
 
@@ -76,11 +79,24 @@ impl JSONReportHandler {
             write!(f, r#""code": "{}","#, escape(&code.to_string()))?;
         }
         let severity = match diagnostic.severity() {
+            Some(Severity::Bug) => "bug",
             Some(Severity::Error) | None => "error",
             Some(Severity::Warning) => "warning",
             Some(Severity::Advice) => "advice",
+            Some(Severity::Info) => "info",
         };
         write!(f, r#""severity": "{:}","#, severity)?;
+        if let Some(tags) = diagnostic.tags() {
+            let tags = tags.map(|tag| format!(r#""{}""#, tag)).collect::<Vec<_>>();
+            write!(f, r#""tags": [{}],"#, tags.join(","))?;
+        }
+        if let Some(metadata) = diagnostic.metadata().filter(|m| !m.is_empty()) {
+            let entries = metadata
+                .iter()
+                .map(|(key, value)| format!(r#""{}": "{}""#, escape(key), escape(value)))
+                .collect::<Vec<_>>();
+            write!(f, r#""metadata": {{{}}},"#, entries.join(","))?;
+        }
         if let Some(cause_iter) = diagnostic
             .diagnostic_source()
             .map(DiagnosticChain::from_diagnostic)
@@ -103,9 +119,21 @@ impl JSONReportHandler {
         if let Some(url) = diagnostic.url() {
             write!(f, r#""url": "{}","#, &url.to_string())?;
         }
+        if let Some(expected) = diagnostic.expected() {
+            write!(f, r#""expected": "{}","#, escape(&expected.to_string()))?;
+        }
+        if let Some(found) = diagnostic.found() {
+            write!(f, r#""found": "{}","#, escape(&found.to_string()))?;
+        }
+        if let Some(note) = diagnostic.note() {
+            write!(f, r#""note": "{}","#, escape(&note.to_string()))?;
+        }
         if let Some(help) = diagnostic.help() {
             write!(f, r#""help": "{}","#, escape(&help.to_string()))?;
         }
+        if let Some(suggestion) = diagnostic.suggestion() {
+            write!(f, r#""suggestion": "{}","#, escape(&suggestion.to_string()))?;
+        }
         let src = diagnostic.source_code().or(parent_src);
         if let Some(src) = src {
             self.render_snippets(f, diagnostic, src)?;
@@ -133,6 +161,26 @@ impl JSONReportHandler {
         } else {
             write!(f, r#""labels": [],"#)?;
         }
+        if let Some(fixes) = diagnostic.fixes() {
+            write!(f, r#""fixes": ["#)?;
+            let mut add_comma = false;
+            for fix in fixes {
+                if add_comma {
+                    write!(f, ",")?;
+                } else {
+                    add_comma = true;
+                }
+                write!(f, "{{")?;
+                write!(f, r#""span": {{"#)?;
+                write!(f, r#""offset": {},"#, fix.span().offset())?;
+                write!(f, r#""length": {}"#, fix.span().len())?;
+                write!(f, "}},")?;
+                write!(f, r#""replacement": "{}","#, escape(fix.replacement()))?;
+                write!(f, r#""applicability": "{}""#, fix.applicability())?;
+                write!(f, "}}")?;
+            }
+            write!(f, "],")?;
+        }
         if let Some(relateds) = diagnostic.related() {
             write!(f, r#""related": ["#)?;
             let mut add_comma = false;
@@ -157,12 +205,10 @@ impl JSONReportHandler {
         diagnostic: &(dyn Diagnostic),
         source: &dyn SourceCode,
     ) -> fmt::Result {
-        if let Some(mut labels) = diagnostic.labels() {
-            if let Some(label) = labels.next() {
-                if let Ok(span_content) = source.read_span(label.inner(), 0, 0) {
-                    let filename = span_content.name().unwrap_or_default();
-                    return write!(f, r#""filename": "{}","#, escape(filename));
-                }
+        if let Some(label) = primary_label(diagnostic) {
+            if let Ok(span_content) = source.read_span(label.inner(), 0, 0) {
+                let filename = span_content.name().unwrap_or_default();
+                return write!(f, r#""filename": "{}","#, escape(filename));
             }
         }
         write!(f, r#""filename": "","#)
@@ -179,4 +225,5 @@ impl ReportHandler for JSONReportHandler {
 fn test_escape() {
     assert_eq!(escape("a\nb").to_string(), r"a\nb");
     assert_eq!(escape("C:\\Miette").to_string(), r"C:\\Miette");
+    assert_eq!(escape("\u{1b}[0m").to_string(), r"\u001b[0m");
 }
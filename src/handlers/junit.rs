@@ -0,0 +1,229 @@
+use std::fmt;
+
+use crate::{
+    handlers::{html::escape, primary_label, CompactReportHandler},
+    protocol::Diagnostic,
+    ReportHandler, Severity,
+};
+
+/**
+[`ReportHandler`] that converts diagnostics into a [JUnit
+XML](https://github.com/testmoapp/junitxml) report, so that test dashboards
+can ingest linter and compiler output.
+
+By default, every diagnostic becomes a `testcase` of its own, named after its
+[`Diagnostic::code`] and line, and grouped by the name of the file its primary
+label points into. With
+[`with_testcase_per_file`](JUnitReportHandler::with_testcase_per_file), all
+diagnostics for a file are collected into a single testcase instead.
+
+Error and bug diagnostics turn their testcase into a `failure`. Warnings,
+advice and info don't fail a testcase, and are listed in its `system-out` instead.
+*/
+#[derive(Debug, Clone)]
+pub struct JUnitReportHandler {
+    suite_name: String,
+    testcase_per_file: bool,
+}
+
+impl JUnitReportHandler {
+    /// Create a new [`JUnitReportHandler`]. The test suite name defaults to
+    /// `miette`, which you will most likely want to override with
+    /// [`JUnitReportHandler::with_suite_name`].
+    pub fn new() -> Self {
+        Self {
+            suite_name: "miette".into(),
+            testcase_per_file: false,
+        }
+    }
+
+    /// Sets the name of the test suite. This is also used as the name of the
+    /// testcases for diagnostics that don't point into a named file.
+    pub fn with_suite_name(mut self, name: impl Into<String>) -> Self {
+        self.suite_name = name.into();
+        self
+    }
+
+    /// Whether to render a single testcase per file, instead of one per
+    /// diagnostic. Defaults to `false`.
+    pub fn with_testcase_per_file(mut self, testcase_per_file: bool) -> Self {
+        self.testcase_per_file = testcase_per_file;
+        self
+    }
+}
+
+impl Default for JUnitReportHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JUnitReportHandler {
+    /// Render a [`Diagnostic`] as a complete JUnit report containing a single
+    /// testcase. This function is mostly internal and meant to be called by
+    /// the toplevel [`ReportHandler`] handler, but is made public to make it
+    /// easier (possible) to test in isolation from global state.
+    pub fn render_report(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+    ) -> fmt::Result {
+        self.render_reports(f, std::iter::once(diagnostic))
+    }
+
+    /// Render any number of [`Diagnostic`]s as a single JUnit report, with one
+    /// test suite containing their testcases.
+    pub fn render_reports<'a>(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostics: impl IntoIterator<Item = &'a (dyn Diagnostic + 'a)>,
+    ) -> fmt::Result {
+        let mut testcases: Vec<TestCase<'a>> = Vec::new();
+        for diagnostic in diagnostics {
+            let (file, line) = location(diagnostic);
+            let classname = file.unwrap_or_else(|| self.suite_name.clone());
+            if self.testcase_per_file {
+                match testcases.iter_mut().find(|tc| tc.name == classname) {
+                    Some(testcase) => testcase.diagnostics.push(diagnostic),
+                    None => testcases.push(TestCase {
+                        classname: self.suite_name.clone(),
+                        name: classname,
+                        diagnostics: vec![diagnostic],
+                    }),
+                }
+            } else {
+                let mut name = diagnostic
+                    .code()
+                    .map(|code| code.to_string())
+                    .unwrap_or_else(|| diagnostic.to_string());
+                if let Some(line) = line {
+                    name = format!("{} at line {}", name, line);
+                }
+                testcases.push(TestCase {
+                    classname,
+                    name,
+                    diagnostics: vec![diagnostic],
+                });
+            }
+        }
+
+        let failures = testcases
+            .iter()
+            .filter(|tc| tc.errors().count() > 0)
+            .count();
+        writeln!(f, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            f,
+            r#"<testsuites name="{}" tests="{}" failures="{}">"#,
+            escape(&self.suite_name),
+            testcases.len(),
+            failures
+        )?;
+        writeln!(
+            f,
+            r#"<testsuite name="{}" tests="{}" failures="{}" errors="0" skipped="0">"#,
+            escape(&self.suite_name),
+            testcases.len(),
+            failures
+        )?;
+        for testcase in &testcases {
+            self.render_testcase(f, testcase)?;
+        }
+        writeln!(f, "</testsuite>")?;
+        writeln!(f, "</testsuites>")
+    }
+
+    fn render_testcase(&self, f: &mut impl fmt::Write, testcase: &TestCase<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            r#"<testcase classname="{}" name="{}">"#,
+            escape(&testcase.classname),
+            escape(&testcase.name)
+        )?;
+        let errors = testcase.errors().collect::<Vec<_>>();
+        if !errors.is_empty() {
+            let message = match &errors[..] {
+                [error] => error.to_string(),
+                _ => format!("{} errors", errors.len()),
+            };
+            let kind = match &errors[..] {
+                [error] => error.code().map(|code| code.to_string()),
+                _ => None,
+            };
+            write!(f, r#"<failure message="{}""#, escape(&message))?;
+            if let Some(kind) = kind {
+                write!(f, r#" type="{}""#, escape(&kind))?;
+            }
+            writeln!(f, ">{}</failure>", escape(&details(&errors)?))?;
+        }
+        let others = testcase
+            .diagnostics
+            .iter()
+            .copied()
+            .filter(|diagnostic| !is_error(*diagnostic))
+            .collect::<Vec<_>>();
+        if !others.is_empty() {
+            writeln!(f, "<system-out>{}</system-out>", escape(&details(&others)?))?;
+        }
+        writeln!(f, "</testcase>")
+    }
+}
+
+struct TestCase<'a> {
+    classname: String,
+    name: String,
+    diagnostics: Vec<&'a (dyn Diagnostic + 'a)>,
+}
+
+impl<'a> TestCase<'a> {
+    fn errors(&self) -> impl Iterator<Item = &'a (dyn Diagnostic + 'a)> + '_ {
+        self.diagnostics
+            .iter()
+            .copied()
+            .filter(|diagnostic| is_error(*diagnostic))
+    }
+}
+
+fn is_error(diagnostic: &dyn Diagnostic) -> bool {
+    matches!(
+        diagnostic.severity(),
+        Some(Severity::Bug | Severity::Error) | None
+    )
+}
+
+/// Returns the name of the file the primary label of a diagnostic points
+/// into, and the 1-based line it's on.
+fn location(diagnostic: &dyn Diagnostic) -> (Option<String>, Option<usize>) {
+    let contents = primary_label(diagnostic).and_then(|label| {
+        diagnostic
+            .source_code()?
+            .read_span(label.inner(), 0, 0)
+            .ok()
+    });
+    match contents {
+        Some(contents) => (contents.name().map(String::from), Some(contents.line() + 1)),
+        None => (None, None),
+    }
+}
+
+/// Renders diagnostics as compact lines, each followed by its help text.
+fn details(diagnostics: &[&dyn Diagnostic]) -> Result<String, fmt::Error> {
+    let compact = CompactReportHandler::new();
+    let mut out = String::new();
+    for diagnostic in diagnostics {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        compact.render_report(&mut out, *diagnostic)?;
+        if let Some(help) = diagnostic.help() {
+            out.push_str(&format!("\nhelp: {}", help));
+        }
+    }
+    Ok(out)
+}
+
+impl ReportHandler for JUnitReportHandler {
+    fn debug(&self, diagnostic: &(dyn Diagnostic), f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render_report(f, diagnostic)
+    }
+}
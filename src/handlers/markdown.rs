@@ -0,0 +1,311 @@
+use std::fmt::{self, Write};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::handlers::{
+    cause_messages, grapheme_width, lossy_slice, snippet_lines, snippet_spans, Line,
+};
+use crate::protocol::{Diagnostic, Severity};
+use crate::{LabeledSpan, ReportHandler, SourceCode, SourceSpan};
+
+/**
+[`ReportHandler`] that renders a [`Diagnostic`] as GitHub-flavored Markdown,
+for posting diagnostics into PR comments, issue bodies and the like.
+
+The severity and code are rendered as a bold header, snippets are rendered
+as fenced code blocks with line numbers and `^^^` underlines, and help text
+is rendered as a block quote.
+*/
+#[derive(Debug, Clone)]
+pub struct MarkdownReportHandler {
+    context_lines: usize,
+    with_cause_chain: bool,
+    footer: Option<String>,
+}
+
+impl MarkdownReportHandler {
+    /// Create a new [`MarkdownReportHandler`].
+    pub fn new() -> Self {
+        Self {
+            footer: None,
+            context_lines: 1,
+            with_cause_chain: true,
+        }
+    }
+
+    /// Include the cause chain of the top-level error in the report, if
+    /// available.
+    pub fn with_cause_chain(mut self) -> Self {
+        self.with_cause_chain = true;
+        self
+    }
+
+    /// Do not include the cause chain of the top-level error in the report.
+    pub fn without_cause_chain(mut self) -> Self {
+        self.with_cause_chain = false;
+        self
+    }
+
+    /// Set the footer to be displayed at the end of the report.
+    pub fn with_footer(mut self, footer: String) -> Self {
+        self.footer = Some(footer);
+        self
+    }
+
+    /// Sets the number of lines of context to show around each error.
+    pub fn with_context_lines(mut self, lines: usize) -> Self {
+        self.context_lines = lines;
+        self
+    }
+}
+
+impl Default for MarkdownReportHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Backslash-escapes characters that would otherwise be interpreted as inline
+/// Markdown formatting.
+struct Escape<'a>(&'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            if matches!(
+                c,
+                '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+            ) {
+                f.write_char('\\')?;
+            }
+            f.write_char(c)?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(input: &'_ str) -> Escape<'_> {
+    Escape(input)
+}
+
+impl MarkdownReportHandler {
+    /// Render a [`Diagnostic`]. This function is mostly internal and meant to
+    /// be called by the toplevel [`ReportHandler`] handler, but is made public
+    /// to make it easier (possible) to test in isolation from global state.
+    pub fn render_report(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+    ) -> fmt::Result {
+        self.render_diagnostic(f, diagnostic, None, self.with_cause_chain)?;
+        if let Some(footer) = &self.footer {
+            writeln!(f)?;
+            writeln!(f, "{}", footer)?;
+        }
+        Ok(())
+    }
+
+    fn render_diagnostic(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+        parent_src: Option<&dyn SourceCode>,
+        with_cause_chain: bool,
+    ) -> fmt::Result {
+        self.render_header(f, diagnostic)?;
+        if with_cause_chain {
+            self.render_causes(f, diagnostic)?;
+        }
+        let src = diagnostic.source_code().or(parent_src);
+        self.render_snippets(f, diagnostic, src)?;
+        self.render_footer(f, diagnostic)?;
+        if let Some(related) = diagnostic.related() {
+            for rel in related {
+                writeln!(f)?;
+                self.render_diagnostic(f, rel, src, true)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn render_header(&self, f: &mut impl fmt::Write, diagnostic: &(dyn Diagnostic)) -> fmt::Result {
+        let severity = match diagnostic.severity() {
+            Some(Severity::Bug) => "bug",
+            Some(Severity::Error) | None => "error",
+            Some(Severity::Warning) => "warning",
+            Some(Severity::Advice) => "advice",
+            Some(Severity::Info) => "info",
+        };
+        write!(f, "**{}", severity)?;
+        if let Some(code) = diagnostic.code() {
+            write!(f, "\\[{}\\]", escape(&code.to_string()))?;
+        }
+        writeln!(f, "**: {}", escape(&diagnostic.to_string()))
+    }
+
+    fn render_causes(&self, f: &mut impl fmt::Write, diagnostic: &(dyn Diagnostic)) -> fmt::Result {
+        let causes = cause_messages(diagnostic);
+        if !causes.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Caused by:")?;
+            for error in causes {
+                writeln!(f, "- {}", escape(&error))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn render_footer(&self, f: &mut impl fmt::Write, diagnostic: &(dyn Diagnostic)) -> fmt::Result {
+        if let Some(note) = diagnostic.note() {
+            self.render_quote(f, "note", &note.to_string())?;
+        }
+        if let Some(help) = diagnostic.help() {
+            self.render_quote(f, "help", &help.to_string())?;
+        }
+        if let Some(url) = diagnostic.url() {
+            writeln!(f)?;
+            writeln!(f, "For more details, see <{}>", url)?;
+        }
+        Ok(())
+    }
+
+    fn render_quote(&self, f: &mut impl fmt::Write, kind: &str, text: &str) -> fmt::Result {
+        writeln!(f)?;
+        let mut lines = text.lines();
+        writeln!(
+            f,
+            "> **{}**: {}",
+            kind,
+            escape(lines.next().unwrap_or_default())
+        )?;
+        for line in lines {
+            writeln!(f, "> {}", escape(line))?;
+        }
+        Ok(())
+    }
+
+    fn render_snippets(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+        source_code: Option<&dyn SourceCode>,
+    ) -> fmt::Result {
+        let (source, labels) = match (source_code, diagnostic.labels()) {
+            (Some(source), Some(labels)) => (source, labels),
+            _ => return Ok(()),
+        };
+        let mut labels = labels.collect::<Vec<_>>();
+        labels.sort_unstable_by_key(|l| l.inner().offset());
+
+        let spans = snippet_spans(source, labels.iter().map(|l| l.inner()), self.context_lines)?;
+        for span in spans {
+            self.render_context(f, source, &span, &labels)?;
+        }
+        Ok(())
+    }
+
+    fn render_context(
+        &self,
+        f: &mut impl fmt::Write,
+        source: &dyn SourceCode,
+        context: &SourceSpan,
+        labels: &[LabeledSpan],
+    ) -> fmt::Result {
+        let (contents, lines) = snippet_lines(source, context, self.context_lines)?;
+        let linum_width = lines
+            .last()
+            .map(|line| line.line_number.to_string().len())
+            .unwrap_or(1);
+
+        // The fence has to be longer than any run of backticks in the snippet
+        // itself, or the snippet would end the code block early.
+        let fence_len = lines
+            .iter()
+            .flat_map(|line| line.text.split(|c| c != '`'))
+            .map(str::len)
+            .max()
+            .unwrap_or(0)
+            .max(2)
+            + 1;
+        let fence = "`".repeat(fence_len);
+
+        writeln!(f)?;
+        write!(f, "`")?;
+        if let Some(name) = contents.name() {
+            write!(f, "{}:", name)?;
+        }
+        writeln!(f, "{}:{}`", contents.line() + 1, contents.column() + 1)?;
+        writeln!(f)?;
+        writeln!(f, "{}", fence)?;
+        for line in &lines {
+            writeln!(
+                f,
+                "{:>width$} | {}",
+                line.line_number,
+                line.text,
+                width = linum_width
+            )?;
+            for label in labels {
+                self.render_underline(f, line, label, linum_width)?;
+            }
+        }
+        writeln!(f, "{}", fence)
+    }
+
+    /// Underlines the part of `line` covered by `label`, attaching the label
+    /// text to the last line the label covers.
+    fn render_underline(
+        &self,
+        f: &mut impl fmt::Write,
+        line: &Line,
+        label: &LabeledSpan,
+        linum_width: usize,
+    ) -> fmt::Result {
+        if !line.intersects(label.inner()) {
+            return Ok(());
+        }
+        let line_end = line.end();
+        let label_end = label.offset() + label.len();
+        let start = label.offset().max(line.offset) - line.offset;
+        let end = label_end.min(line_end) - line.offset;
+        // Keep tabs in the padding so the underline lines up no matter how
+        // wide the reader's tabs are.
+        let padding = lossy_slice(&line.source, 0, start)
+            .graphemes(true)
+            .map(|g| {
+                if g == "\t" {
+                    "\t".into()
+                } else {
+                    " ".repeat(grapheme_width(g))
+                }
+            })
+            .collect::<String>();
+        let width = lossy_slice(&line.source, start, end)
+            .graphemes(true)
+            .map(grapheme_width)
+            .sum::<usize>()
+            .max(1);
+        write!(
+            f,
+            "{:width$} | {}{}",
+            "",
+            padding,
+            "^".repeat(width),
+            width = linum_width
+        )?;
+        match label.label() {
+            Some(text) if label_end <= line_end + 1 => writeln!(f, " {}", text),
+            _ => writeln!(f),
+        }
+    }
+}
+
+impl ReportHandler for MarkdownReportHandler {
+    fn debug(&self, diagnostic: &(dyn Diagnostic), f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return fmt::Debug::fmt(diagnostic, f);
+        }
+
+        self.render_report(f, diagnostic)
+    }
+}
@@ -2,23 +2,279 @@
 Reporters included with `miette`.
 */
 
+#[allow(unreachable_pub)]
+pub use compact::*;
 #[allow(unreachable_pub)]
 pub use debug::*;
 #[allow(unreachable_pub)]
 #[cfg(feature = "fancy-no-backtrace")]
 pub use graphical::*;
 #[allow(unreachable_pub)]
+pub use html::*;
+#[allow(unreachable_pub)]
 pub use json::*;
 #[allow(unreachable_pub)]
+pub use junit::*;
+#[allow(unreachable_pub)]
+pub use markdown::*;
+#[allow(unreachable_pub)]
 pub use narratable::*;
 #[allow(unreachable_pub)]
+pub use ndjson::*;
+#[allow(unreachable_pub)]
+pub use quiet::*;
+#[allow(unreachable_pub)]
+pub use rustc::*;
+#[allow(unreachable_pub)]
+pub use sarif::*;
+#[allow(unreachable_pub)]
+pub use tap::*;
+#[allow(unreachable_pub)]
+pub use teamcity::*;
+#[allow(unreachable_pub)]
 #[cfg(feature = "fancy-no-backtrace")]
 pub use theme::*;
+#[allow(unreachable_pub)]
+pub use verbose::*;
 
+mod compact;
 mod debug;
 #[cfg(feature = "fancy-no-backtrace")]
 mod graphical;
+mod html;
 mod json;
+mod junit;
+mod markdown;
 mod narratable;
+mod ndjson;
+mod quiet;
+mod rustc;
+mod sarif;
+mod tap;
+mod teamcity;
 #[cfg(feature = "fancy-no-backtrace")]
 mod theme;
+mod verbose;
+
+/// Returns the display width of a single grapheme cluster.
+///
+/// Combining marks, emoji modifiers and zero-width joiners don't add to the
+/// width of the cluster they're a part of, so the cluster is as wide as its
+/// widest character rather than the sum of them.
+pub(crate) fn grapheme_width(grapheme: &str) -> usize {
+    use unicode_width::UnicodeWidthChar;
+
+    grapheme
+        .chars()
+        .map(|c| c.width().unwrap_or(0))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Decodes source code for display, one char at a time, along with the number
+/// of bytes of source each char stands for. Invalid UTF-8 sequences are
+/// decoded to U+FFFD rather than failing, while still counting as however
+/// many bytes they took up, so that offsets into the source stay accurate.
+pub(crate) fn lossy_chars(data: &[u8]) -> Vec<(char, usize)> {
+    let mut chars = Vec::with_capacity(data.len());
+    let mut rest = data;
+    while !rest.is_empty() {
+        let (valid, invalid_len) = match std::str::from_utf8(rest) {
+            Ok(valid) => (valid, 0),
+            Err(err) => {
+                let valid = std::str::from_utf8(&rest[..err.valid_up_to()]).unwrap_or_default();
+                let invalid_len = err
+                    .error_len()
+                    .unwrap_or_else(|| rest.len() - err.valid_up_to());
+                (valid, invalid_len)
+            }
+        };
+        chars.extend(valid.chars().map(|c| (c, c.len_utf8())));
+        if invalid_len > 0 {
+            chars.push((char::REPLACEMENT_CHARACTER, invalid_len));
+        }
+        rest = &rest[valid.len() + invalid_len..];
+    }
+    chars
+}
+
+/// Decodes the bytes of `data` between `start` and `end`, clamped to its
+/// length, replacing any invalid UTF-8 with U+FFFD.
+pub(crate) fn lossy_slice(data: &[u8], start: usize, end: usize) -> std::borrow::Cow<'_, str> {
+    let end = end.min(data.len());
+    String::from_utf8_lossy(&data[start.min(end)..end])
+}
+
+/// Groups spans, sorted by offset, into the spans of the snippets they're
+/// rendered in, merging spans whose context lines would otherwise overlap.
+pub(crate) fn snippet_spans<'s>(
+    source: &dyn crate::SourceCode,
+    spans: impl IntoIterator<Item = &'s crate::SourceSpan>,
+    context_lines: usize,
+) -> Result<Vec<crate::SourceSpan>, std::fmt::Error> {
+    let mut groups: Vec<(crate::SourceSpan, usize)> = Vec::new();
+    for span in spans {
+        let contents = source
+            .read_span(span, context_lines, context_lines)
+            .map_err(|_| std::fmt::Error)?;
+        let (first_line, last_line) = (contents.line(), contents.end_line());
+        match groups.last_mut() {
+            Some((group, group_last_line)) if *group_last_line >= first_line => {
+                let end = (group.offset() + group.len()).max(span.offset() + span.len());
+                *group = (group.offset(), end - group.offset()).into();
+                *group_last_line = (*group_last_line).max(last_line);
+            }
+            _ => groups.push((*span, last_line)),
+        }
+    }
+    Ok(groups.into_iter().map(|(span, _)| span).collect())
+}
+
+/// Reads the full lines `span` is on, along with `context_lines` lines of
+/// context on either side of them.
+pub(crate) fn snippet_lines<'a>(
+    source: &'a dyn crate::SourceCode,
+    span: &crate::SourceSpan,
+    context_lines: usize,
+) -> Result<(Box<dyn crate::SpanContents<'a> + 'a>, Vec<Line>), std::fmt::Error> {
+    // Without any context lines, `read_span` returns only the span itself
+    // rather than the full lines it covers, so we always read from the start
+    // of the first line and one line further than needed, and trim the excess
+    // afterwards.
+    let column = source
+        .read_span(span, 0, 0)
+        .map_err(|_| std::fmt::Error)?
+        .column();
+    let line_start = span.offset() - column.min(span.offset());
+    let full_span = (line_start, span.offset() - line_start + span.len()).into();
+    let contents = source
+        .read_span(&full_span, context_lines, context_lines + 1)
+        .map_err(|_| std::fmt::Error)?;
+    let mut offset = contents.span().offset();
+    let mut lines = Vec::new();
+    for (i, text) in contents.data().split_inclusive(|b| *b == b'\n').enumerate() {
+        let line_offset = offset;
+        offset += text.len();
+        let text = text.strip_suffix(b"\n").unwrap_or(text);
+        let text = text.strip_suffix(b"\r").unwrap_or(text);
+        lines.push(Line {
+            line_number: contents.line() + i + 1,
+            offset: line_offset,
+            source: text.to_vec(),
+            text: String::from_utf8_lossy(text).into_owned(),
+        });
+    }
+    let span_end = span.offset() + span.len().saturating_sub(1);
+    if let Some(last) = lines.iter().rposition(|line| line.offset <= span_end) {
+        lines.truncate(last + 1 + context_lines);
+    }
+    Ok((contents, lines))
+}
+
+/// A line of a snippet read by [`snippet_lines`].
+pub(crate) struct Line {
+    /// The 1-based number of the line.
+    pub(crate) line_number: usize,
+    pub(crate) offset: usize,
+    /// The line as it is in the source, without its line ending.
+    pub(crate) source: Vec<u8>,
+    /// The line decoded for display, with invalid UTF-8 replaced.
+    pub(crate) text: String,
+}
+
+impl Line {
+    /// The offset just past the end of the line, before its line ending.
+    pub(crate) fn end(&self) -> usize {
+        self.offset + self.source.len()
+    }
+
+    /// Whether `span` covers any of this line. Zero-length spans count as
+    /// covering it if they're anywhere from its start to its end.
+    pub(crate) fn intersects(&self, span: &crate::SourceSpan) -> bool {
+        if span.is_empty() {
+            span.offset() >= self.offset && span.offset() <= self.end()
+        } else {
+            span.offset() < self.end().max(self.offset + 1)
+                && span.offset() + span.len() > self.offset
+        }
+    }
+}
+
+/// Returns a diagnostic's primary label, or its first label if none of them is
+/// marked as primary.
+pub(crate) fn primary_label(diagnostic: &(dyn crate::Diagnostic)) -> Option<crate::LabeledSpan> {
+    let mut first = None;
+    for label in diagnostic.labels()? {
+        if label.primary() {
+            return Some(label);
+        }
+        if first.is_none() {
+            first = Some(label);
+        }
+    }
+    first
+}
+
+/// A 1-based line and column that a span starts at, along with the name of
+/// its source, if it has one. Columns count characters rather than bytes.
+pub(crate) struct Location {
+    pub(crate) name: Option<String>,
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+impl Location {
+    pub(crate) fn new(source: &dyn crate::SourceCode, span: &crate::SourceSpan) -> Option<Self> {
+        let contents = source.read_span(span, 0, 0).ok()?;
+        // `SpanContents::column()` counts bytes, so count characters instead.
+        let column = source
+            .visual_column(span, 1)
+            .unwrap_or_else(|_| contents.column());
+        Some(Self {
+            name: contents.name().map(String::from),
+            line: contents.line() + 1,
+            column: column + 1,
+        })
+    }
+}
+
+/// Returns the backtrace of a diagnostic, or failing that, the first one found
+/// in its chain of [`diagnostic_source`](crate::Diagnostic::diagnostic_source)s.
+pub(crate) fn find_backtrace<'a>(
+    diagnostic: &'a (dyn crate::Diagnostic + 'a),
+) -> Option<&'a crate::DiagnosticBacktrace> {
+    let mut next = Some(diagnostic);
+    while let Some(diagnostic) = next {
+        if let Some(backtrace) = diagnostic.backtrace() {
+            return Some(backtrace);
+        }
+        next = diagnostic.diagnostic_source();
+    }
+    None
+}
+
+/// Returns the messages in a diagnostic's cause chain.
+///
+/// Wrapping errors often produces a cause with the exact same message as the
+/// error wrapping it, so causes that repeat the message right before them
+/// (starting with the diagnostic's own) are left out.
+pub(crate) fn cause_messages(diagnostic: &(dyn crate::Diagnostic)) -> Vec<String> {
+    use crate::diagnostic_chain::DiagnosticChain;
+
+    let mut messages = Vec::new();
+    let mut previous = diagnostic.to_string();
+    if let Some(cause_iter) = diagnostic
+        .diagnostic_source()
+        .map(DiagnosticChain::from_diagnostic)
+        .or_else(|| diagnostic.source().map(DiagnosticChain::from_stderror))
+    {
+        for error in cause_iter {
+            let message = error.to_string();
+            if message != previous {
+                messages.push(message.clone());
+            }
+            previous = message;
+        }
+    }
+    messages
+}
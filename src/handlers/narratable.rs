@@ -1,8 +1,8 @@
 use std::fmt;
 
-use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use unicode_segmentation::UnicodeSegmentation;
 
-use crate::diagnostic_chain::DiagnosticChain;
+use crate::handlers::{cause_messages, find_backtrace, grapheme_width, lossy_chars};
 use crate::protocol::{Diagnostic, Severity};
 use crate::{LabeledSpan, MietteError, ReportHandler, SourceCode, SourceSpan, SpanContents};
 
@@ -16,6 +16,7 @@ pub struct NarratableReportHandler {
     context_lines: usize,
     with_cause_chain: bool,
     footer: Option<String>,
+    zero_based_line_numbers: bool,
 }
 
 impl NarratableReportHandler {
@@ -26,6 +27,7 @@ impl NarratableReportHandler {
             footer: None,
             context_lines: 1,
             with_cause_chain: true,
+            zero_based_line_numbers: false,
         }
     }
 
@@ -53,6 +55,13 @@ impl NarratableReportHandler {
         self.context_lines = lines;
         self
     }
+
+    /// Whether to narrate line and column numbers starting from 0 instead of
+    /// 1. Defaults to `false`.
+    pub fn with_zero_based_line_numbers(mut self, zero_based: bool) -> Self {
+        self.zero_based_line_numbers = zero_based;
+        self
+    }
 }
 
 impl Default for NarratableReportHandler {
@@ -88,38 +97,49 @@ impl NarratableReportHandler {
     fn render_header(&self, f: &mut impl fmt::Write, diagnostic: &(dyn Diagnostic)) -> fmt::Result {
         writeln!(f, "{}", diagnostic)?;
         let severity = match diagnostic.severity() {
+            Some(Severity::Bug) => "bug",
             Some(Severity::Error) | None => "error",
             Some(Severity::Warning) => "warning",
             Some(Severity::Advice) => "advice",
+            Some(Severity::Info) => "info",
         };
         writeln!(f, "    Diagnostic severity: {}", severity)?;
         Ok(())
     }
 
     fn render_causes(&self, f: &mut impl fmt::Write, diagnostic: &(dyn Diagnostic)) -> fmt::Result {
-        if let Some(cause_iter) = diagnostic
-            .diagnostic_source()
-            .map(DiagnosticChain::from_diagnostic)
-            .or_else(|| diagnostic.source().map(DiagnosticChain::from_stderror))
-        {
-            for error in cause_iter {
-                writeln!(f, "    Caused by: {}", error)?;
-            }
+        for error in cause_messages(diagnostic) {
+            writeln!(f, "    Caused by: {}", error)?;
         }
 
         Ok(())
     }
 
     fn render_footer(&self, f: &mut impl fmt::Write, diagnostic: &(dyn Diagnostic)) -> fmt::Result {
+        if let Some(expected) = diagnostic.expected() {
+            writeln!(f, "diagnostic expected: {}", expected)?;
+        }
+        if let Some(found) = diagnostic.found() {
+            writeln!(f, "diagnostic found: {}", found)?;
+        }
+        if let Some(note) = diagnostic.note() {
+            writeln!(f, "diagnostic note: {}", note)?;
+        }
         if let Some(help) = diagnostic.help() {
             writeln!(f, "diagnostic help: {}", help)?;
         }
+        if let Some(suggestion) = diagnostic.suggestion() {
+            writeln!(f, "diagnostic help: did you mean `{}`?", suggestion)?;
+        }
         if let Some(code) = diagnostic.code() {
             writeln!(f, "diagnostic code: {}", code)?;
         }
         if let Some(url) = diagnostic.url() {
             writeln!(f, "For more details, see {}", url)?;
         }
+        if let Some(backtrace) = find_backtrace(diagnostic) {
+            write!(f, "diagnostic backtrace:\n{}", backtrace)?;
+        }
         Ok(())
     }
 
@@ -132,10 +152,12 @@ impl NarratableReportHandler {
         if let Some(related) = diagnostic.related() {
             writeln!(f)?;
             for rel in related {
-                match diagnostic.severity() {
+                match rel.severity() {
+                    Some(Severity::Bug) => write!(f, "Bug: ")?,
                     Some(Severity::Error) | None => write!(f, "Error: ")?,
                     Some(Severity::Warning) => write!(f, "Warning: ")?,
                     Some(Severity::Advice) => write!(f, "Advice: ")?,
+                    Some(Severity::Info) => write!(f, "Info: ")?,
                 };
                 self.render_header(f, rel)?;
                 writeln!(f)?;
@@ -233,12 +255,13 @@ impl NarratableReportHandler {
         writeln!(
             f,
             " starting at line {}, column {}",
-            contents.line() + 1,
-            contents.column() + 1
+            self.display_number(contents.line() + 1),
+            self.display_number(contents.column() + 1)
         )?;
         writeln!(f)?;
         for line in &lines {
-            writeln!(f, "snippet line {}: {}", line.line_number, line.text)?;
+            let line_number = self.display_number(line.line_number);
+            writeln!(f, "snippet line {}: {}", line_number, line.text)?;
             let relevant = labels
                 .iter()
                 .filter_map(|l| line.span_attach(l.inner()).map(|a| (a, l)));
@@ -248,28 +271,33 @@ impl NarratableReportHandler {
                         write!(
                             f,
                             "    label at line {}, column {}",
-                            line.line_number, col_start,
+                            line_number,
+                            self.display_number(col_start),
                         )?;
                     }
                     SpanAttach::Contained { col_start, col_end } => {
                         write!(
                             f,
                             "    label at line {}, columns {} to {}",
-                            line.line_number, col_start, col_end,
+                            line_number,
+                            self.display_number(col_start),
+                            self.display_number(col_end),
                         )?;
                     }
                     SpanAttach::Starts { col_start } => {
                         write!(
                             f,
                             "    label starting at line {}, column {}",
-                            line.line_number, col_start,
+                            line_number,
+                            self.display_number(col_start),
                         )?;
                     }
                     SpanAttach::Ends { col_end } => {
                         write!(
                             f,
                             "    label ending at line {}, column {}",
-                            line.line_number, col_end,
+                            line_number,
+                            self.display_number(col_end),
                         )?;
                     }
                 }
@@ -282,6 +310,15 @@ impl NarratableReportHandler {
         Ok(())
     }
 
+    /// Converts a 1-based line or column number to the configured base.
+    fn display_number(&self, number: usize) -> usize {
+        if self.zero_based_line_numbers {
+            number.saturating_sub(1)
+        } else {
+            number
+        }
+    }
+
     fn get_lines<'a>(
         &'a self,
         source: &'a dyn SourceCode,
@@ -290,20 +327,19 @@ impl NarratableReportHandler {
         let context_data = source
             .read_span(context_span, self.context_lines, self.context_lines)
             .map_err(|_| fmt::Error)?;
-        let context = std::str::from_utf8(context_data.data()).expect("Bad utf8 detected");
         let mut line = context_data.line();
         let mut column = context_data.column();
         let mut offset = context_data.span().offset();
         let mut line_offset = offset;
-        let mut iter = context.chars().peekable();
+        let mut iter = lossy_chars(context_data.data()).into_iter().peekable();
         let mut line_str = String::new();
         let mut lines = Vec::new();
-        while let Some(char) = iter.next() {
-            offset += char.len_utf8();
+        while let Some((char, len)) = iter.next() {
+            offset += len;
             let mut at_end_of_file = false;
             match char {
                 '\r' => {
-                    if iter.next_if_eq(&'\n').is_some() {
+                    if iter.next_if(|&(c, _)| c == '\n').is_some() {
                         offset += 1;
                         line += 1;
                         column = 0;
@@ -373,16 +409,13 @@ enum SpanAttach {
 /// Returns column at offset, and nearest boundary if offset is in the middle of
 /// the character
 fn safe_get_column(text: &str, offset: usize, start: bool) -> usize {
-    let mut column = text.get(0..offset).map(|s| s.width()).unwrap_or_else(|| {
-        let mut column = 0;
-        for (idx, c) in text.char_indices() {
-            if offset <= idx {
-                break;
-            }
-            column += c.width().unwrap_or(0);
+    let mut column = 0;
+    for (idx, grapheme) in text.grapheme_indices(true) {
+        if offset <= idx {
+            break;
         }
-        column
-    });
+        column += grapheme_width(grapheme);
+    }
     if start {
         // Offset are zero-based, so plus one
         column += 1;
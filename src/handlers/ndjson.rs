@@ -0,0 +1,78 @@
+use std::io;
+
+use crate::{protocol::Diagnostic, JSONReportHandler};
+
+/**
+Streams diagnostics to a writer as [newline-delimited JSON](http://ndjson.org/),
+one object per line, for watch-mode and language-server-adjacent tools that
+need results as they come in rather than all at once.
+
+Each object has the same shape as the output of [`JSONReportHandler`]. The
+writer is flushed after every diagnostic, so that readers on the other end
+see it right away.
+
+## Example
+
+```
+use miette::{Diagnostic, NDJSONReporter};
+use thiserror::Error;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("oops!")]
+struct MyBad;
+
+let mut reporter = NDJSONReporter::new(Vec::new());
+reporter.report(&MyBad).unwrap();
+reporter.report(&MyBad).unwrap();
+let out = String::from_utf8(reporter.into_inner()).unwrap();
+assert_eq!(out.lines().count(), 2);
+```
+*/
+#[derive(Debug)]
+pub struct NDJSONReporter<W> {
+    writer: W,
+    handler: JSONReportHandler,
+}
+
+impl<W: io::Write> NDJSONReporter<W> {
+    /// Create a new [`NDJSONReporter`] writing into `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            handler: JSONReportHandler::new(),
+        }
+    }
+
+    /// Writes a [`Diagnostic`] out as a single line of JSON, and flushes the
+    /// writer.
+    pub fn report(&mut self, diagnostic: &(dyn Diagnostic)) -> io::Result<()> {
+        let mut line = String::new();
+        self.handler
+            .render_report(&mut line, diagnostic)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to render diagnostic"))?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.flush()
+    }
+
+    /// Writes any number of [`Diagnostic`]s out, one line each.
+    pub fn report_all<'a>(
+        &mut self,
+        diagnostics: impl IntoIterator<Item = &'a (dyn Diagnostic + 'a)>,
+    ) -> io::Result<()> {
+        for diagnostic in diagnostics {
+            self.report(diagnostic)?;
+        }
+        Ok(())
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
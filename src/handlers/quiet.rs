@@ -0,0 +1,75 @@
+use std::fmt;
+
+use crate::{protocol::Diagnostic, ReportHandler, Severity};
+
+/**
+[`ReportHandler`] that renders only the severity, code and message of a
+diagnostic, as `severity[code]: message`, for `--quiet` flags and for
+summarizing large numbers of diagnostics.
+
+Snippets, causes, help text and URLs are all left out. Messages spanning
+several lines are joined into one, and related diagnostics are rendered on
+lines of their own.
+*/
+#[derive(Debug, Clone)]
+pub struct QuietReportHandler;
+
+impl QuietReportHandler {
+    /// Create a new [`QuietReportHandler`]. There are no customization
+    /// options.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for QuietReportHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QuietReportHandler {
+    /// Render a [`Diagnostic`]. This function is mostly internal and meant to
+    /// be called by the toplevel [`ReportHandler`] handler, but is made public
+    /// to make it easier (possible) to test in isolation from global state.
+    pub fn render_report(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+    ) -> fmt::Result {
+        let severity = match diagnostic.severity() {
+            Some(Severity::Bug) => "bug",
+            Some(Severity::Error) | None => "error",
+            Some(Severity::Warning) => "warning",
+            Some(Severity::Advice) => "advice",
+            Some(Severity::Info) => "info",
+        };
+        write!(f, "{}", severity)?;
+        if let Some(code) = diagnostic.code() {
+            write!(f, "[{}]", code)?;
+        }
+        let message = diagnostic.to_string();
+        let mut lines = message.lines();
+        write!(f, ": {}", lines.next().unwrap_or_default())?;
+        for line in lines {
+            write!(f, " {}", line.trim_start())?;
+        }
+        if let Some(related) = diagnostic.related() {
+            for rel in related {
+                writeln!(f)?;
+                self.render_report(f, rel)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ReportHandler for QuietReportHandler {
+    fn debug(&self, diagnostic: &(dyn Diagnostic), f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return fmt::Debug::fmt(diagnostic, f);
+        }
+
+        self.render_report(f, diagnostic)
+    }
+}
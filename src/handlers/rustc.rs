@@ -0,0 +1,287 @@
+use std::fmt;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::handlers::{
+    cause_messages, grapheme_width, lossy_slice, snippet_lines, snippet_spans, Line,
+};
+use crate::protocol::{Diagnostic, Severity};
+use crate::{LabeledSpan, ReportHandler, SourceCode};
+
+/**
+[`ReportHandler`] that mimics rustc's diagnostic layout:
+
+```text
+error[E0308]: mismatched types
+ --> src/main.rs:3:18
+  |
+3 |     let x: i32 = "a";
+  |            --- expected due to this
+  |                  ^^^ expected `i32`, found `&str`
+  |
+  = help: ...
+```
+
+The primary label (or the first one, if no label is marked as primary) is
+underlined with `^`, while all other labels are underlined with `-`.
+[`Severity::Bug`] is rendered as `error`, and [`Severity::Advice`] and
+[`Severity::Info`] as `note`, so tools that already parse compiler output keep
+working.
+*/
+#[derive(Debug, Clone)]
+pub struct RustcReportHandler {
+    context_lines: usize,
+    with_cause_chain: bool,
+    footer: Option<String>,
+}
+
+impl RustcReportHandler {
+    /// Create a new [`RustcReportHandler`]. Like rustc, no context lines are
+    /// shown around labels by default.
+    pub fn new() -> Self {
+        Self {
+            footer: None,
+            context_lines: 0,
+            with_cause_chain: true,
+        }
+    }
+
+    /// Include the cause chain of the top-level error in the report, if
+    /// available.
+    pub fn with_cause_chain(mut self) -> Self {
+        self.with_cause_chain = true;
+        self
+    }
+
+    /// Do not include the cause chain of the top-level error in the report.
+    pub fn without_cause_chain(mut self) -> Self {
+        self.with_cause_chain = false;
+        self
+    }
+
+    /// Set the footer to be displayed at the end of the report.
+    pub fn with_footer(mut self, footer: String) -> Self {
+        self.footer = Some(footer);
+        self
+    }
+
+    /// Sets the number of lines of context to show around each error.
+    pub fn with_context_lines(mut self, lines: usize) -> Self {
+        self.context_lines = lines;
+        self
+    }
+}
+
+impl Default for RustcReportHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustcReportHandler {
+    /// Render a [`Diagnostic`]. This function is mostly internal and meant to
+    /// be called by the toplevel [`ReportHandler`] handler, but is made public
+    /// to make it easier (possible) to test in isolation from global state.
+    pub fn render_report(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+    ) -> fmt::Result {
+        self.render_diagnostic(f, diagnostic, None, self.with_cause_chain)?;
+        if let Some(footer) = &self.footer {
+            writeln!(f)?;
+            writeln!(f, "{}", footer)?;
+        }
+        Ok(())
+    }
+
+    fn render_diagnostic(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+        parent_src: Option<&dyn SourceCode>,
+        with_cause_chain: bool,
+    ) -> fmt::Result {
+        let severity = match diagnostic.severity() {
+            Some(Severity::Bug | Severity::Error) | None => "error",
+            Some(Severity::Warning) => "warning",
+            Some(Severity::Advice | Severity::Info) => "note",
+        };
+        write!(f, "{}", severity)?;
+        if let Some(code) = diagnostic.code() {
+            write!(f, "[{}]", code)?;
+        }
+        writeln!(f, ": {}", diagnostic)?;
+
+        let src = diagnostic.source_code().or(parent_src);
+        let linum_width = self.render_snippets(f, diagnostic, src)?;
+
+        let mut notes = Vec::new();
+        if with_cause_chain {
+            for error in cause_messages(diagnostic) {
+                notes.push(format!("note: caused by: {}", error));
+            }
+        }
+        if let Some(note) = diagnostic.note() {
+            notes.push(format!("note: {}", note));
+        }
+        if let Some(help) = diagnostic.help() {
+            notes.push(format!("help: {}", help));
+        }
+        if let Some(url) = diagnostic.url() {
+            notes.push(format!("note: for more information, see <{}>", url));
+        }
+        if !notes.is_empty() {
+            writeln!(f, "{:width$} |", "", width = linum_width)?;
+            for note in notes {
+                let mut lines = note.lines();
+                writeln!(
+                    f,
+                    "{:width$} = {}",
+                    "",
+                    lines.next().unwrap_or_default(),
+                    width = linum_width
+                )?;
+                for line in lines {
+                    writeln!(f, "{:width$}   {}", "", line, width = linum_width)?;
+                }
+            }
+        }
+
+        if let Some(related) = diagnostic.related() {
+            for rel in related {
+                writeln!(f)?;
+                self.render_diagnostic(f, rel, src, true)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders all the labels of a diagnostic as a single snippet, and
+    /// returns the width of the line number gutter so notes can be lined up
+    /// with it.
+    fn render_snippets(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+        source_code: Option<&dyn SourceCode>,
+    ) -> Result<usize, fmt::Error> {
+        let (source, labels) = match (source_code, diagnostic.labels()) {
+            (Some(source), Some(labels)) => (source, labels),
+            _ => return Ok(1),
+        };
+        let labels = labels.collect::<Vec<_>>();
+        let primary_idx = labels.iter().position(|label| label.primary()).unwrap_or(0);
+        let labels = labels
+            .into_iter()
+            .enumerate()
+            .map(|(i, label)| (i == primary_idx, label))
+            .collect::<Vec<_>>();
+        let primary = match labels.get(primary_idx) {
+            Some((_, label)) => *label.inner(),
+            None => return Ok(1),
+        };
+        let mut labels = labels;
+        labels.sort_unstable_by_key(|(_, l)| l.inner().offset());
+
+        let spans = snippet_spans(
+            source,
+            labels.iter().map(|(_, label)| label.inner()),
+            self.context_lines,
+        )?;
+        let mut snippets = Vec::new();
+        for span in spans {
+            snippets.push(snippet_lines(source, &span, self.context_lines)?.1);
+        }
+        let linum_width = snippets
+            .iter()
+            .filter_map(|lines| lines.last())
+            .map(|line| line.line_number.to_string().len())
+            .max()
+            .unwrap_or(1);
+
+        let contents = source.read_span(&primary, 0, 0).map_err(|_| fmt::Error)?;
+        write!(f, "{:width$}--> ", "", width = linum_width)?;
+        if let Some(name) = contents.name() {
+            write!(f, "{}:", name)?;
+        }
+        writeln!(
+            f,
+            "{}:{}",
+            contents.line() + 1,
+            source
+                .visual_column(&primary, 1)
+                .unwrap_or_else(|_| contents.column())
+                + 1
+        )?;
+        writeln!(f, "{:width$} |", "", width = linum_width)?;
+        for (i, lines) in snippets.iter().enumerate() {
+            if i > 0 {
+                writeln!(f, "...")?;
+            }
+            for line in lines {
+                writeln!(
+                    f,
+                    "{:>width$} | {}",
+                    line.line_number,
+                    line.text.replace('\t', "    "),
+                    width = linum_width
+                )?;
+                for (is_primary, label) in &labels {
+                    self.render_underline(f, line, label, *is_primary, linum_width)?;
+                }
+            }
+        }
+        Ok(linum_width)
+    }
+
+    /// Underlines the part of `line` covered by `label`, attaching the label
+    /// text to the last line the label covers.
+    fn render_underline(
+        &self,
+        f: &mut impl fmt::Write,
+        line: &Line,
+        label: &LabeledSpan,
+        is_primary: bool,
+        linum_width: usize,
+    ) -> fmt::Result {
+        if !line.intersects(label.inner()) {
+            return Ok(());
+        }
+        let line_end = line.end();
+        let label_end = label.offset() + label.len();
+        let start = label.offset().max(line.offset) - line.offset;
+        let end = label_end.min(line_end) - line.offset;
+        let width = |s: &str| {
+            s.graphemes(true)
+                .map(|g| if g == "\t" { 4 } else { grapheme_width(g) })
+                .sum()
+        };
+        let padding: usize = width(&lossy_slice(&line.source, 0, start));
+        let len: usize = width(&lossy_slice(&line.source, start, end));
+        let marker = if is_primary { "^" } else { "-" };
+        write!(
+            f,
+            "{:width$} | {:padding$}{}",
+            "",
+            "",
+            marker.repeat(len.max(1)),
+            width = linum_width,
+            padding = padding
+        )?;
+        match label.label() {
+            Some(text) if label_end <= line_end + 1 => writeln!(f, " {}", text),
+            _ => writeln!(f),
+        }
+    }
+}
+
+impl ReportHandler for RustcReportHandler {
+    fn debug(&self, diagnostic: &(dyn Diagnostic), f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return fmt::Debug::fmt(diagnostic, f);
+        }
+
+        self.render_report(f, diagnostic)
+    }
+}
@@ -0,0 +1,284 @@
+use std::fmt;
+
+use crate::{
+    handlers::json::escape, protocol::Diagnostic, ReportHandler, Severity, SourceCode, SourceSpan,
+};
+
+/**
+[`ReportHandler`] that renders [SARIF
+2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+logs, for consumption by GitHub code scanning and other static analysis
+tooling.
+
+[`Diagnostic::code`] is used as the `ruleId`, [`Diagnostic::severity`] is
+mapped to the SARIF `level`, and each label becomes a physical location with
+a line/column region. [`Diagnostic::tags`] are listed in the result's
+`properties`.
+*/
+#[derive(Debug, Clone)]
+pub struct SarifReportHandler {
+    tool_name: String,
+    tool_version: Option<String>,
+}
+
+impl SarifReportHandler {
+    /// Create a new [`SarifReportHandler`]. The tool name defaults to
+    /// `miette`, which you will most likely want to override with
+    /// [`SarifReportHandler::with_tool_name`].
+    pub fn new() -> Self {
+        Self {
+            tool_name: "miette".into(),
+            tool_version: None,
+        }
+    }
+
+    /// Sets the name of the tool that produced the results.
+    pub fn with_tool_name(mut self, name: impl Into<String>) -> Self {
+        self.tool_name = name.into();
+        self
+    }
+
+    /// Sets the version of the tool that produced the results.
+    pub fn with_tool_version(mut self, version: impl Into<String>) -> Self {
+        self.tool_version = Some(version.into());
+        self
+    }
+}
+
+impl Default for SarifReportHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SarifReportHandler {
+    /// Render a [`Diagnostic`] as a complete SARIF log containing a single
+    /// result. This function is mostly internal and meant to be called by the
+    /// toplevel [`ReportHandler`] handler, but is made public to make it easier
+    /// (possible) to test in isolation from global state.
+    pub fn render_report(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+    ) -> fmt::Result {
+        self.render_reports(f, std::iter::once(diagnostic))
+    }
+
+    /// Render any number of [`Diagnostic`]s as a single SARIF log, with one
+    /// run containing a result for each of them.
+    pub fn render_reports<'a>(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostics: impl IntoIterator<Item = &'a (dyn Diagnostic + 'a)>,
+    ) -> fmt::Result {
+        let diagnostics = diagnostics.into_iter().collect::<Vec<_>>();
+        write!(
+            f,
+            r#"{{"$schema": "https://json.schemastore.org/sarif-2.1.0.json","version": "2.1.0","runs": [{{"#
+        )?;
+        write!(
+            f,
+            r#""tool": {{"driver": {{"name": "{}","#,
+            escape(&self.tool_name)
+        )?;
+        if let Some(version) = &self.tool_version {
+            write!(f, r#""version": "{}","#, escape(version))?;
+        }
+        self.render_rules(f, &diagnostics)?;
+        write!(f, r#"}}}},"columnKind": "unicodeCodePoints","results": ["#)?;
+        let mut add_comma = false;
+        for diagnostic in diagnostics {
+            self.render_result(f, diagnostic, None, &mut add_comma)?;
+        }
+        write!(f, "]}}]}}")
+    }
+
+    fn render_rules(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostics: &[&dyn Diagnostic],
+    ) -> fmt::Result {
+        fn collect<'a>(
+            diagnostic: &'a dyn Diagnostic,
+            rules: &mut Vec<(String, &'a dyn Diagnostic)>,
+        ) {
+            if let Some(code) = diagnostic.code() {
+                let code = code.to_string();
+                if !rules.iter().any(|(id, _)| *id == code) {
+                    rules.push((code, diagnostic));
+                }
+            }
+            if let Some(related) = diagnostic.related() {
+                for rel in related {
+                    collect(rel, rules);
+                }
+            }
+        }
+
+        let mut rules = Vec::new();
+        for diagnostic in diagnostics {
+            collect(*diagnostic, &mut rules);
+        }
+        write!(f, r#""rules": ["#)?;
+        let mut add_comma = false;
+        for (id, diagnostic) in rules {
+            if add_comma {
+                write!(f, ",")?;
+            } else {
+                add_comma = true;
+            }
+            write!(f, r#"{{"id": "{}""#, escape(&id))?;
+            if let Some(url) = diagnostic.url() {
+                write!(f, r#","helpUri": "{}""#, escape(&url.to_string()))?;
+            }
+            if let Some(help) = diagnostic.help() {
+                write!(f, r#","help": {{"text": "{}"}}"#, escape(&help.to_string()))?;
+            }
+            write!(f, "}}")?;
+        }
+        write!(f, "]")
+    }
+
+    fn render_result(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+        parent_src: Option<&dyn SourceCode>,
+        add_comma: &mut bool,
+    ) -> fmt::Result {
+        if *add_comma {
+            write!(f, ",")?;
+        } else {
+            *add_comma = true;
+        }
+        write!(f, "{{")?;
+        if let Some(code) = diagnostic.code() {
+            write!(f, r#""ruleId": "{}","#, escape(&code.to_string()))?;
+        }
+        let level = match diagnostic.severity() {
+            Some(Severity::Bug | Severity::Error) | None => "error",
+            Some(Severity::Warning) => "warning",
+            Some(Severity::Advice | Severity::Info) => "note",
+        };
+        write!(f, r#""level": "{}","#, level)?;
+        write!(
+            f,
+            r#""message": {{"text": "{}"}}"#,
+            escape(&diagnostic.to_string())
+        )?;
+        let src = diagnostic.source_code().or(parent_src);
+        let mut labels = diagnostic
+            .labels()
+            .map(|labels| labels.collect::<Vec<_>>())
+            .unwrap_or_default();
+        if let Some(idx) = labels.iter().position(|label| label.primary()) {
+            let primary = labels.remove(idx);
+            labels.insert(0, primary);
+        }
+        if let Some((first, rest)) = labels.split_first() {
+            write!(f, r#","locations": ["#)?;
+            self.render_location(f, src, first.inner(), None, None)?;
+            write!(f, "]")?;
+            if !rest.is_empty() {
+                write!(f, r#","relatedLocations": ["#)?;
+                for (id, label) in rest.iter().enumerate() {
+                    if id > 0 {
+                        write!(f, ",")?;
+                    }
+                    self.render_location(f, src, label.inner(), Some(id), label.label())?;
+                }
+                write!(f, "]")?;
+            }
+        }
+        if let Some(tags) = diagnostic.tags() {
+            let tags = tags.map(|tag| format!(r#""{}""#, tag)).collect::<Vec<_>>();
+            write!(f, r#","properties": {{"tags": [{}]}}"#, tags.join(","))?;
+        }
+        write!(f, "}}")?;
+        if let Some(related) = diagnostic.related() {
+            for rel in related {
+                self.render_result(f, rel, src, add_comma)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn render_location(
+        &self,
+        f: &mut impl fmt::Write,
+        source: Option<&dyn SourceCode>,
+        span: &SourceSpan,
+        id: Option<usize>,
+        message: Option<&str>,
+    ) -> fmt::Result {
+        write!(f, "{{")?;
+        if let Some(id) = id {
+            write!(f, r#""id": {},"#, id)?;
+        }
+        write!(f, r#""physicalLocation": {{"#)?;
+        let name = source
+            .and_then(|source| source.read_span(span, 0, 0).ok())
+            .and_then(|contents| contents.name().map(String::from));
+        if let Some(name) = name {
+            write!(f, r#""artifactLocation": {{"uri": "{}"}},"#, escape(&name))?;
+        }
+        write!(f, r#""region": {{"#)?;
+        if let Some(region) = source.and_then(|source| Region::new(source, span)) {
+            write!(
+                f,
+                r#""startLine": {},"startColumn": {},"endLine": {},"endColumn": {},"#,
+                region.start_line, region.start_column, region.end_line, region.end_column
+            )?;
+        }
+        write!(
+            f,
+            r#""byteOffset": {},"byteLength": {}}}}}"#,
+            span.offset(),
+            span.len()
+        )?;
+        if let Some(message) = message {
+            write!(f, r#","message": {{"text": "{}"}}"#, escape(message))?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// 1-based SARIF region, using unicode code points for columns. The end
+/// column is exclusive, as required by the SARIF spec.
+struct Region {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+impl Region {
+    fn new(source: &dyn SourceCode, span: &SourceSpan) -> Option<Self> {
+        // `SpanContents::column()` counts bytes, so we re-read the span from
+        // the start of its line to be able to count characters instead.
+        let contents = source.read_span(span, 0, 0).ok()?;
+        let start_line = contents.line() + 1;
+        let line_start = span.offset().checked_sub(contents.column())?;
+        let prefix_len = span.offset() - line_start;
+        let full_span: SourceSpan = (line_start, prefix_len + span.len()).into();
+        let contents = source.read_span(&full_span, 0, 0).ok()?;
+        let data = contents.data();
+        let prefix = data.get(..prefix_len)?;
+        // The data starts at column 0, so its end column is also how many
+        // bytes of its last line there are.
+        let last_line = data.get(data.len().checked_sub(contents.end_column())?..)?;
+
+        Some(Self {
+            start_line,
+            start_column: String::from_utf8_lossy(prefix).chars().count() + 1,
+            end_line: contents.end_line() + 1,
+            end_column: String::from_utf8_lossy(last_line).chars().count() + 1,
+        })
+    }
+}
+
+impl ReportHandler for SarifReportHandler {
+    fn debug(&self, diagnostic: &(dyn Diagnostic), f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render_report(f, diagnostic)
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt;
+
+use crate::{
+    handlers::{json::escape, primary_label, Location},
+    protocol::Diagnostic,
+    ReportHandler, Severity, SourceCode,
+};
+
+/**
+[`ReportHandler`] that renders diagnostics as [Test Anything
+Protocol](https://testanything.org/tap-version-13-specification.html) output,
+for integrating miette-based checkers into TAP harnesses.
+
+Every diagnostic, including related ones, is a test point. Errors and bugs
+are `not ok`, while warnings, advice and info are `ok`. Each test point is
+followed by a YAML block with the diagnostic's message, severity, code,
+location (taken from its primary label) and help text. The plan comes last, so
+that output can be streamed.
+*/
+#[derive(Debug, Clone)]
+pub struct TapReportHandler;
+
+impl TapReportHandler {
+    /// Create a new [`TapReportHandler`]. There are no customization options.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TapReportHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TapReportHandler {
+    /// Render a [`Diagnostic`] as a complete TAP stream. This function is
+    /// mostly internal and meant to be called by the toplevel
+    /// [`ReportHandler`] handler, but is made public to make it easier
+    /// (possible) to test in isolation from global state.
+    pub fn render_report(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+    ) -> fmt::Result {
+        self.render_reports(f, std::iter::once(diagnostic))
+    }
+
+    /// Render any number of [`Diagnostic`]s as a single TAP stream.
+    pub fn render_reports<'a>(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostics: impl IntoIterator<Item = &'a (dyn Diagnostic + 'a)>,
+    ) -> fmt::Result {
+        writeln!(f, "TAP version 13")?;
+        let mut count = 0;
+        for diagnostic in diagnostics {
+            self.render_test_point(f, diagnostic, None, &mut count)?;
+        }
+        writeln!(f, "1..{}", count)
+    }
+
+    fn render_test_point(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+        parent_src: Option<&dyn SourceCode>,
+        count: &mut usize,
+    ) -> fmt::Result {
+        *count += 1;
+        let (status, severity) = match diagnostic.severity() {
+            Some(Severity::Bug) => ("not ok", "bug"),
+            Some(Severity::Error) | None => ("not ok", "error"),
+            Some(Severity::Warning) => ("ok", "warning"),
+            Some(Severity::Advice) => ("ok", "advice"),
+            Some(Severity::Info) => ("ok", "info"),
+        };
+        let message = diagnostic.to_string();
+        let description = message
+            .lines()
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join(" ")
+            .replace('#', "\\#");
+        writeln!(f, "{} {} - {}", status, count, description)?;
+
+        // JSON strings are valid double-quoted YAML scalars.
+        writeln!(f, "  ---")?;
+        writeln!(f, "  message: \"{}\"", escape(&message))?;
+        writeln!(f, "  severity: {}", severity)?;
+        if let Some(code) = diagnostic.code() {
+            writeln!(f, "  code: \"{}\"", escape(&code.to_string()))?;
+        }
+        let src = diagnostic.source_code().or(parent_src);
+        let location =
+            primary_label(diagnostic).and_then(|label| Location::new(src?, label.inner()));
+        if let Some(location) = location {
+            if let Some(name) = &location.name {
+                writeln!(f, "  file: \"{}\"", escape(name))?;
+            }
+            writeln!(f, "  line: {}", location.line)?;
+            writeln!(f, "  column: {}", location.column)?;
+        }
+        if let Some(help) = diagnostic.help() {
+            writeln!(f, "  help: \"{}\"", escape(&help.to_string()))?;
+        }
+        writeln!(f, "  ...")?;
+
+        if let Some(related) = diagnostic.related() {
+            for rel in related {
+                self.render_test_point(f, rel, src, count)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ReportHandler for TapReportHandler {
+    fn debug(&self, diagnostic: &(dyn Diagnostic), f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render_report(f, diagnostic)
+    }
+}
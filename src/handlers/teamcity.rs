@@ -0,0 +1,186 @@
+use std::fmt;
+
+use crate::{handlers::primary_label, protocol::Diagnostic, ReportHandler, Severity, SourceCode};
+
+/**
+[`ReportHandler`] that renders diagnostics as [TeamCity service
+messages](https://www.jetbrains.com/help/teamcity/service-messages.html), so
+that miette-based linters can report inspections and build problems when run
+on TeamCity.
+
+Every diagnostic becomes an `inspection`, declared by an `inspectionType` whose
+id is the [`Diagnostic::code`]. [`Diagnostic::severity`] is mapped to the
+inspection's `SEVERITY`, and its file and line are taken from the primary
+label. Error and bug diagnostics are additionally reported as a `buildProblem`, which
+fails the build, unless disabled with
+[`TeamCityReportHandler::with_build_problems`].
+
+Each service message is written on a line of its own.
+*/
+#[derive(Debug, Clone)]
+pub struct TeamCityReportHandler {
+    category: String,
+    build_problems: bool,
+}
+
+impl TeamCityReportHandler {
+    /// Create a new [`TeamCityReportHandler`]. The inspection category
+    /// defaults to `miette`, which you will most likely want to override with
+    /// [`TeamCityReportHandler::with_category`].
+    pub fn new() -> Self {
+        Self {
+            category: "miette".into(),
+            build_problems: true,
+        }
+    }
+
+    /// Sets the category that inspection types are grouped under. This is
+    /// also used as the inspection type id of diagnostics without a code.
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.category = category.into();
+        self
+    }
+
+    /// Whether to report error diagnostics as build problems, failing the
+    /// build. Defaults to `true`.
+    pub fn with_build_problems(mut self, build_problems: bool) -> Self {
+        self.build_problems = build_problems;
+        self
+    }
+}
+
+impl Default for TeamCityReportHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TeamCityReportHandler {
+    /// Render a [`Diagnostic`]. This function is mostly internal and meant to
+    /// be called by the toplevel [`ReportHandler`] handler, but is made public
+    /// to make it easier (possible) to test in isolation from global state.
+    pub fn render_report(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+    ) -> fmt::Result {
+        self.render_reports(f, std::iter::once(diagnostic))
+    }
+
+    /// Render any number of [`Diagnostic`]s, declaring each inspection type
+    /// only once.
+    pub fn render_reports<'a>(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostics: impl IntoIterator<Item = &'a (dyn Diagnostic + 'a)>,
+    ) -> fmt::Result {
+        let mut declared = Vec::new();
+        for diagnostic in diagnostics {
+            self.render_diagnostic(f, diagnostic, None, &mut declared)?;
+        }
+        Ok(())
+    }
+
+    fn render_diagnostic(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+        parent_src: Option<&dyn SourceCode>,
+        declared: &mut Vec<String>,
+    ) -> fmt::Result {
+        let type_id = diagnostic
+            .code()
+            .map(|code| code.to_string())
+            .unwrap_or_else(|| self.category.clone());
+        if !declared.contains(&type_id) {
+            let description = diagnostic
+                .help()
+                .map(|help| help.to_string())
+                .unwrap_or_else(|| type_id.clone());
+            writeln!(
+                f,
+                "##teamcity[inspectionType id='{}' name='{}' category='{}' description='{}']",
+                escape(&type_id),
+                escape(&type_id),
+                escape(&self.category),
+                escape(&description)
+            )?;
+            declared.push(type_id.clone());
+        }
+
+        let message = diagnostic.to_string();
+        write!(
+            f,
+            "##teamcity[inspection typeId='{}' message='{}'",
+            escape(&type_id),
+            escape(&message)
+        )?;
+        let src = diagnostic.source_code().or(parent_src);
+        let contents =
+            primary_label(diagnostic).and_then(|label| src?.read_span(label.inner(), 0, 0).ok());
+        match contents.as_ref().and_then(|contents| contents.name()) {
+            Some(name) => write!(f, " file='{}'", escape(name))?,
+            // `file` is a required attribute.
+            None => write!(f, " file=''")?,
+        }
+        if let Some(contents) = &contents {
+            write!(f, " line='{}'", contents.line() + 1)?;
+        }
+        let severity = match diagnostic.severity() {
+            Some(Severity::Bug | Severity::Error) | None => "ERROR",
+            Some(Severity::Warning) => "WARNING",
+            Some(Severity::Advice) => "WEAK WARNING",
+            Some(Severity::Info) => "INFO",
+        };
+        writeln!(f, " SEVERITY='{}']", severity)?;
+
+        let is_error = matches!(
+            diagnostic.severity(),
+            Some(Severity::Bug | Severity::Error) | None
+        );
+        if self.build_problems && is_error {
+            let description = match diagnostic.code() {
+                Some(code) => format!("{}: {}", code, message),
+                None => message,
+            };
+            writeln!(
+                f,
+                "##teamcity[buildProblem description='{}']",
+                escape(&description)
+            )?;
+        }
+
+        if let Some(related) = diagnostic.related() {
+            for rel in related {
+                self.render_diagnostic(f, rel, src, declared)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escapes a value for use inside a TeamCity service message attribute.
+fn escape(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '|' => output.push_str("||"),
+            '\'' => output.push_str("|'"),
+            '\n' => output.push_str("|n"),
+            '\r' => output.push_str("|r"),
+            '[' => output.push_str("|["),
+            ']' => output.push_str("|]"),
+            '\u{0085}' => output.push_str("|x"),
+            '\u{2028}' => output.push_str("|l"),
+            '\u{2029}' => output.push_str("|p"),
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+impl ReportHandler for TeamCityReportHandler {
+    fn debug(&self, diagnostic: &(dyn Diagnostic), f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render_report(f, diagnostic)
+    }
+}
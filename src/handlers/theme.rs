@@ -62,10 +62,12 @@ impl GraphicalTheme {
 
 impl Default for GraphicalTheme {
     fn default() -> Self {
-        match std::env::var("NO_COLOR") {
-            _ if !atty::is(Stream::Stdout) || !atty::is(Stream::Stderr) => Self::ascii(),
-            Ok(string) if string != "0" => Self::unicode_nocolor(),
-            _ => Self::unicode(),
+        let tty = atty::is(Stream::Stdout) && atty::is(Stream::Stderr);
+        match crate::handler::env_color() {
+            Some(false) if tty => Self::unicode_nocolor(),
+            Some(false) => Self::none(),
+            _ if tty => Self::unicode(),
+            _ => Self::ascii(),
         }
     }
 }
@@ -75,18 +77,36 @@ Styles for various parts of graphical rendering for the [crate::GraphicalReportH
 */
 #[derive(Debug, Clone)]
 pub struct ThemeStyles {
+    /// Style to apply to things highlighted as "bug".
+    pub bug: Style,
     /// Style to apply to things highlighted as "error".
     pub error: Style,
     /// Style to apply to things highlighted as "warning".
     pub warning: Style,
     /// Style to apply to things highlighted as "advice".
     pub advice: Style,
+    /// Style to apply to things highlighted as "info".
+    pub info: Style,
     /// Style to apply to the help text.
     pub help: Style,
+    /// Style to apply to the `note:` prefix of notes.
+    pub note: Style,
+    /// Style to apply to the suggested text in `did you mean` help.
+    pub suggestion: Style,
     /// Style to apply to filenames/links/URLs.
     pub link: Style,
     /// Style to apply to line numbers.
     pub linum: Style,
+    /// Style to apply to lines removed by a suggested fix.
+    pub removed: Style,
+    /// Style to apply to lines added by a suggested fix.
+    pub added: Style,
+    /// Style to apply to label text of diagnostics tagged as
+    /// [`DiagnosticTag::Unnecessary`](crate::DiagnosticTag::Unnecessary).
+    pub unnecessary: Style,
+    /// Style to apply to label text of diagnostics tagged as
+    /// [`DiagnosticTag::Deprecated`](crate::DiagnosticTag::Deprecated).
+    pub deprecated: Style,
     /// Styles to cycle through (using `.iter().cycle()`), to render the lines
     /// and text for diagnostic highlights.
     pub highlights: Vec<Style>,
@@ -101,12 +121,20 @@ impl ThemeStyles {
     /// [Credit](http://terminal.sexy/#FRUV0NDQFRUVrEFCkKlZ9L91ap-1qnWfdbWq0NDQUFBQrEFCkKlZ9L91ap-1qnWfdbWq9fX1).
     pub fn rgb() -> Self {
         Self {
+            bug: style().fg_rgb::<255, 30, 30>().bold(),
             error: style().fg_rgb::<255, 30, 30>(),
             warning: style().fg_rgb::<244, 191, 117>(),
             advice: style().fg_rgb::<106, 159, 181>(),
+            info: style().fg_rgb::<170, 170, 170>(),
             help: style().fg_rgb::<106, 159, 181>(),
+            note: style().bold(),
+            suggestion: style().fg_rgb::<145, 246, 111>().bold(),
             link: style().fg_rgb::<92, 157, 255>().underline().bold(),
             linum: style().dimmed(),
+            removed: style().fg_rgb::<255, 30, 30>(),
+            added: style().fg_rgb::<145, 246, 111>(),
+            unnecessary: style().dimmed(),
+            deprecated: style().strikethrough(),
             highlights: vec![
                 style().fg_rgb::<246, 87, 248>(),
                 style().fg_rgb::<30, 201, 212>(),
@@ -118,12 +146,20 @@ impl ThemeStyles {
     /// ANSI color-based styles.
     pub fn ansi() -> Self {
         Self {
+            bug: style().red().bold(),
             error: style().red(),
             warning: style().yellow(),
             advice: style().cyan(),
+            info: style().blue(),
             help: style().cyan(),
+            note: style().bold(),
+            suggestion: style().green().bold(),
             link: style().cyan().underline().bold(),
             linum: style().dimmed(),
+            removed: style().red(),
+            added: style().green(),
+            unnecessary: style().dimmed(),
+            deprecated: style().strikethrough(),
             highlights: vec![
                 style().magenta().bold(),
                 style().yellow().bold(),
@@ -135,12 +171,20 @@ impl ThemeStyles {
     /// No styling. Just regular ol' monochrome.
     pub fn none() -> Self {
         Self {
+            bug: style(),
             error: style(),
             warning: style(),
             advice: style(),
+            info: style(),
             help: style(),
+            note: style(),
+            suggestion: style(),
             link: style(),
             linum: style(),
+            removed: style(),
+            added: style(),
+            unnecessary: style(),
+            deprecated: style(),
             highlights: vec![style()],
         }
     }
@@ -179,9 +223,11 @@ pub struct ThemeCharacters {
     pub underbar: char,
     pub underline: char,
 
+    pub bug: String,
     pub error: String,
     pub warning: String,
     pub advice: String,
+    pub info: String,
 }
 
 impl ThemeCharacters {
@@ -206,9 +252,11 @@ impl ThemeCharacters {
             rcross: '┤',
             underbar: '┬',
             underline: '─',
+            bug: "‼".into(),
             error: "×".into(),
             warning: "⚠".into(),
             advice: "☞".into(),
+            info: "ℹ".into(),
         }
     }
 
@@ -233,9 +281,11 @@ impl ThemeCharacters {
             rcross: '┤',
             underbar: '┬',
             underline: '─',
+            bug: "🐛".into(),
             error: "💥".into(),
             warning: "⚠️".into(),
             advice: "💡".into(),
+            info: "ℹ️".into(),
         }
     }
     /// ASCII-art-based graphical elements. Works well on older terminals.
@@ -259,9 +309,11 @@ impl ThemeCharacters {
             rcross: '|',
             underbar: '|',
             underline: '^',
+            bug: "X".into(),
             error: "x".into(),
             warning: "!".into(),
             advice: ">".into(),
+            info: "i".into(),
         }
     }
 }
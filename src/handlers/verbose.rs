@@ -0,0 +1,260 @@
+use std::fmt;
+
+use crate::{
+    diagnostic_chain::{DiagnosticChain, ErrorKind},
+    handlers::{find_backtrace, Location},
+    protocol::Diagnostic,
+    ReportHandler, Severity, SourceCode, SourceSpan,
+};
+
+/**
+[`ReportHandler`] that renders everything the [`Diagnostic`] protocol exposes,
+for bug reports and for debugging diagnostic definitions themselves.
+
+Alongside the message, this lists the severity, code, URL, tags and metadata
+of a diagnostic, every label with its location, byte offsets and the source
+lines it covers, every suggested fix with its applicability, its expected
+and found values, its note, help and suggestion, and its full cause chain,
+including the codes of causes that are diagnostics themselves, and its
+backtrace. Unlike other handlers, repeated cause messages aren't collapsed.
+Related diagnostics are rendered the same way, indented under their parent.
+*/
+#[derive(Debug, Clone)]
+pub struct VerboseReportHandler;
+
+impl VerboseReportHandler {
+    /// Create a new [`VerboseReportHandler`]. There are no customization
+    /// options.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for VerboseReportHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VerboseReportHandler {
+    /// Render a [`Diagnostic`]. This function is mostly internal and meant to
+    /// be called by the toplevel [`ReportHandler`] handler, but is made public
+    /// to make it easier (possible) to test in isolation from global state.
+    pub fn render_report(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+    ) -> fmt::Result {
+        self.render_diagnostic(f, diagnostic, None, "")
+    }
+
+    fn render_diagnostic(
+        &self,
+        f: &mut impl fmt::Write,
+        diagnostic: &(dyn Diagnostic),
+        parent_src: Option<&dyn SourceCode>,
+        indent: &str,
+    ) -> fmt::Result {
+        let severity = match diagnostic.severity() {
+            Some(Severity::Bug) => "bug",
+            Some(Severity::Error) | None => "error",
+            Some(Severity::Warning) => "warning",
+            Some(Severity::Advice) => "advice",
+            Some(Severity::Info) => "info",
+        };
+        write!(f, "{}{}", indent, severity)?;
+        if let Some(code) = diagnostic.code() {
+            write!(f, "[{}]", code)?;
+        }
+        self.render_text(f, ": ", &diagnostic.to_string(), indent)?;
+
+        let indent = format!("{}  ", indent);
+        match diagnostic.severity() {
+            Some(severity) => writeln!(f, "{}severity: {:?}", indent, severity)?,
+            None => writeln!(f, "{}severity: Error (default)", indent)?,
+        }
+        if let Some(code) = diagnostic.code() {
+            writeln!(f, "{}code: {}", indent, code)?;
+        }
+        if let Some(url) = diagnostic.url() {
+            writeln!(f, "{}url: {}", indent, url)?;
+        }
+        if let Some(tags) = diagnostic.tags() {
+            let tags = tags.map(|tag| tag.to_string()).collect::<Vec<_>>();
+            writeln!(f, "{}tags: {}", indent, tags.join(", "))?;
+        }
+        if let Some(metadata) = diagnostic.metadata() {
+            for (key, value) in metadata.iter() {
+                writeln!(f, "{}metadata: {} = {:?}", indent, key, value)?;
+            }
+        }
+
+        let src = diagnostic.source_code().or(parent_src);
+        if let Some(labels) = diagnostic.labels() {
+            for label in labels {
+                write!(f, "{}label", indent)?;
+                if let Some(text) = label.label() {
+                    write!(f, " {:?}", text)?;
+                }
+                self.render_span(f, src, label.inner(), &indent)?;
+            }
+        }
+        if let Some(fixes) = diagnostic.fixes() {
+            for fix in fixes {
+                write!(
+                    f,
+                    "{}fix {:?} ({})",
+                    indent,
+                    fix.replacement(),
+                    fix.applicability()
+                )?;
+                self.render_span(f, src, fix.span(), &indent)?;
+            }
+        }
+
+        if let Some(expected) = diagnostic.expected() {
+            self.render_text(
+                f,
+                &format!("{}expected: ", indent),
+                &expected.to_string(),
+                &indent,
+            )?;
+        }
+        if let Some(found) = diagnostic.found() {
+            self.render_text(
+                f,
+                &format!("{}found: ", indent),
+                &found.to_string(),
+                &indent,
+            )?;
+        }
+        if let Some(note) = diagnostic.note() {
+            self.render_text(f, &format!("{}note: ", indent), &note.to_string(), &indent)?;
+        }
+        if let Some(help) = diagnostic.help() {
+            self.render_text(f, &format!("{}help: ", indent), &help.to_string(), &indent)?;
+        }
+        if let Some(suggestion) = diagnostic.suggestion() {
+            writeln!(f, "{}suggestion: {}", indent, suggestion)?;
+        }
+
+        let causes = diagnostic
+            .diagnostic_source()
+            .map(DiagnosticChain::from_diagnostic)
+            .or_else(|| diagnostic.source().map(DiagnosticChain::from_stderror));
+        if let Some(causes) = causes {
+            writeln!(f, "{}caused by:", indent)?;
+            for (idx, cause) in causes.enumerate() {
+                write!(f, "{}  {}: ", indent, idx)?;
+                if let ErrorKind::Diagnostic(cause) = &cause {
+                    if let Some(code) = cause.code() {
+                        write!(f, "[{}] ", code)?;
+                    }
+                }
+                self.render_text(f, "", &cause.to_string(), &format!("{}     ", indent))?;
+            }
+        }
+
+        if let Some(backtrace) = find_backtrace(diagnostic) {
+            writeln!(f, "{}backtrace:", indent)?;
+            for line in backtrace.to_string().lines() {
+                writeln!(f, "{}  {}", indent, line)?;
+            }
+        }
+
+        if let Some(related) = diagnostic.related() {
+            writeln!(f, "{}related:", indent)?;
+            for rel in related {
+                self.render_diagnostic(f, rel, src, &format!("{}  ", indent))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes possibly multi-line text after a prefix, indenting every line
+    /// but the first.
+    fn render_text(
+        &self,
+        f: &mut impl fmt::Write,
+        prefix: &str,
+        text: &str,
+        indent: &str,
+    ) -> fmt::Result {
+        let mut lines = text.lines();
+        writeln!(f, "{}{}", prefix, lines.next().unwrap_or_default())?;
+        for line in lines {
+            writeln!(f, "{}  {}", indent, line)?;
+        }
+        Ok(())
+    }
+
+    /// Writes where a span is, followed by the source lines it covers.
+    fn render_span(
+        &self,
+        f: &mut impl fmt::Write,
+        source: Option<&dyn SourceCode>,
+        span: &SourceSpan,
+        indent: &str,
+    ) -> fmt::Result {
+        let location = source.and_then(|source| Location::new(source, span));
+        if let Some(location) = &location {
+            write!(f, " at ")?;
+            if let Some(name) = &location.name {
+                write!(f, "{}:", name)?;
+            }
+            write!(f, "{}:{}", location.line, location.column)?;
+        }
+        writeln!(
+            f,
+            " (bytes {}..{})",
+            span.offset(),
+            span.offset() + span.len()
+        )?;
+
+        let (source, location) = match (source, location) {
+            (Some(source), Some(location)) => (source, location),
+            _ => return Ok(()),
+        };
+        // Reading with a line of context on either side gets us whole lines,
+        // which are then trimmed down to the ones the span covers.
+        let contents = source.read_span(span, 1, 1).map_err(|_| fmt::Error)?;
+        let data = String::from_utf8_lossy(contents.data());
+        let start = span
+            .offset()
+            .saturating_sub(contents.span().offset())
+            .min(data.len());
+        let end = (start + span.len()).min(data.len());
+        let line_start = data
+            .get(..start)
+            .and_then(|before| before.rfind('\n'))
+            .map_or(0, |idx| idx + 1);
+        let line_end = data
+            .get(end..)
+            .and_then(|after| after.find('\n'))
+            .map_or(data.len(), |idx| end + idx);
+        let lines = data.get(line_start..line_end).unwrap_or_default();
+        let last_line = location.line + lines.lines().count().saturating_sub(1);
+        let width = last_line.to_string().len();
+        for (idx, line) in lines.lines().enumerate() {
+            writeln!(
+                f,
+                "{}  {:>width$} | {}",
+                indent,
+                location.line + idx,
+                line,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl ReportHandler for VerboseReportHandler {
+    fn debug(&self, diagnostic: &(dyn Diagnostic), f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return fmt::Debug::fmt(diagnostic, f);
+        }
+
+        self.render_report(f, diagnostic)
+    }
+}
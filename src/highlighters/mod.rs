@@ -0,0 +1,113 @@
+/*!
+Syntax highlighters for source code snippets rendered by
+[`GraphicalReportHandler`](crate::GraphicalReportHandler).
+
+A [`Highlighter`] is handed the [`SpanContents`] of a snippet and hands back a
+[`HighlighterState`], which is then fed every line of the snippet in order.
+This lets stateful highlighters (such as `syntect`) keep track of things like
+multi-line comments and strings.
+
+With the `syntect-highlighter` feature enabled, [`SyntectHighlighter`] is
+used by default by [`MietteHandlerOpts`](crate::MietteHandlerOpts) whenever
+colors are enabled.
+*/
+
+use std::{fmt, ops::Deref, sync::Arc};
+
+use owo_colors::Styled;
+
+use crate::SpanContents;
+
+#[cfg(feature = "syntect-highlighter")]
+pub use self::syntect::*;
+pub use blank::*;
+
+mod blank;
+#[cfg(feature = "syntect-highlighter")]
+mod syntect;
+
+/// A syntax highlighter for highlighting miette [`SourceCode`](crate::SourceCode) snippets.
+pub trait Highlighter {
+    /// Creates a new [`HighlighterState`] to begin parsing and highlighting
+    /// a [`SpanContents`].
+    ///
+    /// The [`GraphicalReportHandler`](crate::GraphicalReportHandler) will call
+    /// this method at the start of rendering a [`SpanContents`].
+    ///
+    /// The [`SpanContents`] is provided as input only so that the
+    /// [`Highlighter`] can detect language syntax and make other
+    /// initialization decisions prior to highlighting, but it is not intended
+    /// that the Highlighter begin highlighting at this point. The returned
+    /// [`HighlighterState`] is responsible for the actual rendering.
+    fn start_highlighter_state<'h>(
+        &'h self,
+        source: &dyn SpanContents<'_>,
+    ) -> Box<dyn HighlighterState + 'h>;
+}
+
+/// A stateful highlighter that incrementally highlights lines of a particular
+/// source code.
+///
+/// The [`GraphicalReportHandler`](crate::GraphicalReportHandler)
+/// will create a highlighter state by calling
+/// [`start_highlighter_state`](Highlighter::start_highlighter_state) at the
+/// start of rendering, then it will iteratively call
+/// [`highlight_line`](HighlighterState::highlight_line) to render individual
+/// highlighted lines. This allows [`Highlighter`] implementations to maintain
+/// mutable parsing and highlighting state.
+pub trait HighlighterState {
+    /// Highlight an individual line from the source code by returning a vector
+    /// of [`Styled`] regions. The returned regions must cover the whole line,
+    /// in order.
+    fn highlight_line<'s>(&mut self, line: &'s str) -> Vec<Styled<&'s str>>;
+}
+
+/// Arcified trait object for Highlighter. Used internally by
+/// [`GraphicalReportHandler`](crate::GraphicalReportHandler).
+///
+/// Wrapping the trait object in this way allows us to implement `Debug` and
+/// `Clone`.
+#[derive(Clone)]
+#[repr(transparent)]
+pub(crate) struct MietteHighlighter(Arc<dyn Highlighter + Send + Sync>);
+
+impl MietteHighlighter {
+    pub(crate) fn nocolor() -> Self {
+        Self::from(BlankHighlighter)
+    }
+
+    #[cfg(feature = "syntect-highlighter")]
+    pub(crate) fn syntect_truecolor() -> Self {
+        Self::from(SyntectHighlighter::default())
+    }
+}
+
+impl Default for MietteHighlighter {
+    #[cfg(feature = "syntect-highlighter")]
+    fn default() -> Self {
+        MietteHighlighter::syntect_truecolor()
+    }
+    #[cfg(not(feature = "syntect-highlighter"))]
+    fn default() -> Self {
+        MietteHighlighter::nocolor()
+    }
+}
+
+impl<T: Highlighter + Send + Sync + 'static> From<T> for MietteHighlighter {
+    fn from(value: T) -> Self {
+        Self(Arc::new(value))
+    }
+}
+
+impl fmt::Debug for MietteHighlighter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MietteHighlighter(...)")
+    }
+}
+
+impl Deref for MietteHighlighter {
+    type Target = dyn Highlighter + Send + Sync;
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
@@ -0,0 +1,142 @@
+use std::path::Path;
+
+use owo_colors::{Style, Styled};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{FontStyle, Style as SyntectStyle, Theme, ThemeSet},
+    parsing::{SyntaxReference, SyntaxSet},
+};
+
+use crate::SpanContents;
+
+use super::{BlankHighlighterState, Highlighter, HighlighterState};
+
+/// Highlights miette [`SourceCode`](crate::SourceCode) snippets with the
+/// [syntect](https://docs.rs/syntect/latest/syntect/) highlighting library.
+///
+/// The syntax is picked from the snippet's
+/// [`language`](SpanContents::language) if there is one, and otherwise from
+/// the extension of its [`name`](SpanContents::name) or from hints in its
+/// first line (such as shebangs). Snippets whose syntax can't be detected are
+/// left unhighlighted.
+///
+/// Syntect highlighting always uses 24-bit color.
+#[derive(Debug, Clone)]
+pub struct SyntectHighlighter {
+    theme: Theme,
+    syntax_set: SyntaxSet,
+    use_bg_color: bool,
+}
+
+impl Default for SyntectHighlighter {
+    fn default() -> Self {
+        let theme_set = ThemeSet::load_defaults();
+        let theme = theme_set.themes["base16-eighties.dark"].clone();
+        Self::new_themed(theme, false)
+    }
+}
+
+impl Highlighter for SyntectHighlighter {
+    fn start_highlighter_state<'h>(
+        &'h self,
+        source: &dyn SpanContents<'_>,
+    ) -> Box<dyn HighlighterState + 'h> {
+        match self.detect_syntax(source) {
+            Some(syntax) => Box::new(SyntectHighlighterState {
+                syntax_set: &self.syntax_set,
+                highlighter: HighlightLines::new(syntax, &self.theme),
+                use_bg_color: self.use_bg_color,
+            }),
+            None => Box::new(BlankHighlighterState),
+        }
+    }
+}
+
+impl SyntectHighlighter {
+    /// Create a syntect highlighter with the given theme and syntax set.
+    pub fn new(syntax_set: SyntaxSet, theme: Theme, use_bg_color: bool) -> Self {
+        Self {
+            theme,
+            syntax_set,
+            use_bg_color,
+        }
+    }
+
+    /// Create a syntect highlighter with the given theme and the default
+    /// syntax set.
+    pub fn new_themed(theme: Theme, use_bg_color: bool) -> Self {
+        Self::new(SyntaxSet::load_defaults_nonewlines(), theme, use_bg_color)
+    }
+
+    /// Determine the syntect [`SyntaxReference`] to use
+    /// for the given source code.
+    fn detect_syntax(&self, contents: &dyn SpanContents<'_>) -> Option<&SyntaxReference> {
+        // use language if given
+        if let Some(language) = contents.language() {
+            let syntax = self
+                .syntax_set
+                .find_syntax_by_name(language)
+                .or_else(|| self.syntax_set.find_syntax_by_token(language));
+            if syntax.is_some() {
+                return syntax;
+            }
+        }
+        // otherwise try to use any file extension provided in the name
+        if let Some(name) = contents.name() {
+            if let Some(ext) = Path::new(name).extension() {
+                let syntax = self
+                    .syntax_set
+                    .find_syntax_by_extension(ext.to_string_lossy().as_ref());
+                if syntax.is_some() {
+                    return syntax;
+                }
+            }
+        }
+        // finally, attempt to guess syntax based on the first line
+        std::str::from_utf8(contents.data())
+            .ok()
+            .and_then(|text| text.lines().next())
+            .and_then(|line| self.syntax_set.find_syntax_by_first_line(line))
+    }
+}
+
+/// Stateful highlighting iterator for [`SyntectHighlighter`].
+pub(crate) struct SyntectHighlighterState<'h> {
+    syntax_set: &'h SyntaxSet,
+    highlighter: HighlightLines<'h>,
+    use_bg_color: bool,
+}
+
+impl HighlighterState for SyntectHighlighterState<'_> {
+    fn highlight_line<'s>(&mut self, line: &'s str) -> Vec<Styled<&'s str>> {
+        if let Ok(ranges) = self.highlighter.highlight_line(line, self.syntax_set) {
+            ranges
+                .into_iter()
+                .map(|(style, str)| convert_style(style, self.use_bg_color).style(str))
+                .collect()
+        } else {
+            vec![Style::default().style(line)]
+        }
+    }
+}
+
+/// Convert syntect [`Style`](SyntectStyle) into an owo_colors [`Style`].
+fn convert_style(syntect_style: SyntectStyle, use_bg_color: bool) -> Style {
+    let fg = syntect_style.foreground;
+    let mut style = Style::new().truecolor(fg.r, fg.g, fg.b);
+    if use_bg_color {
+        let bg = syntect_style.background;
+        style = style.on_truecolor(bg.r, bg.g, bg.b);
+    }
+    let font_style = syntect_style.font_style;
+    if font_style.contains(FontStyle::BOLD) {
+        style = style.bold();
+    }
+    if font_style.contains(FontStyle::ITALIC) {
+        style = style.italic();
+    }
+    if font_style.contains(FontStyle::UNDERLINE) {
+        style = style.underline();
+    }
+    style
+}
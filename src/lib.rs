@@ -46,6 +46,9 @@
 //!   - [... multiple related errors](#-multiple-related-errors)
 //!   - [... delayed source code](#-delayed-source-code)
 //!   - [... handler options](#-handler-options)
+//!   - [... syntax highlighting](#-syntax-highlighting)
+//!   - [... binary sources](#-binary-sources)
+//!   - [... comparing sources](#-comparing-sources)
 //! - [Acknowledgements](#acknowledgements)
 //! - [License](#license)
 //!
@@ -60,7 +63,13 @@
 //!   types [`Result`], [`Report`] and the [`miette!`] macro for the
 //!   `anyhow!`/`eyre!` macros.
 //! - Generic support for arbitrary [`SourceCode`]s for snippet data, with
-//!   default support for `String`s included.
+//!   default support for `String`s and files included, and for memory-mapped
+//!   files with the `mmap` feature.
+//! - Snippets fetched asynchronously from an [`AsyncSource`], such as a database
+//!   or object storage, with the `async` feature.
+//! - [`SourceSpan`]s from `proc_macro2::Span`s, for diagnostics about Rust
+//!   source parsed outside of a procedural macro, such as in tests and build
+//!   scripts, with the `proc-macro` feature.
 //!
 //! The `miette` crate also comes bundled with a default [`ReportHandler`] with
 //! the following features:
@@ -374,6 +383,11 @@
 //!     // with or without label text
 //!     #[label]
 //!     snip4: Option<SourceSpan>,
+//!
+//!     // Handlers that only point at one location, like the compact handler,
+//!     // use the primary label, or the first one if none is marked.
+//!     #[label(primary, "and this is why")]
+//!     snip5: SourceSpan,
 //! }
 //! ```
 //!
@@ -413,6 +427,38 @@
 //! };
 //! ```
 //!
+//! If what you have to offer is a replacement for something the user wrote,
+//! such as a similarly-named identifier, use `#[suggestion]` instead. It's
+//! rendered as ``help: did you mean `...`?``, with the suggested text styled on
+//! its own:
+//!
+//! ```rust
+//! use miette::Diagnostic;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Diagnostic, Error)]
+//! #[error("unknown variable `{name}`")]
+//! struct UnknownVariable {
+//!     name: String,
+//!     #[suggestion]
+//!     candidate: Option<String>,
+//! }
+//! ```
+//!
+//! Notes, for advisory context rather than actionable advice, are supplied the
+//! same way as help text, either with `#[diagnostic(note(...))]` or a `#[note]`
+//! field. They're rendered under a `note:` prefix of their own, before any help.
+//!
+//! Assertion-style diagnostics, such as failed tests, can carry the values that
+//! were expected and found, with `#[diagnostic(expected(...), found(...))]` or
+//! `#[expected]` and `#[found]` fields, instead of spelling them out in the
+//! message. The graphical handler renders them aligned with each other.
+//!
+//! Diagnostics about unused or deprecated code can be tagged with
+//! `#[diagnostic(tags(unnecessary))]` or `#[diagnostic(tags(deprecated))]`, so
+//! that editors can gray out or strike through the code they point at. The
+//! graphical handler styles their labels the same way.
+//!
 //! ### ... multiple related errors
 //!
 //! `miette` supports collecting multiple errors into a single diagnostic, and
@@ -575,6 +621,52 @@
 //! See the docs for [`MietteHandlerOpts`] for more details on what you can
 //! customize!
 //!
+//! ### ... syntax highlighting
+//!
+//! With the `syntect-highlighter` crate feature enabled, source code snippets
+//! are syntax highlighted by [`MietteHandler`] whenever colors are enabled. The
+//! language is detected from the file extension of a [`NamedSource`]'s name, or
+//! can be given explicitly:
+//!
+//! ```rust,ignore
+//! let src = NamedSource::new("config", source).with_language("TOML");
+//! ```
+//!
+//! You can also plug in your own highlighter by implementing
+//! [`highlighters::Highlighter`] and handing it to
+//! `MietteHandlerOpts::with_syntax_highlighting()`, or turn highlighting off
+//! with `MietteHandlerOpts::without_syntax_highlighting()`.
+//!
+//! ### ... binary sources
+//!
+//! With binary data as the source code, such as the input to a parser for a
+//! binary file format, [`GraphicalReportHandler`] renders snippets as a hexdump
+//! instead, with the bytes each label covers underlined. This happens when the
+//! source's [`SpanContents`] say it [is binary](SpanContents::is_binary), or when
+//! a snippet isn't valid UTF-8 and has control characters in it:
+//!
+//! ```rust,ignore
+//! let src = NamedSource::new("a.out", bytes).with_binary(true);
+//! ```
+//!
+//! ### ... comparing sources
+//!
+//! With two sources to compare, such as an expected and an actual file, or two
+//! versions of a config, a diagnostic can return a [`SourceComparison`] of them
+//! with pairs of spans that correspond to one another. [`GraphicalReportHandler`]
+//! renders a snippet of each source for every pair, side by side when there's
+//! room and one above the other otherwise, with both spans highlighted in the
+//! same color:
+//!
+//! ```rust,ignore
+//! #[derive(Debug, Diagnostic, Error)]
+//! #[error("config doesn't match the schema")]
+//! struct Mismatch {
+//!     #[comparison]
+//!     comparison: SourceComparison,
+//! }
+//! ```
+//!
 //! ## Acknowledgements
 //!
 //! `miette` was not developed in a void. It owes enormous credit to various
@@ -604,27 +696,63 @@
 //! [`ariadne`](https://github.com/zesterer/ariadne), which is MIT licensed.
 pub use miette_derive::*;
 
+#[cfg(feature = "async")]
+pub use async_source::*;
+pub use code_registry::*;
+pub use diagnostic_backtrace::*;
+pub use diagnostic_key::*;
+pub use diagnostic_metadata::*;
 pub use error::*;
 pub use eyreish::*;
+pub use file_source::*;
 #[cfg(feature = "fancy-no-backtrace")]
 pub use handler::*;
 pub use handlers::*;
+pub use line_source::*;
+pub use miette_diagnostic::*;
+#[cfg(feature = "mmap")]
+pub use mmap_source::*;
 pub use named_source::*;
 #[cfg(feature = "fancy")]
 pub use panic::*;
 pub use protocol::*;
+pub use source_comparison::*;
+pub use source_map::*;
+pub use source_resolver::*;
+pub use streaming_source::*;
+pub use summary::*;
 
+#[cfg(feature = "async")]
+mod async_source;
 mod chain;
+mod code_registry;
+mod diagnostic_backtrace;
 mod diagnostic_chain;
+mod diagnostic_key;
+mod diagnostic_metadata;
 mod error;
 mod eyreish;
+mod file_source;
+mod file_windows;
 #[cfg(feature = "fancy-no-backtrace")]
 mod handler;
 mod handlers;
+#[cfg(feature = "fancy-no-backtrace")]
+pub mod highlighters;
+mod line_source;
 #[doc(hidden)]
 pub mod macro_helpers;
+mod miette_diagnostic;
+#[cfg(feature = "mmap")]
+mod mmap_source;
 mod named_source;
 #[cfg(feature = "fancy")]
 mod panic;
 mod protocol;
+mod snippet_cache;
+mod source_comparison;
 mod source_impls;
+mod source_map;
+mod source_resolver;
+mod streaming_source;
+mod summary;
@@ -0,0 +1,130 @@
+use std::fmt;
+
+use crate::snippet_cache::SnippetCache;
+use crate::{MietteError, MietteSpanContents, SourceCode, SourceSpan, SpanContents};
+
+/**
+[`SourceCode`] over input that has already been split into lines, such as the
+`Vec<String>` many parsers keep around, so that it doesn't have to be joined
+back together into one `String` to render snippets of it.
+
+Offsets into it are those of the lines joined together, with each one followed
+by a `\n`, so the first character of the second line is at the length of the
+first line plus one. The lines themselves shouldn't contain line endings.
+
+[`SpanContents`] borrow their data from their source as one contiguous slice,
+so reading a span joins together just the lines it and its context are on,
+and keeps them in memory until the `LineSource` is dropped. Reading lines
+that have already been joined borrows them again rather than keeping another
+copy.
+
+## Example
+
+```
+use miette::{miette, LineSource};
+
+let lines = vec!["fn main() {".to_string(), "    oops()".to_string(), "}".to_string()];
+let report = miette!("oops!").with_source_code(LineSource::new(lines));
+```
+*/
+pub struct LineSource<T> {
+    lines: Vec<T>,
+    line_starts: Vec<usize>,
+    snippets: SnippetCache,
+}
+
+impl<T: AsRef<str>> LineSource<T> {
+    /// Create a new [`LineSource`] from a list of lines, without their line
+    /// endings.
+    pub fn new(lines: impl Into<Vec<T>>) -> Self {
+        let lines = lines.into();
+        let mut line_starts = Vec::with_capacity(lines.len());
+        let mut offset = 0;
+        for line in &lines {
+            line_starts.push(offset);
+            offset += line.as_ref().len() + 1;
+        }
+        Self {
+            lines,
+            line_starts,
+            snippets: SnippetCache::default(),
+        }
+    }
+
+    /// Returns the lines of the source.
+    pub fn lines(&self) -> &[T] {
+        &self.lines
+    }
+
+    /// Returns the 0-indexed line that `offset` is on.
+    fn line_of(&self, offset: usize) -> usize {
+        self.line_starts
+            .partition_point(|start| *start <= offset)
+            .saturating_sub(1)
+    }
+}
+
+impl<T> fmt::Debug for LineSource<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LineSource")
+            .field("lines", &self.lines.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: AsRef<str> + Send + Sync> SourceCode for LineSource<T> {
+    fn read_span<'a>(
+        &'a self,
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        if self.lines.is_empty() {
+            return <[u8] as SourceCode>::read_span(
+                &[],
+                span,
+                context_lines_before,
+                context_lines_after,
+            );
+        }
+        let first = self
+            .line_of(span.offset())
+            .saturating_sub(context_lines_before);
+        // A couple of lines past the context after the span are joined too,
+        // since it's trimmed down to exactly what was asked for below.
+        let last = self.line_of(span.offset() + span.len().saturating_sub(1));
+        let last = (last + context_lines_after + 2).min(self.lines.len() - 1);
+
+        let mut window = Vec::new();
+        let mut start = self.line_starts[first];
+        let mut start_line = first;
+        if context_lines_before == 0 && start > 0 {
+            // Include the line ending before the span's line, so that empty
+            // spans at the start of a line are read the same as they would be
+            // from one joined string.
+            window.push(b'\n');
+            start -= 1;
+            start_line -= 1;
+        }
+        for line in &self.lines[first..=last] {
+            window.extend_from_slice(line.as_ref().as_bytes());
+            window.push(b'\n');
+        }
+
+        let window = self.snippets.keep(start, window);
+        let relative_span = (span.offset() - start.min(span.offset()), span.len()).into();
+        let contents = <[u8] as SourceCode>::read_span(
+            window,
+            &relative_span,
+            context_lines_before,
+            context_lines_after,
+        )?;
+        Ok(Box::new(MietteSpanContents::new(
+            contents.data(),
+            (start + contents.span().offset(), contents.span().len()).into(),
+            start_line + contents.line(),
+            contents.column(),
+            start_line + contents.line_count(),
+        )))
+    }
+}
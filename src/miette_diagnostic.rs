@@ -0,0 +1,177 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display},
+    sync::Arc,
+};
+
+use crate::{Diagnostic, LabeledSpan, Severity, SourceCode};
+
+/**
+A [`Diagnostic`] built at runtime, for when diagnostics come from somewhere
+other than your own code, such as config files, scripts or network
+responses, and defining a type for each of them isn't an option.
+
+## Example
+
+```
+use miette::{LabeledSpan, MietteDiagnostic, Report, Severity};
+
+let diag = MietteDiagnostic::new("unknown key `colour`")
+    .with_code("config::unknown_key")
+    .with_severity(Severity::Warning)
+    .with_help("did you mean `color`?")
+    .with_label(LabeledSpan::new_with_span(Some("this key".into()), (0, 6)))
+    .with_source_code("colour = \"red\"");
+let report = Report::new(diag);
+assert_eq!(report.to_string(), "unknown key `colour`");
+```
+*/
+#[derive(Clone)]
+pub struct MietteDiagnostic {
+    message: String,
+    code: Option<String>,
+    severity: Option<Severity>,
+    help: Option<String>,
+    url: Option<String>,
+    labels: Vec<LabeledSpan>,
+    source_code: Option<Arc<dyn SourceCode + 'static>>,
+}
+
+impl MietteDiagnostic {
+    /// Create a new [`MietteDiagnostic`] with the given message, and nothing
+    /// else.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            code: None,
+            severity: None,
+            help: None,
+            url: None,
+            labels: Vec::new(),
+            source_code: None,
+        }
+    }
+
+    /// Sets the message, which is what the diagnostic displays as.
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Sets the [`Diagnostic::code`].
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// Sets the [`Diagnostic::severity`].
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = Some(severity);
+        self
+    }
+
+    /// Sets the [`Diagnostic::help`] text.
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Sets the [`Diagnostic::url`].
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Adds a label. Labels are rendered in the order they're added.
+    pub fn with_label(mut self, label: LabeledSpan) -> Self {
+        self.labels.push(label);
+        self
+    }
+
+    /// Adds any number of labels.
+    pub fn with_labels(mut self, labels: impl IntoIterator<Item = LabeledSpan>) -> Self {
+        self.labels.extend(labels);
+        self
+    }
+
+    /// Sets the [`Diagnostic::source_code`] that labels point into.
+    pub fn with_source_code(mut self, source_code: impl SourceCode + 'static) -> Self {
+        self.source_code = Some(Arc::new(source_code));
+        self
+    }
+
+    /// Sets the [`Diagnostic::source_code`] that labels point into, sharing
+    /// it with any other diagnostics about the same source.
+    pub fn with_shared_source_code(mut self, source_code: Arc<dyn SourceCode + 'static>) -> Self {
+        self.source_code = Some(source_code);
+        self
+    }
+
+    /// Returns the message of this diagnostic.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Debug for MietteDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MietteDiagnostic")
+            .field("message", &self.message)
+            .field("code", &self.code)
+            .field("severity", &self.severity)
+            .field("help", &self.help)
+            .field("url", &self.url)
+            .field("labels", &self.labels)
+            .field(
+                "source_code",
+                &self.source_code.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
+}
+
+impl Display for MietteDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for MietteDiagnostic {}
+
+impl Diagnostic for MietteDiagnostic {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.code
+            .as_ref()
+            .map(|code| Box::new(code) as Box<dyn Display + 'a>)
+    }
+
+    fn severity(&self) -> Option<Severity> {
+        self.severity
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.help
+            .as_ref()
+            .map(|help| Box::new(help) as Box<dyn Display + 'a>)
+    }
+
+    fn url<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.url
+            .as_ref()
+            .map(|url| Box::new(url) as Box<dyn Display + 'a>)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        if self.labels.is_empty() {
+            None
+        } else {
+            Some(Box::new(self.labels.iter().cloned()))
+        }
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        self.source_code
+            .as_ref()
+            .map(|source_code| &**source_code as &dyn SourceCode)
+    }
+}
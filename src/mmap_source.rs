@@ -0,0 +1,107 @@
+use std::{
+    fmt,
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+};
+
+use memmap2::Mmap;
+
+use crate::{MietteError, MietteSpanContents, SourceCode, SourceSpan, SpanContents};
+
+/**
+[`SourceCode`] for a memory-mapped file, named after its path, for rendering
+snippets of inputs too large to comfortably read into memory, such as logs or
+generated code.
+
+Only the pages of the file that are actually touched are loaded, and snippets
+are borrowed straight from the mapping instead of being copied. Finding the
+line a span is on still means scanning the file up to that span.
+
+Requires the `mmap` feature.
+
+Opening one is `unsafe`, since its contents can change underneath it. See
+[`MmapSource::open`].
+
+## Example
+
+```no_run
+use miette::{miette, MmapSource};
+
+// SAFETY: nothing else modifies huge.log while this is running.
+let source = unsafe { MmapSource::open("huge.log")? };
+let report = miette!("oops!").with_source_code(source);
+# Ok::<(), std::io::Error>(())
+```
+*/
+pub struct MmapSource {
+    path: PathBuf,
+    mmap: Option<Mmap>,
+}
+
+impl MmapSource {
+    /// Maps the file at `path` into memory.
+    ///
+    /// # Safety
+    ///
+    /// As with any memory map, the file must not be modified or truncated, by
+    /// this process or any other, while the returned `MmapSource` is alive.
+    /// Doing so is undefined behavior, and on some platforms crashes the
+    /// process.
+    pub unsafe fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let file = File::open(&path)?;
+        // Empty files can't be mapped on every platform.
+        let mmap = if file.metadata()?.len() == 0 {
+            None
+        } else {
+            // SAFETY: the caller promises not to modify the file while it's
+            // mapped.
+            Some(Mmap::map(&file)?)
+        };
+        Ok(Self { path, mmap })
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the mapped contents of the file.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.mmap.as_deref().unwrap_or_default()
+    }
+}
+
+impl fmt::Debug for MmapSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MmapSource")
+            .field("path", &self.path)
+            .field("len", &self.as_bytes().len())
+            .finish()
+    }
+}
+
+impl SourceCode for MmapSource {
+    fn read_span<'a>(
+        &'a self,
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        let contents = <[u8] as SourceCode>::read_span(
+            self.as_bytes(),
+            span,
+            context_lines_before,
+            context_lines_after,
+        )?;
+        Ok(Box::new(MietteSpanContents::new_named(
+            self.path.display().to_string(),
+            contents.data(),
+            *contents.span(),
+            contents.line(),
+            contents.column(),
+            contents.line_count(),
+        )))
+    }
+}
@@ -1,17 +1,27 @@
+use std::sync::Arc;
+
 use crate::{MietteError, MietteSpanContents, SourceCode, SpanContents};
 
 /// Utility struct for when you have a regular [`SourceCode`] type that doesn't
 /// implement `name`. For example [`String`]. Or if you want to override the
 /// `name` returned by the `SourceCode`.
+///
+/// Cloning a `NamedSource` is cheap, and the clones share the same source
+/// code, so many diagnostics about the same file can each own one.
+#[derive(Clone)]
 pub struct NamedSource {
-    source: Box<dyn SourceCode + 'static>,
+    source: Arc<dyn SourceCode + 'static>,
     name: String,
+    language: Option<String>,
+    binary: bool,
 }
 
 impl std::fmt::Debug for NamedSource {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("NamedSource")
             .field("name", &self.name)
+            .field("language", &self.language)
+            .field("binary", &self.binary)
             .field("source", &"<redacted>");
         Ok(())
     }
@@ -22,8 +32,10 @@ impl NamedSource {
     /// its returned [`SpanContents`] a name.
     pub fn new(name: impl AsRef<str>, source: impl SourceCode + Send + Sync + 'static) -> Self {
         Self {
-            source: Box::new(source),
+            source: Arc::new(source),
             name: name.as_ref().to_string(),
+            language: None,
+            binary: false,
         }
     }
 
@@ -32,6 +44,20 @@ impl NamedSource {
     pub fn inner(&self) -> &(dyn SourceCode + 'static) {
         &*self.source
     }
+
+    /// Sets the [`language`](SpanContents::language) for this source code,
+    /// used to pick the grammar for syntax highlighting.
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Sets whether this source code [is binary](SpanContents::is_binary),
+    /// so that snippets of it are rendered as a hexdump.
+    pub fn with_binary(mut self, binary: bool) -> Self {
+        self.binary = binary;
+        self
+    }
 }
 
 impl SourceCode for NamedSource {
@@ -44,13 +70,22 @@ impl SourceCode for NamedSource {
         let contents = self
             .inner()
             .read_span(span, context_lines_before, context_lines_after)?;
-        Ok(Box::new(MietteSpanContents::new_named(
+        let language = self
+            .language
+            .clone()
+            .or_else(|| contents.language().map(String::from));
+        let mut contents = MietteSpanContents::new_named(
             self.name.clone(),
             contents.data(),
             *contents.span(),
             contents.line(),
             contents.column(),
             contents.line_count(),
-        )))
+        )
+        .with_binary(self.binary || contents.is_binary());
+        if let Some(language) = language {
+            contents = contents.with_language(language);
+        }
+        Ok(Box::new(contents))
     }
 }
@@ -9,7 +9,7 @@ use std::{
     panic::Location,
 };
 
-use crate::MietteError;
+use crate::{DiagnosticBacktrace, DiagnosticMetadata, MietteError, SourceComparison};
 
 /// Adds rich metadata to your Error that can be used by
 /// [`Report`](crate::Report) to print really nice and human-friendly error
@@ -39,6 +39,33 @@ pub trait Diagnostic: std::error::Error {
         None
     }
 
+    /// An additional note about this `Diagnostic`. Unlike [`Diagnostic::help`],
+    /// which tells the user what to do about it, notes give advisory context,
+    /// such as why something isn't allowed.
+    fn note<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        None
+    }
+
+    /// The value that was expected, for assertion-style diagnostics such as
+    /// failed tests or validation errors. Reporters render it aligned with
+    /// [`Diagnostic::found`].
+    fn expected<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        None
+    }
+
+    /// The value that was actually found, where [`Diagnostic::expected`] was
+    /// expected.
+    fn found<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        None
+    }
+
+    /// A suggested replacement for whatever went wrong, such as the name of
+    /// a similarly-named identifier when an unknown one was used. Reporters
+    /// render this as `did you mean `...`?` instead of as generic help text.
+    fn suggestion<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        None
+    }
+
     /// URL to visit for a more detailed explanation/help about this
     /// `Diagnostic`.
     fn url<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
@@ -55,6 +82,31 @@ pub trait Diagnostic: std::error::Error {
         None
     }
 
+    /// Suggested fixes for this `Diagnostic`, each replacing a span of its
+    /// [`Diagnostic::source_code`] with new text. Tools can apply them with
+    /// [`Fix::apply_all`], checking their [`Fix::applicability`] first.
+    fn fixes(&self) -> Option<Box<dyn Iterator<Item = Fix> + '_>> {
+        None
+    }
+
+    /// Tags describing the code this `Diagnostic` points at, so that editors
+    /// can gray out unused code or strike through deprecated usages.
+    fn tags(&self) -> Option<Box<dyn Iterator<Item = DiagnosticTag> + '_>> {
+        None
+    }
+
+    /// Structured key-value metadata for this `Diagnostic`, such as request
+    /// IDs or lint groups, for reporters to pass along.
+    fn metadata(&self) -> Option<&DiagnosticMetadata> {
+        None
+    }
+
+    /// Two sources to compare, such as an expected and an actual file, with
+    /// the spans of each that correspond to one another.
+    fn comparison(&self) -> Option<&SourceComparison> {
+        None
+    }
+
     /// Additional related `Diagnostic`s.
     fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
         None
@@ -64,6 +116,12 @@ pub trait Diagnostic: std::error::Error {
     fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
         None
     }
+
+    /// The [`DiagnosticBacktrace`] captured where this `Diagnostic` was
+    /// created, if any. Reporters show it along with the diagnostic.
+    fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+        None
+    }
 }
 
 impl std::error::Error for Box<dyn Diagnostic> {
@@ -76,23 +134,49 @@ impl std::error::Error for Box<dyn Diagnostic> {
     }
 }
 
-impl<T: Diagnostic + Send + Sync + 'static> From<T>
-    for Box<dyn Diagnostic + Send + Sync + 'static>
-{
+impl std::error::Error for Box<dyn Diagnostic + Send + Sync> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        (**self).source()
+    }
+
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        self.source()
+    }
+}
+
+impl<'a, T: Diagnostic + Send + Sync + 'a> From<T> for Box<dyn Diagnostic + Send + Sync + 'a> {
     fn from(diag: T) -> Self {
         Box::new(diag)
     }
 }
 
-impl<T: Diagnostic + Send + Sync + 'static> From<T> for Box<dyn Diagnostic + Send + 'static> {
+impl<'a, T: Diagnostic + Send + 'a> From<T> for Box<dyn Diagnostic + Send + 'a> {
     fn from(diag: T) -> Self {
-        Box::<dyn Diagnostic + Send + Sync>::from(diag)
+        Box::new(diag)
     }
 }
 
-impl<T: Diagnostic + Send + Sync + 'static> From<T> for Box<dyn Diagnostic + 'static> {
+impl<'a, T: Diagnostic + 'a> From<T> for Box<dyn Diagnostic + 'a> {
     fn from(diag: T) -> Self {
-        Box::<dyn Diagnostic + Send + Sync>::from(diag)
+        Box::new(diag)
+    }
+}
+
+impl<'a> From<Box<dyn Diagnostic + Send + Sync + 'a>> for Box<dyn Diagnostic + Send + 'a> {
+    fn from(diag: Box<dyn Diagnostic + Send + Sync + 'a>) -> Self {
+        diag
+    }
+}
+
+impl<'a> From<Box<dyn Diagnostic + Send + Sync + 'a>> for Box<dyn Diagnostic + 'a> {
+    fn from(diag: Box<dyn Diagnostic + Send + Sync + 'a>) -> Self {
+        diag
+    }
+}
+
+impl<'a> From<Box<dyn Diagnostic + Send + 'a>> for Box<dyn Diagnostic + 'a> {
+    fn from(diag: Box<dyn Diagnostic + Send + 'a>) -> Self {
+        diag
     }
 }
 
@@ -157,19 +241,75 @@ impl From<Box<dyn std::error::Error + Send + Sync>> for Box<dyn Diagnostic + Sen
     }
 }
 
+impl From<Box<dyn std::error::Error + Send + Sync>> for Box<dyn Diagnostic> {
+    fn from(s: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        let err: Box<dyn Diagnostic + Send + Sync> = From::from(s);
+        err
+    }
+}
+
 /**
 [`Diagnostic`] severity. Intended to be used by
 [`ReportHandler`](crate::ReportHandler)s to change the way different
 [`Diagnostic`]s are displayed.
+
+Severities are ordered from least to most severe, so they can be compared to
+filter out diagnostics below a certain level:
+
+```
+use miette::Severity;
+
+assert!(Severity::Bug > Severity::Error);
+assert!(Severity::Warning > Severity::Advice);
+assert!(Severity::Info < Severity::Advice);
+```
+
+More levels may be added in the future, so matches on `Severity` need a
+wildcard arm.
 */
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
 pub enum Severity {
-    /// Critical failure. The program cannot continue.
-    Error,
-    /// Warning. Please take note.
-    Warning,
+    /// Purely informational. Nothing needs to be done about it.
+    ///
+    /// `#[diagnostic(severity(Info))]` derives this level. The name is
+    /// case-sensitive, since the lowercase `severity(info)` is an alias for
+    /// [`Severity::Advice`].
+    Info,
     /// Just some help. Here's how you could be doing it better.
     Advice,
+    /// Warning. Please take note.
+    Warning,
+    /// Critical failure. The program cannot continue.
+    Error,
+    /// Internal failure caused by a bug in the program itself, rather than
+    /// anything the user did.
+    Bug,
+}
+
+/**
+Extra information about the code a [`Diagnostic`] points at, for editors and
+reporters to render it accordingly. These mirror the Language Server
+Protocol's `DiagnosticTag`s.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DiagnosticTag {
+    /// Unused or unnecessary code, such as an unused variable or an
+    /// unreachable statement. Editors may gray it out.
+    Unnecessary,
+    /// Deprecated or obsolete code. Editors may strike it through.
+    Deprecated,
+}
+
+impl Display for DiagnosticTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag = match self {
+            DiagnosticTag::Unnecessary => "unnecessary",
+            DiagnosticTag::Deprecated => "deprecated",
+        };
+        write!(f, "{}", tag)
+    }
 }
 
 /**
@@ -182,23 +322,81 @@ types, file handles, and even network streams.
 If you can read it, you can source it, and it's not necessary to read the
 whole thing--meaning you should be able to support `SourceCode`s which are
 gigabytes or larger in size.
+
+For in-memory source text, `SourceCode` is already implemented for `str`,
+`&str`, [`String`], `[u8]`, `&[u8]` and `Vec<u8>`, as well as for `Arc`s and
+`Cow`s of any of them, such as `Arc<str>`. These skip over a UTF-8 byte order
+mark at the start of the text, so that it doesn't count towards columns on the
+first line or show up in snippets.
 */
 pub trait SourceCode: Send + Sync {
     /// Read the bytes for a specific span from this SourceCode, keeping a
     /// certain number of lines before and after the span as context.
+    ///
+    /// Report handlers ask for all of the context they're going to render in
+    /// this one call, so implementations shouldn't return any more or less
+    /// than that. With `context_lines_before` lines of context, the returned
+    /// [`SpanContents`] starts at the beginning of that many lines above the
+    /// one the span starts on, or at the start of the source if there aren't
+    /// that many, and with none, it starts where the span does. Likewise, it
+    /// ends at the end of `context_lines_after` lines below the one the span
+    /// ends on, or where the span does. Its [`line`](SpanContents::line) and
+    /// [`column`](SpanContents::column) are those of the start of the
+    /// returned data, not of the span.
+    ///
+    /// Spans that aren't within the source should return
+    /// [`MietteError::OutOfBounds`].
     fn read_span<'a>(
         &'a self,
         span: &SourceSpan,
         context_lines_before: usize,
         context_lines_after: usize,
     ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError>;
+
+    /// The 0-indexed column that `span` starts at, the way an editor would
+    /// display it: counting characters rather than bytes, like
+    /// [`SpanContents::column`] does, with each tab advancing to the next
+    /// multiple of `tab_width`.
+    ///
+    /// By default, this reads the start of the span's line with
+    /// [`read_span`](SourceCode::read_span) to count what's on it.
+    fn visual_column(&self, span: &SourceSpan, tab_width: usize) -> Result<usize, MietteError> {
+        let byte_column = self.read_span(span, 0, 0)?.column();
+        let line_start = span
+            .offset()
+            .checked_sub(byte_column)
+            .ok_or(MietteError::OutOfBounds)?;
+        let prefix = self.read_span(&(line_start, byte_column).into(), 0, 0)?;
+        let prefix = prefix.data().get(..byte_column).unwrap_or_default();
+        let tab_width = tab_width.max(1);
+        Ok(String::from_utf8_lossy(prefix)
+            .chars()
+            .fold(0, |column, c| match c {
+                '\t' => column + tab_width - column % tab_width,
+                _ => column + 1,
+            }))
+    }
+}
+
+/// Lets `Arc<dyn SourceCode>` and `Box<dyn SourceCode>` fields be used in
+/// types that derive `Debug`, without dumping the whole source.
+impl fmt::Debug for dyn SourceCode + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SourceCode").finish_non_exhaustive()
+    }
 }
 
 /// A labeled [`SourceSpan`].
+///
+/// One of a diagnostic's labels can be marked as its primary label, pointing
+/// at the main location of the problem, as opposed to secondary labels that
+/// add context. Reporters that only show a single location use the primary
+/// label, falling back to the first one if no label is marked.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LabeledSpan {
     label: Option<String>,
     span: SourceSpan,
+    primary: bool,
 }
 
 impl LabeledSpan {
@@ -207,14 +405,32 @@ impl LabeledSpan {
         Self {
             label,
             span: (offset, len).into(),
+            primary: false,
         }
     }
 
+    /// Makes a new labeled span pointing at a single point between two
+    /// characters, such as where something is missing, rather than covering
+    /// any of them.
+    pub fn point(label: Option<String>, offset: ByteOffset) -> Self {
+        Self::new(label, offset, 0)
+    }
+
     /// Makes a new labeled span using an existing span.
     pub fn new_with_span(label: Option<String>, span: impl Into<SourceSpan>) -> Self {
         Self {
             label,
             span: span.into(),
+            primary: false,
+        }
+    }
+
+    /// Makes a new primary labeled span using an existing span.
+    pub fn new_primary_with_span(label: Option<String>, span: impl Into<SourceSpan>) -> Self {
+        Self {
+            label,
+            span: span.into(),
+            primary: true,
         }
     }
 
@@ -242,6 +458,160 @@ impl LabeledSpan {
     pub fn is_empty(&self) -> bool {
         self.span.is_empty()
     }
+
+    /// True if this is the primary `LabeledSpan` of its diagnostic.
+    pub fn primary(&self) -> bool {
+        self.primary
+    }
+}
+
+/**
+A suggested fix for a [`Diagnostic`]: the source code covered by a
+[`SourceSpan`], and the text it should be replaced with.
+
+Each fix carries an [`Applicability`], telling tools such as IDEs and `--fix`
+flags whether it's safe to apply without asking the user first. Fixes can be
+applied to source text with [`Fix::apply_all`].
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    span: SourceSpan,
+    replacement: String,
+    applicability: Applicability,
+}
+
+impl Fix {
+    /// Makes a new fix replacing `span` with `replacement`. An empty span
+    /// inserts the replacement, and an empty replacement deletes the span.
+    ///
+    /// Its applicability is [`Applicability::Unspecified`] until set with
+    /// [`Fix::with_applicability`].
+    pub fn new(span: impl Into<SourceSpan>, replacement: impl Into<String>) -> Self {
+        Self {
+            span: span.into(),
+            replacement: replacement.into(),
+            applicability: Applicability::Unspecified,
+        }
+    }
+
+    /// Sets how confident we are that this fix is correct.
+    pub fn with_applicability(mut self, applicability: Applicability) -> Self {
+        self.applicability = applicability;
+        self
+    }
+
+    /// Returns a reference to the [`SourceSpan`] being replaced.
+    pub fn span(&self) -> &SourceSpan {
+        &self.span
+    }
+
+    /// Returns the text to replace the span with.
+    pub fn replacement(&self) -> &str {
+        &self.replacement
+    }
+
+    /// Returns how confident we are that this fix is correct.
+    pub fn applicability(&self) -> Applicability {
+        self.applicability
+    }
+
+    /// Applies `fixes` to `source`, returning the fixed text.
+    ///
+    /// Fixes are applied in order of their offsets, regardless of the order
+    /// they're given in. A fix overlapping one that was already applied is
+    /// skipped, so that the rest can still go through. Returns
+    /// [`MietteError::OutOfBounds`] if a span is outside of `source`, or
+    /// doesn't fall on character boundaries.
+    ///
+    /// ```
+    /// use miette::{Applicability, Fix};
+    ///
+    /// let fixes = vec![
+    ///     Fix::new((10, 3), "y").with_applicability(Applicability::MachineApplicable),
+    ///     Fix::new((4, 3), "y").with_applicability(Applicability::MachineApplicable),
+    /// ];
+    /// let fixed = Fix::apply_all("let foo = foo + 1;", &fixes).unwrap();
+    /// assert_eq!(fixed, "let y = y + 1;");
+    /// ```
+    pub fn apply_all<'a>(
+        source: &str,
+        fixes: impl IntoIterator<Item = &'a Fix>,
+    ) -> Result<String, MietteError> {
+        let mut fixes = fixes.into_iter().collect::<Vec<_>>();
+        fixes.sort_by_key(|fix| (fix.span.offset(), fix.span.len()));
+        let mut fixed = String::with_capacity(source.len());
+        let mut pos = 0;
+        for fix in fixes {
+            let start = fix.span.offset();
+            let end = start + fix.span.len();
+            if !source.is_char_boundary(start) || !source.is_char_boundary(end) {
+                return Err(MietteError::OutOfBounds);
+            }
+            if start < pos {
+                continue;
+            }
+            fixed.push_str(&source[pos..start]);
+            fixed.push_str(&fix.replacement);
+            pos = end;
+        }
+        fixed.push_str(&source[pos..]);
+        Ok(fixed)
+    }
+}
+
+/**
+How confident we are that a [`Fix`] is correct, mirroring the applicability
+levels of rustc's suggestions.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Applicability {
+    /// The fix is definitely what the user intended, and can be applied
+    /// automatically.
+    MachineApplicable,
+    /// The fix may be what the user intended, but it's uncertain. It should
+    /// result in valid code if applied.
+    MaybeIncorrect,
+    /// The fix contains placeholders like `(...)` or `{ /* fields */ }`, which
+    /// the user needs to fill in before it's valid.
+    HasPlaceholders,
+    /// The applicability of the fix is unknown.
+    Unspecified,
+}
+
+impl Default for Applicability {
+    fn default() -> Self {
+        Applicability::Unspecified
+    }
+}
+
+impl Display for Applicability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let applicability = match self {
+            Applicability::MachineApplicable => "machine-applicable",
+            Applicability::MaybeIncorrect => "maybe-incorrect",
+            Applicability::HasPlaceholders => "has-placeholders",
+            Applicability::Unspecified => "unspecified",
+        };
+        write!(f, "{}", applicability)
+    }
+}
+
+/// Counts the line endings in `data`, treating `\r\n`, `\n` and a lone `\r`
+/// each as one, and returns that along with where the last line starts.
+fn last_line_start(data: &[u8]) -> (usize, usize) {
+    let mut breaks = 0;
+    let mut start = 0;
+    for (idx, byte) in data.iter().enumerate() {
+        match byte {
+            b'\n' if idx > 0 && data[idx - 1] == b'\r' => start = idx + 1,
+            b'\r' | b'\n' => {
+                breaks += 1;
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    (breaks, start)
 }
 
 /**
@@ -259,13 +629,48 @@ pub trait SpanContents<'a> {
         None
     }
     /// The 0-indexed line in the associated [`SourceCode`] where the data
-    /// begins.
+    /// begins. Report handlers add 1 to this when displaying it, unless one
+    /// that supports it is configured to show 0-based line numbers.
     fn line(&self) -> usize;
     /// The 0-indexed column in the associated [`SourceCode`] where the data
-    /// begins, relative to `line`.
+    /// begins, relative to `line`. Like [`line`](SpanContents::line), it is
+    /// displayed 1-based by default.
     fn column(&self) -> usize;
     /// Total number of lines covered by this `SpanContents`.
     fn line_count(&self) -> usize;
+    /// The 0-indexed line in the associated [`SourceCode`] where the data
+    /// ends, that is, the line that the offset just past its last byte falls
+    /// on. Data that ends with a line ending ends on the next line.
+    ///
+    /// By default, this is worked out from [`line`](SpanContents::line) and
+    /// the line endings in the data, so implementations only need to
+    /// override it if they already know it.
+    fn end_line(&self) -> usize {
+        self.line() + last_line_start(self.data()).0
+    }
+    /// The 0-indexed column in the associated [`SourceCode`] where the data
+    /// ends, relative to [`end_line`](SpanContents::end_line). Like
+    /// [`column`](SpanContents::column), it counts bytes.
+    fn end_column(&self) -> usize {
+        let data = self.data();
+        match last_line_start(data) {
+            (0, _) => self.column() + data.len(),
+            (_, start) => data.len() - start,
+        }
+    }
+    /// Optional method. The language name for this source code, if any.
+    /// This is used to drive syntax highlighting.
+    ///
+    /// Examples: Rust, TOML, C
+    fn language(&self) -> Option<&str> {
+        None
+    }
+    /// Optional method. Whether this source code is binary data rather than
+    /// text, in which case report handlers that support it render snippets of
+    /// it as a hexdump instead of as lines of text.
+    fn is_binary(&self) -> bool {
+        false
+    }
 }
 
 /**
@@ -285,6 +690,10 @@ pub struct MietteSpanContents<'a> {
     line_count: usize,
     // Optional filename
     name: Option<String>,
+    // Optional language
+    language: Option<String>,
+    // Whether the data is binary rather than text
+    binary: bool,
 }
 
 impl<'a> MietteSpanContents<'a> {
@@ -303,6 +712,8 @@ impl<'a> MietteSpanContents<'a> {
             column,
             line_count,
             name: None,
+            language: None,
+            binary: false,
         }
     }
 
@@ -322,8 +733,22 @@ impl<'a> MietteSpanContents<'a> {
             column,
             line_count,
             name: Some(name),
+            language: None,
+            binary: false,
         }
     }
+
+    /// Sets the [`language`](SpanContents::language) for syntax highlighting.
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Sets whether the data [is binary](SpanContents::is_binary).
+    pub fn with_binary(mut self, binary: bool) -> Self {
+        self.binary = binary;
+        self
+    }
 }
 
 impl<'a> SpanContents<'a> for MietteSpanContents<'a> {
@@ -345,10 +770,43 @@ impl<'a> SpanContents<'a> for MietteSpanContents<'a> {
     fn name(&self) -> Option<&str> {
         self.name.as_deref()
     }
+    fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+    fn is_binary(&self) -> bool {
+        self.binary
+    }
 }
 
 /**
 Span within a [`SourceCode`] with an associated message.
+
+Spans can be converted from a `Range` of byte offsets, an `(offset, length)`
+tuple, or a single offset for a zero-length span at that point:
+
+```
+use miette::SourceSpan;
+
+let range: SourceSpan = (4..10).into();
+let tuple: SourceSpan = (4, 6).into();
+assert_eq!(range, tuple);
+
+let point: SourceSpan = 4.into();
+assert!(point.is_empty());
+```
+
+Going the other way, a span converts into the `Range` of byte offsets it
+covers, for slicing the source it's a span of:
+
+```
+use std::ops::Range;
+
+use miette::SourceSpan;
+
+let source = "let x = oops;";
+let span = SourceSpan::new(8.into(), 4.into());
+assert_eq!(&source[Range::from(span)], "oops");
+```
 */
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct SourceSpan {
@@ -367,6 +825,12 @@ impl SourceSpan {
         }
     }
 
+    /// Create a new zero-length [`SourceSpan`], pointing at a single point
+    /// between two characters rather than covering any of them.
+    pub fn point(offset: ByteOffset) -> Self {
+        Self::new(offset.into(), 0.into())
+    }
+
     /// The absolute offset, in bytes, from the beginning of a [`SourceCode`].
     pub fn offset(&self) -> usize {
         self.offset.offset()
@@ -382,6 +846,94 @@ impl SourceSpan {
     pub fn is_empty(&self) -> bool {
         self.length.offset() == 0
     }
+
+    /// Returns the smallest [`SourceSpan`] covering both this one and
+    /// `other`, along with anything in between them.
+    pub fn union(&self, other: &SourceSpan) -> Self {
+        let start = self.offset().min(other.offset());
+        let end = (self.offset() + self.len()).max(other.offset() + other.len());
+        (start, end - start).into()
+    }
+
+    /// Whether `offset` is within this [`SourceSpan`]. Like with a `Range`,
+    /// the end of the span isn't, so empty spans contain no offsets.
+    pub fn contains(&self, offset: ByteOffset) -> bool {
+        self.offset() <= offset && offset < self.offset() + self.len()
+    }
+
+    /// Whether `other` is entirely within this [`SourceSpan`]. Empty spans
+    /// are within it anywhere from its start to its end, inclusive.
+    pub fn contains_span(&self, other: &SourceSpan) -> bool {
+        self.offset() <= other.offset()
+            && other.offset() + other.len() <= self.offset() + self.len()
+    }
+
+    /// Whether this [`SourceSpan`] and `other` have at least one byte in
+    /// common. Empty spans don't intersect anything.
+    pub fn intersects(&self, other: &SourceSpan) -> bool {
+        self.offset().max(other.offset())
+            < (self.offset() + self.len()).min(other.offset() + other.len())
+    }
+
+    /// Returns this [`SourceSpan`] moved `by` bytes further into the source,
+    /// such as to turn a span within a snippet of it into one within all of
+    /// it.
+    pub fn shift_forward(&self, by: usize) -> Self {
+        (self.offset() + by, self.len()).into()
+    }
+
+    /// Returns this [`SourceSpan`] moved `by` bytes back towards the start of
+    /// the source, stopping at the start of it.
+    pub fn shift_back(&self, by: usize) -> Self {
+        (self.offset().saturating_sub(by), self.len()).into()
+    }
+
+    /// Little utility to help convert a span counted in some other
+    /// [`OffsetUnit`] into a miette-compatible Span, counted in bytes.
+    ///
+    /// Like [`SourceOffset::from_units`], this function is infallible: the
+    /// span is cut short if it would run past the end of the source.
+    ///
+    /// ```
+    /// use miette::{OffsetUnit, SourceSpan};
+    ///
+    /// // An LSP range, in UTF-16 code units, around "oops".
+    /// let span = SourceSpan::from_units("let 😬 = oops;", 9, 4, OffsetUnit::Utf16);
+    /// assert_eq!(span, (11, 4).into());
+    /// ```
+    pub fn from_units(
+        source: impl AsRef<str>,
+        offset: usize,
+        length: usize,
+        unit: OffsetUnit,
+    ) -> Self {
+        let source = source.as_ref();
+        let start = SourceOffset::from_units(source, offset, unit);
+        let end = SourceOffset::from_units(source, offset + length, unit);
+        Self::new(start, (end.offset() - start.offset()).into())
+    }
+
+    /// Little utility to help convert a 1-based line/column location and a
+    /// length, in characters, into a miette-compatible Span.
+    ///
+    /// Like [`SourceOffset::from_location`], this function is infallible: an
+    /// out-of-range location starts the span at the end of the source, and
+    /// the span is cut short if it would run past the end of the source.
+    pub fn from_location(
+        source: impl AsRef<str>,
+        loc_line: usize,
+        loc_col: usize,
+        length: usize,
+    ) -> Self {
+        let source = source.as_ref();
+        let offset = SourceOffset::from_location(source, loc_line, loc_col);
+        let length = source[offset.offset()..]
+            .chars()
+            .take(length)
+            .map(char::len_utf8)
+            .sum::<usize>();
+        Self::new(offset, length.into())
+    }
 }
 
 impl From<(ByteOffset, ByteOffset)> for SourceSpan {
@@ -411,6 +963,24 @@ impl From<std::ops::Range<ByteOffset>> for SourceSpan {
     }
 }
 
+impl From<SourceSpan> for std::ops::Range<ByteOffset> {
+    fn from(span: SourceSpan) -> Self {
+        span.offset()..span.offset() + span.len()
+    }
+}
+
+/// Uses the byte range of the span, which is only known outside of procedural
+/// macros, or within them on a nightly compiler. Otherwise, the span is empty
+/// and at the start of the source.
+///
+/// Requires the `proc-macro` feature.
+#[cfg(feature = "proc-macro")]
+impl From<proc_macro2::Span> for SourceSpan {
+    fn from(span: proc_macro2::Span) -> Self {
+        span.byte_range().into()
+    }
+}
+
 impl From<SourceOffset> for SourceSpan {
     fn from(offset: SourceOffset) -> Self {
         Self {
@@ -434,6 +1004,25 @@ impl From<ByteOffset> for SourceSpan {
 */
 pub type ByteOffset = usize;
 
+/**
+The unit that an offset into source text is counted in, for offsets from tools
+that don't count bytes, such as JavaScript tooling and the Language Server
+Protocol, which count UTF-16 code units.
+
+See [`SourceOffset::from_units`] and [`SourceSpan::from_units`].
+*/
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum OffsetUnit {
+    /// Bytes of UTF-8, which is what [`SourceOffset`]s and [`SourceSpan`]s
+    /// are counted in.
+    Bytes,
+    /// Unicode scalar values, that is, Rust [`char`]s.
+    Chars,
+    /// UTF-16 code units, where characters outside of the Basic Multilingual
+    /// Plane count as two.
+    Utf16,
+}
+
 /**
 Newtype that represents the [`ByteOffset`] from the beginning of a [`SourceCode`]
 */
@@ -471,6 +1060,29 @@ impl SourceOffset {
         SourceOffset(offset)
     }
 
+    /// Little utility to help convert offsets counted in some other
+    /// [`OffsetUnit`] into miette-compatible byte offsets.
+    ///
+    /// This function is infallible: Giving an out-of-range offset will return
+    /// the offset of the end of the source. A UTF-16 offset into the middle
+    /// of a character is rounded up to the end of it.
+    pub fn from_units(source: impl AsRef<str>, offset: usize, unit: OffsetUnit) -> Self {
+        let source = source.as_ref();
+        let len = |c: char| match unit {
+            OffsetUnit::Bytes => c.len_utf8(),
+            OffsetUnit::Chars => 1,
+            OffsetUnit::Utf16 => c.len_utf16(),
+        };
+        let mut units = 0usize;
+        for (idx, char) in source.char_indices() {
+            if units >= offset {
+                return SourceOffset(idx);
+            }
+            units += len(char);
+        }
+        SourceOffset(source.len())
+    }
+
     /// Returns an offset for the _file_ location of wherever this function is
     /// called. If you want to get _that_ caller's location, mark this
     /// function's caller with `#[track_caller]` (and so on and so forth).
@@ -521,3 +1133,109 @@ fn test_source_offset_from_location() {
         source.len()
     );
 }
+
+#[test]
+fn test_source_span_conversions() {
+    let span = SourceSpan::from(4..10);
+    assert_eq!(span.offset(), 4);
+    assert_eq!(span.len(), 6);
+    assert_eq!(span, SourceSpan::from((4, 6)));
+    assert_eq!(
+        span,
+        SourceSpan::from((SourceOffset::from(4), SourceOffset::from(6)))
+    );
+
+    let point = SourceSpan::from(4);
+    assert_eq!(point.offset(), 4);
+    assert!(point.is_empty());
+    assert_eq!(point, SourceSpan::point(4));
+    assert_eq!(point, SourceSpan::from(SourceOffset::from(4)));
+
+    assert_eq!(std::ops::Range::from(span), 4..10);
+    assert_eq!(std::ops::Range::from(point), 4..4);
+}
+
+#[test]
+fn test_source_span_from_location() {
+    let source = "f\n\noo\r\nbär";
+
+    assert_eq!(SourceSpan::from_location(source, 1, 1, 1), (0, 1).into());
+    assert_eq!(SourceSpan::from_location(source, 3, 1, 2), (3, 2).into());
+    assert_eq!(SourceSpan::from_location(source, 4, 1, 3), (7, 4).into());
+    assert_eq!(SourceSpan::from_location(source, 4, 2, 0), (8, 0).into());
+
+    // Out-of-range
+    assert_eq!(SourceSpan::from_location(source, 4, 3, 5), (10, 1).into());
+    assert_eq!(
+        SourceSpan::from_location(source, 5, 1, 1),
+        (source.len(), 0).into()
+    );
+}
+
+#[test]
+fn test_source_span_from_units() {
+    let source = "aé😬b";
+
+    assert_eq!(
+        SourceSpan::from_units(source, 1, 2, OffsetUnit::Bytes),
+        (1, 2).into()
+    );
+    assert_eq!(
+        SourceSpan::from_units(source, 1, 2, OffsetUnit::Chars),
+        (1, 6).into()
+    );
+    assert_eq!(
+        SourceSpan::from_units(source, 2, 2, OffsetUnit::Utf16),
+        (3, 4).into()
+    );
+    assert_eq!(
+        SourceSpan::from_units(source, 4, 1, OffsetUnit::Utf16),
+        (7, 1).into()
+    );
+
+    // Into the middle of a surrogate pair
+    assert_eq!(
+        SourceOffset::from_units(source, 3, OffsetUnit::Utf16).offset(),
+        7
+    );
+
+    // Out-of-range
+    assert_eq!(
+        SourceSpan::from_units(source, 3, 5, OffsetUnit::Chars),
+        (7, 1).into()
+    );
+    assert_eq!(
+        SourceOffset::from_units(source, 20, OffsetUnit::Utf16).offset(),
+        source.len()
+    );
+}
+
+#[test]
+fn test_source_span_interval_math() {
+    let a = SourceSpan::from(2..6);
+    let b = SourceSpan::from(5..9);
+    let c = SourceSpan::from(8..12);
+
+    assert_eq!(a.union(&b), (2..9).into());
+    assert_eq!(a.union(&c), (2..12).into());
+    assert_eq!(c.union(&a), (2..12).into());
+    assert_eq!(a.union(&SourceSpan::from(3..4)), a);
+
+    assert!(a.contains(2));
+    assert!(a.contains(5));
+    assert!(!a.contains(6));
+    assert!(!SourceSpan::from(4).contains(4));
+    assert!(a.contains_span(&(3..6).into()));
+    assert!(a.contains_span(&SourceSpan::from(6)));
+    assert!(!a.contains_span(&b));
+
+    assert!(a.intersects(&b));
+    assert!(b.intersects(&a));
+    assert!(!a.intersects(&c));
+    assert!(!a.intersects(&(6..8).into()));
+    assert!(!a.intersects(&SourceSpan::from(3)));
+
+    assert_eq!(a.shift_forward(10), (12..16).into());
+    assert_eq!(a.shift_back(1), (1..5).into());
+    assert_eq!(a.shift_back(10), (0..4).into());
+}
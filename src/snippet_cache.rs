@@ -0,0 +1,43 @@
+use std::sync::Mutex;
+
+/// Parts of a source that have been read into memory to be borrowed by
+/// [`SpanContents`](crate::SpanContents), which have to live as long as the
+/// source they were read from does.
+///
+/// Windows that are already covered by one kept before are borrowed from that
+/// one instead of being kept again, so reading the same spans over and over,
+/// as rendering the same diagnostics again does, doesn't use any more memory.
+#[derive(Default)]
+pub(crate) struct SnippetCache {
+    windows: Mutex<Vec<(usize, Vec<u8>)>>,
+}
+
+impl SnippetCache {
+    /// Keeps `window`, the part of the source starting at offset `start`, and
+    /// returns it, or the same part of a window kept before that covers it.
+    pub(crate) fn keep(&self, start: usize, window: Vec<u8>) -> &[u8] {
+        let mut windows = self.windows.lock().unwrap_or_else(|err| err.into_inner());
+        let end = start + window.len();
+        let (kept_start, kept) = match windows
+            .iter()
+            .position(|(kept_start, kept)| *kept_start <= start && kept_start + kept.len() >= end)
+        {
+            Some(idx) => &windows[idx],
+            None => {
+                windows.push((start, window));
+                windows.last().unwrap()
+            }
+        };
+        let kept = &kept[start - kept_start..end - kept_start];
+        // SAFETY: windows are never modified or removed once they've been
+        // kept, so their contents stay where they are, even when the Vec
+        // holding them grows, until `self` is dropped.
+        unsafe { std::slice::from_raw_parts(kept.as_ptr(), kept.len()) }
+    }
+
+    /// Whether nothing has been kept yet.
+    pub(crate) fn is_empty(&self) -> bool {
+        let windows = self.windows.lock().unwrap_or_else(|err| err.into_inner());
+        windows.is_empty()
+    }
+}
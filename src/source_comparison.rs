@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use crate::{SourceCode, SourceSpan};
+
+/**
+Two sources to compare, such as an expected and an actual file, two versions
+of a schema, or two revisions of a config, along with pairs of spans in them
+that correspond to one another.
+
+Returned from [`Diagnostic::comparison`](crate::Diagnostic::comparison), it
+is rendered as snippets of both sources, with each pair of spans highlighted
+in the same color. By default the snippets are laid out side by side, falling
+back to one above the other when there isn't room for that.
+
+## Example
+
+```
+use miette::{Diagnostic, SourceComparison};
+use thiserror::Error;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("config doesn't match the schema")]
+struct Mismatch {
+    #[comparison]
+    comparison: SourceComparison,
+}
+
+let comparison = SourceComparison::new(
+    "expected.toml",
+    "port = 8080\n",
+    "actual.toml",
+    "name = \"app\"\nport = \"8080\"\n",
+)
+.with_spans(Some("expected an integer".into()), (7, 4), (20, 6));
+let mismatch = Mismatch { comparison };
+```
+*/
+#[derive(Clone, Debug)]
+pub struct SourceComparison {
+    old_name: String,
+    old_source: Arc<dyn SourceCode>,
+    new_name: String,
+    new_source: Arc<dyn SourceCode>,
+    spans: Vec<CorrelatedSpans>,
+    layout: ComparisonLayout,
+}
+
+impl SourceComparison {
+    /// Create a new [`SourceComparison`] of an old source with a new one, each
+    /// with a name to show in snippet headers.
+    pub fn new(
+        old_name: impl Into<String>,
+        old_source: impl SourceCode + 'static,
+        new_name: impl Into<String>,
+        new_source: impl SourceCode + 'static,
+    ) -> Self {
+        Self {
+            old_name: old_name.into(),
+            old_source: Arc::new(old_source),
+            new_name: new_name.into(),
+            new_source: Arc::new(new_source),
+            spans: Vec::new(),
+            layout: ComparisonLayout::default(),
+        }
+    }
+
+    /// Adds a pair of corresponding spans, one in each source, with an
+    /// optional label describing how they differ.
+    pub fn with_spans(
+        mut self,
+        label: Option<String>,
+        old: impl Into<SourceSpan>,
+        new: impl Into<SourceSpan>,
+    ) -> Self {
+        self.spans.push(CorrelatedSpans {
+            label,
+            old: old.into(),
+            new: new.into(),
+        });
+        self
+    }
+
+    /// Sets how the two sources are laid out relative to one another.
+    pub fn with_layout(mut self, layout: ComparisonLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Returns the name of the old source.
+    pub fn old_name(&self) -> &str {
+        &self.old_name
+    }
+
+    /// Returns the old source.
+    pub fn old_source(&self) -> &dyn SourceCode {
+        &*self.old_source
+    }
+
+    /// Returns the name of the new source.
+    pub fn new_name(&self) -> &str {
+        &self.new_name
+    }
+
+    /// Returns the new source.
+    pub fn new_source(&self) -> &dyn SourceCode {
+        &*self.new_source
+    }
+
+    /// Returns the pairs of corresponding spans.
+    pub fn spans(&self) -> &[CorrelatedSpans] {
+        &self.spans
+    }
+
+    /// Returns how the two sources are laid out.
+    pub fn layout(&self) -> ComparisonLayout {
+        self.layout
+    }
+}
+
+/// A span in each of the sources of a [`SourceComparison`] that correspond to
+/// one another, with an optional label.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorrelatedSpans {
+    label: Option<String>,
+    old: SourceSpan,
+    new: SourceSpan,
+}
+
+impl CorrelatedSpans {
+    /// Returns the label describing how the spans differ, if any.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Returns the span in the old source.
+    pub fn old_span(&self) -> &SourceSpan {
+        &self.old
+    }
+
+    /// Returns the span in the new source.
+    pub fn new_span(&self) -> &SourceSpan {
+        &self.new
+    }
+}
+
+/// How the sources of a [`SourceComparison`] are laid out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComparisonLayout {
+    /// The old source to the left of the new one, as long as there's room
+    /// for both. Otherwise, the same as [`ComparisonLayout::Unified`].
+    SideBySide,
+    /// The old source above the new one, like a unified diff.
+    Unified,
+}
+
+impl Default for ComparisonLayout {
+    fn default() -> Self {
+        Self::SideBySide
+    }
+}
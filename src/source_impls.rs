@@ -9,6 +9,9 @@ use std::{
 
 use crate::{MietteError, MietteSpanContents, SourceCode, SourceSpan, SpanContents};
 
+/// The UTF-8 byte order mark, which some editors put at the start of files.
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
 fn context_info<'a>(
     input: &'a [u8],
     span: &SourceSpan,
@@ -24,6 +27,13 @@ fn context_info<'a>(
     let mut end_lines = 0usize;
     let mut post_span = false;
     let mut post_span_got_newline = false;
+    // A byte order mark isn't part of the first line as far as columns and
+    // snippets are concerned, as long as the span doesn't point into it.
+    let bom_len = if input.starts_with(BOM) && span.offset() >= BOM.len() {
+        BOM.len()
+    } else {
+        0
+    };
     let mut iter = input.iter().copied().peekable();
     while let Some(char) = iter.next() {
         if matches!(char, b'\r' | b'\n') {
@@ -57,7 +67,7 @@ fn context_info<'a>(
                 }
             }
             current_line_start = offset + 1;
-        } else if offset < span.offset() {
+        } else if offset < span.offset() && offset >= bom_len {
             start_column += 1;
         }
 
@@ -73,13 +83,17 @@ fn context_info<'a>(
     }
 
     if offset >= (span.offset() + span.len()).saturating_sub(1) {
-        let starting_offset = before_lines_starts.first().copied().unwrap_or_else(|| {
-            if context_lines_before == 0 {
-                span.offset()
-            } else {
-                0
-            }
-        });
+        let starting_offset = before_lines_starts
+            .first()
+            .copied()
+            .unwrap_or_else(|| {
+                if context_lines_before == 0 {
+                    span.offset()
+                } else {
+                    0
+                }
+            })
+            .max(bom_len);
         Ok(MietteSpanContents::new(
             &input[starting_offset..offset],
             (starting_offset, offset - starting_offset).into(),
@@ -158,6 +172,17 @@ impl SourceCode for String {
     }
 }
 
+impl SourceCode for Vec<u8> {
+    fn read_span<'a>(
+        &'a self,
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        <[u8] as SourceCode>::read_span(self, span, context_lines_before, context_lines_after)
+    }
+}
+
 impl<T: ?Sized + SourceCode> SourceCode for Arc<T> {
     fn read_span<'a>(
         &'a self,
@@ -286,4 +311,89 @@ mod tests {
         assert_eq!(&span, contents.span());
         Ok(())
     }
+
+    #[test]
+    fn end_line_and_column() -> Result<(), MietteError> {
+        let src = String::from("foo\nbarbar\r\nbaz\n");
+        let contents = src.read_span(&(7, 2).into(), 0, 0)?;
+        assert_eq!(1, contents.end_line());
+        assert_eq!(5, contents.end_column());
+        let contents = src.read_span(&(7, 5).into(), 0, 0)?;
+        assert_eq!("bar\r\n", std::str::from_utf8(contents.data()).unwrap());
+        assert_eq!(2, contents.end_line());
+        assert_eq!(0, contents.end_column());
+        let contents = src.read_span(&(4, 3).into(), 1, 1)?;
+        assert_eq!(3, contents.end_line());
+        assert_eq!(0, contents.end_column());
+        Ok(())
+    }
+
+    #[test]
+    fn with_bom() -> Result<(), MietteError> {
+        let src = String::from("\u{feff}foo bar\nbaz\n");
+        let contents = src.read_span(&(7, 3).into(), 0, 0)?;
+        assert_eq!("bar", std::str::from_utf8(contents.data()).unwrap());
+        assert_eq!(0, contents.line());
+        assert_eq!(4, contents.column());
+        let contents = src.read_span(&(7, 3).into(), 1, 1)?;
+        assert_eq!(
+            "foo bar\nbaz\n",
+            std::str::from_utf8(contents.data()).unwrap()
+        );
+        let span: SourceSpan = (3, 12).into();
+        assert_eq!(&span, contents.span());
+        assert_eq!(0, contents.column());
+        Ok(())
+    }
+
+    #[test]
+    fn visual_column() -> Result<(), MietteError> {
+        let src = String::from("foo\n\tbär\tbaz\n");
+        assert_eq!(0, src.visual_column(&(4, 1).into(), 4)?);
+        assert_eq!(4, src.visual_column(&(5, 1).into(), 4)?);
+        assert_eq!(8, src.visual_column(&(10, 3).into(), 4)?);
+        assert_eq!(5, src.visual_column(&(10, 3).into(), 1)?);
+        Ok(())
+    }
+
+    #[test]
+    fn visual_column_past_offset() {
+        // A source claiming a span starts further into its line than the span's
+        // offset allows.
+        struct Broken;
+
+        impl SourceCode for Broken {
+            fn read_span<'a>(
+                &'a self,
+                span: &SourceSpan,
+                _: usize,
+                _: usize,
+            ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+                Ok(Box::new(MietteSpanContents::new(b"", *span, 0, 10, 1)))
+            }
+        }
+
+        assert!(matches!(
+            Broken.visual_column(&(4, 1).into(), 4),
+            Err(MietteError::OutOfBounds)
+        ));
+    }
+
+    #[test]
+    fn other_sources() -> Result<(), MietteError> {
+        fn check(src: &dyn SourceCode) -> Result<(), MietteError> {
+            let contents = src.read_span(&(7, 4).into(), 0, 0)?;
+            assert_eq!("bar\n", std::str::from_utf8(contents.data()).unwrap());
+            assert_eq!(1, contents.line());
+            assert_eq!(3, contents.column());
+            Ok(())
+        }
+
+        let src = "foo\nbarbar\nbaz\n";
+        check(&src)?;
+        check(&Arc::<str>::from(src))?;
+        check(&src.as_bytes().to_vec())?;
+        check(&Cow::Borrowed(src))?;
+        Ok(())
+    }
 }
@@ -0,0 +1,142 @@
+use std::{fmt, sync::Arc};
+
+use crate::{MietteError, SourceCode, SourceSpan, SpanContents};
+
+/**
+Maps spans in generated code, such as the output of a macro expansion or a
+transpiler, back to the original source code it was generated from, so that
+diagnostics about the generated code point at what was actually written.
+
+A `SourceMap` is made up of the original source code and a list of mappings,
+each from a span of the generated code to the span of the original it came
+from. Pass it to [`Report::with_source_map`](crate::Report::with_source_map)
+to have a report's labels and fixes mapped, and its snippets read from the
+original source code.
+
+A span within a mapping whose generated and original spans are the same length
+is mapped byte for byte. Otherwise, it is mapped to the whole original span,
+since there's no telling which part of it produced which part of the output.
+Spans that fall outside of every mapping don't correspond to anything that was
+written, and are left out.
+
+## Example
+
+```
+use miette::SourceMap;
+
+// `twice!(x)` expanded to `x + x`.
+let map = SourceMap::new("let y = twice!(x);")
+    .with_mapping((100, 5), (8, 9));
+assert_eq!(map.map_span(&(102, 1).into()), Some((8, 9).into()));
+```
+*/
+#[derive(Clone)]
+pub struct SourceMap {
+    source: Arc<dyn SourceCode>,
+    mappings: Vec<Mapping>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Mapping {
+    generated: SourceSpan,
+    original: SourceSpan,
+}
+
+impl SourceMap {
+    /// Create a new [`SourceMap`] back to the `original` source code, with no
+    /// mappings yet.
+    pub fn new(original: impl SourceCode + 'static) -> Self {
+        Self {
+            source: Arc::new(original),
+            mappings: Vec::new(),
+        }
+    }
+
+    /// Adds a mapping from a span of the generated code to the span of the
+    /// original source code that it was generated from.
+    pub fn with_mapping(
+        mut self,
+        generated: impl Into<SourceSpan>,
+        original: impl Into<SourceSpan>,
+    ) -> Self {
+        self.add_mapping(generated, original);
+        self
+    }
+
+    /// Adds a mapping from a span of the generated code to the span of the
+    /// original source code that it was generated from.
+    pub fn add_mapping(
+        &mut self,
+        generated: impl Into<SourceSpan>,
+        original: impl Into<SourceSpan>,
+    ) {
+        let mapping = Mapping {
+            generated: generated.into(),
+            original: original.into(),
+        };
+        let idx = self
+            .mappings
+            .partition_point(|m| m.generated.offset() <= mapping.generated.offset());
+        self.mappings.insert(idx, mapping);
+    }
+
+    /// Returns the original source code.
+    pub fn original(&self) -> &dyn SourceCode {
+        &*self.source
+    }
+
+    /// Maps a span of the generated code to the span of the original source
+    /// code it was generated from, if any.
+    pub fn map_span(&self, span: &SourceSpan) -> Option<SourceSpan> {
+        let start = self.map_offset(span.offset(), false)?;
+        let end = if span.is_empty() {
+            start
+        } else {
+            self.map_offset(span.offset() + span.len(), true)?
+        };
+        Some((start, end.saturating_sub(start)).into())
+    }
+
+    /// Maps an offset into the generated code. Offsets at the very end of a
+    /// mapping only belong to it when they're the end of a span.
+    fn map_offset(&self, offset: usize, is_end: bool) -> Option<usize> {
+        let mapping = self.mappings.iter().rev().find(|m| {
+            let start = m.generated.offset();
+            let end = start + m.generated.len();
+            if is_end {
+                start < offset && offset <= end
+            } else {
+                start <= offset && (offset < end || m.generated.is_empty() && offset == end)
+            }
+        })?;
+        if mapping.generated.len() == mapping.original.len() {
+            Some(mapping.original.offset() + (offset - mapping.generated.offset()))
+        } else if is_end {
+            Some(mapping.original.offset() + mapping.original.len())
+        } else {
+            Some(mapping.original.offset())
+        }
+    }
+}
+
+impl fmt::Debug for SourceMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SourceMap")
+            .field("mappings", &self.mappings)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Snippets are read from the original source code, so spans passed in
+/// should already have been mapped.
+impl SourceCode for SourceMap {
+    fn read_span<'a>(
+        &'a self,
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        self.source
+            .read_span(span, context_lines_before, context_lines_after)
+    }
+}
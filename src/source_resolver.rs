@@ -0,0 +1,118 @@
+use std::{fmt, sync::Arc};
+
+use once_cell::sync::OnceCell;
+
+use crate::{MietteError, MietteSpanContents, SourceCode, SourceSpan, SpanContents};
+
+/**
+Maps source names, such as file paths, to their [`SourceCode`] on demand.
+
+This is how a language server or build tool with its own model of files, like
+unsaved editor buffers laid over what's on disk, plugs it into snippet
+rendering: diagnostics refer to their source by name with a
+[`ResolvedSource`], and the source code is only looked up once a snippet of it
+is actually rendered.
+
+Any `Fn(&str) -> Result<Arc<dyn SourceCode>, MietteError>` is a
+`SourceResolver`.
+*/
+pub trait SourceResolver: Send + Sync {
+    /// Looks up the source code named `name`.
+    fn resolve(&self, name: &str) -> Result<Arc<dyn SourceCode>, MietteError>;
+}
+
+impl<F> SourceResolver for F
+where
+    F: Fn(&str) -> Result<Arc<dyn SourceCode>, MietteError> + Send + Sync,
+{
+    fn resolve(&self, name: &str) -> Result<Arc<dyn SourceCode>, MietteError> {
+        self(name)
+    }
+}
+
+/**
+[`SourceCode`] that refers to a source by name, and is looked up through a
+[`SourceResolver`] the first time a span is read from it. Snippets read from
+it are named after it.
+
+If resolving it fails, the error is returned, and the next read tries again.
+
+## Example
+
+```
+use std::{collections::HashMap, sync::Arc};
+
+use miette::{miette, MietteError, ResolvedSource, SourceCode, SourceResolver};
+
+let mut overlay: HashMap<String, Arc<dyn SourceCode>> = HashMap::new();
+overlay.insert("main.rs".into(), Arc::new("fn main() {}".to_string()));
+let resolver: Arc<dyn SourceResolver> = Arc::new(move |name: &str| {
+    overlay.get(name).cloned().ok_or(MietteError::OutOfBounds)
+});
+
+let report = miette!("oops!").with_source_code(ResolvedSource::new(resolver, "main.rs"));
+```
+*/
+pub struct ResolvedSource {
+    resolver: Arc<dyn SourceResolver>,
+    name: String,
+    source: OnceCell<Arc<dyn SourceCode>>,
+}
+
+impl ResolvedSource {
+    /// Create a new [`ResolvedSource`] for the source called `name`. It isn't
+    /// resolved until a span is read from it.
+    pub fn new(resolver: Arc<dyn SourceResolver>, name: impl Into<String>) -> Self {
+        Self {
+            resolver,
+            name: name.into(),
+            source: OnceCell::new(),
+        }
+    }
+
+    /// Returns the name of the source.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the source has been resolved yet.
+    pub fn is_resolved(&self) -> bool {
+        self.source.get().is_some()
+    }
+}
+
+impl fmt::Debug for ResolvedSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResolvedSource")
+            .field("name", &self.name)
+            .field("resolved", &self.is_resolved())
+            .finish()
+    }
+}
+
+impl SourceCode for ResolvedSource {
+    fn read_span<'a>(
+        &'a self,
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        let source = self
+            .source
+            .get_or_try_init(|| self.resolver.resolve(&self.name))?;
+        let contents = source.read_span(span, context_lines_before, context_lines_after)?;
+        let mut named = MietteSpanContents::new_named(
+            self.name.clone(),
+            contents.data(),
+            *contents.span(),
+            contents.line(),
+            contents.column(),
+            contents.line_count(),
+        )
+        .with_binary(contents.is_binary());
+        if let Some(language) = contents.language() {
+            named = named.with_language(language);
+        }
+        Ok(Box::new(named))
+    }
+}
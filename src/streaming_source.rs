@@ -0,0 +1,85 @@
+use std::{
+    fmt,
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::file_windows::FileWindows;
+use crate::{MietteError, SourceCode, SourceSpan, SpanContents};
+
+/**
+[`SourceCode`] for a file on disk that only ever reads the snippets asked of
+it, named after its path, for rendering snippets of inputs too large to read
+into memory.
+
+Reading a span scans the file up to it, a chunk at a time, to find which line
+it's on and where its context starts, and then reads just the span and its
+context lines. Where every thousand or so lines start is remembered along the
+way, so later reads only scan from the nearest of those before them.
+
+Each snippet read is kept in memory until the `StreamingSource` is dropped,
+since [`SpanContents`] borrow from their source, but nothing else of the file
+is, and reading a part of the file that's already been read borrows it again
+rather than keeping another copy.
+
+## Example
+
+```no_run
+use miette::{miette, StreamingSource};
+
+let source = StreamingSource::open("huge.log")?;
+let report = miette!("oops!").with_source_code(source);
+# Ok::<(), std::io::Error>(())
+```
+*/
+pub struct StreamingSource {
+    path: PathBuf,
+    file: Mutex<File>,
+    windows: FileWindows,
+}
+
+impl StreamingSource {
+    /// Opens the file at `path`. Nothing is read from it until a span is.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let file = File::open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            windows: FileWindows::default(),
+        })
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Debug for StreamingSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamingSource")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl SourceCode for StreamingSource {
+    fn read_span<'a>(
+        &'a self,
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        let mut file = self.file.lock().unwrap_or_else(|err| err.into_inner());
+        self.windows.read_span(
+            self.path.display().to_string(),
+            &mut *file,
+            span,
+            context_lines_before,
+            context_lines_after,
+        )
+    }
+}
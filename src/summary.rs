@@ -0,0 +1,143 @@
+use std::{fmt, iter::FromIterator};
+
+use crate::{Diagnostic, Severity};
+
+/**
+Counts diagnostics by severity, to print a rustc-style summary at the end of a
+batch, such as `error: aborting due to 3 previous errors; 5 warnings emitted`.
+
+Related diagnostics are counted along with the diagnostics they're attached
+to. Diagnostics without a severity, and [`Severity::Bug`]s, count as errors,
+and [`Severity::Info`] diagnostics count as advice.
+
+## Example
+
+```
+use miette::{Diagnostic, DiagnosticSummary};
+use thiserror::Error;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("oops!")]
+struct MyBad;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("careful")]
+#[diagnostic(severity(Warning))]
+struct MyWarning;
+
+let mut summary = DiagnosticSummary::new();
+summary.add(&MyBad);
+summary.add(&MyWarning);
+summary.add(&MyWarning);
+assert_eq!(
+    summary.to_string(),
+    "error: aborting due to 1 previous error; 2 warnings emitted"
+);
+```
+*/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiagnosticSummary {
+    errors: usize,
+    warnings: usize,
+    advice: usize,
+}
+
+impl DiagnosticSummary {
+    /// Create a new, empty [`DiagnosticSummary`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts a [`Diagnostic`] and all of its related diagnostics.
+    pub fn add(&mut self, diagnostic: &(dyn Diagnostic)) {
+        match diagnostic.severity() {
+            Some(Severity::Bug | Severity::Error) | None => self.errors += 1,
+            Some(Severity::Warning) => self.warnings += 1,
+            Some(Severity::Advice | Severity::Info) => self.advice += 1,
+        }
+        if let Some(related) = diagnostic.related() {
+            for rel in related {
+                self.add(rel);
+            }
+        }
+    }
+
+    /// Number of errors counted.
+    pub fn errors(&self) -> usize {
+        self.errors
+    }
+
+    /// Number of warnings counted.
+    pub fn warnings(&self) -> usize {
+        self.warnings
+    }
+
+    /// Number of advice diagnostics counted.
+    pub fn advice(&self) -> usize {
+        self.advice
+    }
+
+    /// Whether nothing has been counted, in which case the summary renders
+    /// as an empty string.
+    pub fn is_empty(&self) -> bool {
+        self.errors == 0 && self.warnings == 0 && self.advice == 0
+    }
+}
+
+impl<'a> Extend<&'a (dyn Diagnostic + 'a)> for DiagnosticSummary {
+    fn extend<I: IntoIterator<Item = &'a (dyn Diagnostic + 'a)>>(&mut self, diagnostics: I) {
+        for diagnostic in diagnostics {
+            self.add(diagnostic);
+        }
+    }
+}
+
+impl<'a> FromIterator<&'a (dyn Diagnostic + 'a)> for DiagnosticSummary {
+    fn from_iter<I: IntoIterator<Item = &'a (dyn Diagnostic + 'a)>>(diagnostics: I) -> Self {
+        let mut summary = Self::new();
+        summary.extend(diagnostics);
+        summary
+    }
+}
+
+fn plural(count: usize, singular: &str, plural: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, singular)
+    } else {
+        format!("{} {}", count, plural)
+    }
+}
+
+impl fmt::Display for DiagnosticSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut emitted = Vec::new();
+        if self.warnings > 0 {
+            emitted.push(plural(self.warnings, "warning", "warnings"));
+        }
+        if self.advice > 0 {
+            emitted.push(plural(self.advice, "advice", "advice"));
+        }
+        let emitted = match emitted.len() {
+            0 => None,
+            _ => Some(format!("{} emitted", emitted.join(", "))),
+        };
+        if self.errors > 0 {
+            write!(
+                f,
+                "error: aborting due to {}",
+                plural(self.errors, "previous error", "previous errors")
+            )?;
+            if let Some(emitted) = emitted {
+                write!(f, "; {}", emitted)?;
+            }
+        } else if let Some(emitted) = emitted {
+            let severity = if self.warnings > 0 {
+                "warning"
+            } else {
+                "advice"
+            };
+            write!(f, "{}: {}", severity, emitted)?;
+        }
+        Ok(())
+    }
+}
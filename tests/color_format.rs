@@ -1,7 +1,9 @@
 #![cfg(feature = "fancy-no-backtrace")]
 
 use lazy_static::lazy_static;
-use miette::{Diagnostic, MietteHandler, MietteHandlerOpts, ReportHandler, RgbColors};
+use miette::{
+    Diagnostic, GraphicalTheme, MietteHandler, MietteHandlerOpts, ReportHandler, RgbColors,
+};
 use regex::Regex;
 use std::fmt::{self, Debug};
 use std::sync::Mutex;
@@ -90,6 +92,71 @@ fn check_colors<F: Fn(MietteHandlerOpts) -> MietteHandlerOpts>(
     drop(guard);
 }
 
+#[test]
+fn color_env_vars() {
+    let guard = COLOR_ENV_VARS.lock().unwrap();
+
+    with_env_var("CLICOLOR_FORCE", "1", || {
+        let handler = MietteHandlerOpts::new().build();
+        assert_eq!(color_format(handler), ColorFormat::Ansi);
+    });
+    with_env_var("CLICOLOR", "0", || {
+        let handler = MietteHandlerOpts::new().build();
+        assert_eq!(color_format(handler), ColorFormat::NoColor);
+        with_env_var("CLICOLOR_FORCE", "1", || {
+            let handler = MietteHandlerOpts::new().build();
+            assert_eq!(color_format(handler), ColorFormat::Ansi);
+        });
+    });
+    with_env_var("NO_COLOR", "1", || {
+        with_env_var("CLICOLOR_FORCE", "1", || {
+            let handler = MietteHandlerOpts::new().build();
+            assert_eq!(color_format(handler), ColorFormat::NoColor);
+            let handler = MietteHandlerOpts::new().color(true).build();
+            assert_eq!(color_format(handler), ColorFormat::Ansi);
+        });
+    });
+    // `0` leaves the other variable free to force colors on.
+    with_env_var("FORCE_COLOR", "0", || {
+        with_env_var("CLICOLOR_FORCE", "1", || {
+            let handler = MietteHandlerOpts::new().build();
+            assert_eq!(color_format(handler), ColorFormat::Ansi);
+        });
+    });
+    with_env_var("CLICOLOR_FORCE", "0", || {
+        with_env_var("FORCE_COLOR", "1", || {
+            let handler = MietteHandlerOpts::new().build();
+            assert_eq!(color_format(handler), ColorFormat::Ansi);
+        });
+    });
+
+    drop(guard);
+}
+
+#[test]
+fn default_theme_env_vars() {
+    let guard = COLOR_ENV_VARS.lock().unwrap();
+
+    // The default theme follows the environment whether or not it's writing
+    // to a terminal.
+    let default_theme = || {
+        MietteHandlerOpts::new()
+            .graphical_theme(GraphicalTheme::default())
+            .build()
+    };
+    with_env_var("NO_COLOR", "1", || {
+        assert_eq!(color_format(default_theme()), ColorFormat::NoColor);
+    });
+    with_env_var("CLICOLOR", "0", || {
+        assert_eq!(color_format(default_theme()), ColorFormat::NoColor);
+    });
+    with_env_var("FORCE_COLOR", "1", || {
+        assert_ne!(color_format(default_theme()), ColorFormat::NoColor);
+    });
+
+    drop(guard);
+}
+
 #[test]
 fn no_color_preference() {
     use ColorFormat::*;
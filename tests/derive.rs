@@ -1,4 +1,6 @@
-use miette::{Diagnostic, Report, Severity, SourceSpan};
+use miette::{
+    Diagnostic, DiagnosticMetadata, DiagnosticTag, Report, Severity, SourceComparison, SourceSpan,
+};
 use thiserror::Error;
 
 #[test]
@@ -153,6 +155,27 @@ fn path_severity() {
     }
 
     assert_eq!(Some(Severity::Warning), FooEnum::X.severity());
+
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("welp")]
+    enum Levels {
+        #[diagnostic(severity(bug))]
+        Bug,
+        #[diagnostic(severity(Info))]
+        Info,
+        #[diagnostic(severity("Info"))]
+        InfoStr,
+        #[diagnostic(severity(info))]
+        Advice,
+        #[diagnostic(severity("INFO"))]
+        AdviceUpper,
+    }
+
+    assert_eq!(Some(Severity::Bug), Levels::Bug.severity());
+    assert_eq!(Some(Severity::Info), Levels::Info.severity());
+    assert_eq!(Some(Severity::Info), Levels::InfoStr.severity());
+    assert_eq!(Some(Severity::Advice), Levels::Advice.severity());
+    assert_eq!(Some(Severity::Advice), Levels::AdviceUpper.severity());
 }
 
 #[test]
@@ -304,6 +327,222 @@ fn help_field() {
     );
 }
 
+#[test]
+fn note() {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("welp")]
+    #[diagnostic(note("{name} is reserved"))]
+    struct Foo {
+        name: String,
+    }
+
+    assert_eq!(
+        "x is reserved".to_string(),
+        Foo { name: "x".into() }.note().unwrap().to_string()
+    );
+
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("welp")]
+    enum Bar {
+        A(#[note] Option<String>),
+        B,
+    }
+
+    assert_eq!(
+        "x".to_string(),
+        Bar::A(Some("x".into())).note().unwrap().to_string()
+    );
+    assert!(Bar::A(None).note().is_none());
+    assert!(Bar::B.note().is_none());
+}
+
+#[test]
+fn expected_found() {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("welp")]
+    #[diagnostic(expected("{want} items"), found("{got} items"))]
+    struct Foo {
+        want: usize,
+        got: usize,
+    }
+
+    let foo = Foo { want: 2, got: 3 };
+    assert_eq!("2 items".to_string(), foo.expected().unwrap().to_string());
+    assert_eq!("3 items".to_string(), foo.found().unwrap().to_string());
+
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("welp")]
+    enum Bar {
+        A {
+            #[expected]
+            expected: u32,
+            #[found]
+            found: Option<u32>,
+        },
+        B,
+    }
+
+    let bar = Bar::A {
+        expected: 1,
+        found: Some(2),
+    };
+    assert_eq!("1".to_string(), bar.expected().unwrap().to_string());
+    assert_eq!("2".to_string(), bar.found().unwrap().to_string());
+    let bar = Bar::A {
+        expected: 1,
+        found: None,
+    };
+    assert!(bar.found().is_none());
+    assert!(Bar::B.expected().is_none());
+    assert!(Bar::B.found().is_none());
+}
+
+#[test]
+fn suggestion_field() {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("welp")]
+    #[diagnostic()]
+    struct Foo {
+        #[suggestion]
+        candidate: Option<String>,
+    }
+
+    assert_eq!(
+        "x".to_string(),
+        Foo {
+            candidate: Some("x".into())
+        }
+        .suggestion()
+        .unwrap()
+        .to_string()
+    );
+    assert!(Foo { candidate: None }.suggestion().is_none());
+
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("welp")]
+    #[diagnostic()]
+    enum Bar {
+        A(#[suggestion] String),
+        #[diagnostic(suggestion("y{}", 1))]
+        B,
+        C,
+    }
+
+    assert_eq!(
+        "x".to_string(),
+        Bar::A("x".into()).suggestion().unwrap().to_string()
+    );
+    assert_eq!("y1".to_string(), Bar::B.suggestion().unwrap().to_string());
+    assert!(Bar::C.suggestion().is_none());
+}
+
+#[test]
+fn tags() {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("welp")]
+    #[diagnostic(tags(deprecated, unnecessary))]
+    struct Foo;
+
+    assert_eq!(
+        vec![DiagnosticTag::Deprecated, DiagnosticTag::Unnecessary],
+        Foo.tags().unwrap().collect::<Vec<_>>()
+    );
+
+    #[derive(Debug, Diagnostic, Error)]
+    enum Bar {
+        #[error("a")]
+        #[diagnostic(tags(unused))]
+        A,
+        #[error("b")]
+        #[diagnostic(transparent)]
+        B(Foo),
+        #[error("c")]
+        C,
+    }
+
+    assert_eq!(
+        vec![DiagnosticTag::Unnecessary],
+        Bar::A.tags().unwrap().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![DiagnosticTag::Deprecated, DiagnosticTag::Unnecessary],
+        Bar::B(Foo).tags().unwrap().collect::<Vec<_>>()
+    );
+    assert!(Bar::C.tags().is_none());
+}
+
+#[test]
+fn metadata() {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("welp")]
+    struct Foo {
+        #[metadata]
+        metadata: DiagnosticMetadata,
+    }
+
+    let foo = Foo {
+        metadata: vec![("rule", "no-unused")].into_iter().collect(),
+    };
+    assert_eq!(Some("no-unused"), foo.metadata().unwrap().get("rule"));
+
+    #[derive(Debug, Diagnostic, Error)]
+    enum Bar {
+        #[error("a")]
+        A(#[metadata] DiagnosticMetadata),
+        #[error("b")]
+        #[diagnostic(transparent)]
+        B(Foo),
+        #[error("c")]
+        C,
+    }
+
+    let bar = Bar::A(DiagnosticMetadata::new().with("lint_group", "style"));
+    assert_eq!(Some("style"), bar.metadata().unwrap().get("lint_group"));
+    let bar = Bar::B(Foo {
+        metadata: DiagnosticMetadata::new().with("rule", "no-unused"),
+    });
+    assert_eq!(1, bar.metadata().unwrap().len());
+    assert!(Bar::C.metadata().is_none());
+}
+
+#[test]
+fn comparison() {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("welp")]
+    struct Foo {
+        #[comparison]
+        comparison: SourceComparison,
+    }
+
+    let foo = Foo {
+        comparison: SourceComparison::new("old", "a = 1", "new", "a = \"1\"").with_spans(
+            None,
+            (4, 1),
+            (4, 3),
+        ),
+    };
+    let comparison = foo.comparison().unwrap();
+    assert_eq!("old", comparison.old_name());
+    assert_eq!(&SourceSpan::from((4, 3)), comparison.spans()[0].new_span());
+
+    #[derive(Debug, Diagnostic, Error)]
+    enum Bar {
+        #[error("a")]
+        A(#[comparison] SourceComparison),
+        #[error("b")]
+        #[diagnostic(transparent)]
+        B(Foo),
+        #[error("c")]
+        C,
+    }
+
+    let bar = Bar::A(SourceComparison::new("old", "", "new", ""));
+    assert!(bar.comparison().unwrap().spans().is_empty());
+    let bar = Bar::B(foo);
+    assert_eq!("new", bar.comparison().unwrap().new_name());
+    assert!(Bar::C.comparison().is_none());
+}
+
 #[test]
 fn test_snippet_named_struct() {
     #[derive(Debug, Diagnostic, Error)]
@@ -376,6 +615,44 @@ fn test_snippet_enum() {
     }
 }
 
+#[test]
+fn test_snippet_primary_label() {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("welp")]
+    #[allow(dead_code)]
+    enum Foo {
+        A {
+            #[source_code]
+            src: String,
+            #[label("first")]
+            var0: SourceSpan,
+            #[label(primary, "second {src}")]
+            var1: SourceSpan,
+        },
+        B(
+            #[source_code] String,
+            #[label(primary)] SourceSpan,
+            #[label] SourceSpan,
+        ),
+    }
+
+    let foo = Foo::A {
+        src: "x".into(),
+        var0: (0, 1).into(),
+        var1: (1, 1).into(),
+    };
+    let labels = foo.labels().unwrap().collect::<Vec<_>>();
+    assert!(!labels[0].primary());
+    assert!(labels[1].primary());
+    assert_eq!(labels[1].label(), Some("second x"));
+
+    let foo = Foo::B("x".into(), (0, 1).into(), (1, 1).into());
+    let labels = foo.labels().unwrap().collect::<Vec<_>>();
+    assert!(labels[0].primary());
+    assert_eq!(labels[0].label(), None);
+    assert!(!labels[1].primary());
+}
+
 #[test]
 fn url_basic() {
     #[derive(Debug, Diagnostic, Error)]
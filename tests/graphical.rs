@@ -1,8 +1,8 @@
 #![cfg(feature = "fancy-no-backtrace")]
 
 use miette::{
-    Diagnostic, GraphicalReportHandler, GraphicalTheme, MietteError, NamedSource,
-    NarratableReportHandler, Report, SourceSpan,
+    ComparisonLayout, Diagnostic, GraphicalReportHandler, GraphicalTheme, MietteError, NamedSource,
+    NarratableReportHandler, Report, SourceComparison, SourceSpan,
 };
 use thiserror::Error;
 
@@ -53,11 +53,13 @@ fn empty_source() -> Result<(), MietteError> {
     };
     let out = fmt_report(err.into());
     println!("Error: {}", out);
-    // For an empty string, the label cannot be rendered.
     let expected = r#"oops::my::bad
 
   × oops!
    ╭─[bad_file.rs:1:1]
+ 1 │ 
+   · ▲
+   · ╰── this bit here
    ╰────
   help: try doing it better next time?
 "#
@@ -124,8 +126,8 @@ fn single_line_with_wide_char() -> Result<(), MietteError> {
    ╭─[bad_file.rs:1:1]
  1 │ source
  2 │   👼🏼text
-   ·     ───┬──
-   ·        ╰── this bit here
+   ·     ──┬─
+   ·       ╰── this bit here
  3 │     here
    ╰────
   help: try doing it better next time?
@@ -136,6 +138,39 @@ fn single_line_with_wide_char() -> Result<(), MietteError> {
     Ok(())
 }
 
+#[test]
+fn single_line_with_grapheme_clusters() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("this bit here")]
+        highlight: SourceSpan,
+    }
+
+    // "e" followed by a combining acute accent, and a zero-width-joined
+    // family emoji, are both a single grapheme cluster.
+    let src = "cafe\u{301} \u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467} text".to_string();
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", src),
+        highlight: (26, 4).into(),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = "
+  × oops!
+   ╭─[bad_file.rs:1:1]
+ 1 │ cafe\u{301} \u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467} text
+   ·         ──┬─
+   ·           ╰── this bit here
+   ╰────
+"
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
 #[test]
 fn single_line_with_two_tabs() -> Result<(), MietteError> {
     #[derive(Debug, Diagnostic, Error)]
@@ -251,6 +286,124 @@ fn single_line_highlight() -> Result<(), MietteError> {
     Ok(())
 }
 
+#[test]
+fn note() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    #[diagnostic(
+        code(oops::my::bad),
+        note("text is reserved for future use"),
+        help("try doing it better next time?")
+    )]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("this bit here")]
+        highlight: SourceSpan,
+    }
+
+    let src = "source\n  text\n    here".to_string();
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", src),
+        highlight: (9, 4).into(),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"oops::my::bad
+
+  × oops!
+   ╭─[bad_file.rs:1:1]
+ 1 │ source
+ 2 │   text
+   ·   ──┬─
+   ·     ╰── this bit here
+ 3 │     here
+   ╰────
+  note: text is reserved for future use
+  help: try doing it better next time?
+"#
+    .trim_start()
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn expected_found() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("assertion failed")]
+    #[diagnostic(code(oops::my::bad), help("update the snapshot"))]
+    struct MyBad {
+        #[expected]
+        expected: String,
+        #[found]
+        found: String,
+    }
+
+    let err = MyBad {
+        expected: "[1, 2]".into(),
+        found: "[\n  1,\n  3,\n]".into(),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"oops::my::bad
+
+  × assertion failed
+  expected: [1, 2]
+     found: [
+              1,
+              3,
+            ]
+  help: update the snapshot
+"#
+    .trim_start()
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn suggestion() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("unknown variable `{name}`")]
+    #[diagnostic(code(oops::my::bad), help("variables must be declared before use"))]
+    struct UnknownVariable {
+        name: String,
+        #[source_code]
+        src: NamedSource,
+        #[label("not found in this scope")]
+        highlight: SourceSpan,
+        #[suggestion]
+        candidate: Option<String>,
+    }
+
+    let src = "let text = 1;\nprint(txet)".to_string();
+    let err = UnknownVariable {
+        name: "txet".into(),
+        src: NamedSource::new("bad_file.rs", src),
+        highlight: (20, 4).into(),
+        candidate: Some("text".into()),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"oops::my::bad
+
+  × unknown variable `txet`
+   ╭─[bad_file.rs:1:1]
+ 1 │ let text = 1;
+ 2 │ print(txet)
+   ·       ──┬─
+   ·         ╰── not found in this scope
+   ╰────
+  help: variables must be declared before use
+  help: did you mean `text`?
+"#
+    .trim_start()
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
 #[test]
 fn external_source() -> Result<(), MietteError> {
     #[derive(Debug, Diagnostic, Error)]
@@ -286,6 +439,44 @@ fn external_source() -> Result<(), MietteError> {
     Ok(())
 }
 
+#[test]
+fn zero_based_line_numbers() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("this bit here")]
+        highlight: SourceSpan,
+    }
+
+    let src = "source\n  text\n    here".to_string();
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", src),
+        highlight: (9, 4).into(),
+    };
+    let mut out = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .with_width(80)
+        .with_zero_based_line_numbers(true)
+        .render_report(&mut out, &err)
+        .unwrap();
+    println!("Error: {}", out);
+    let expected = r#"
+  × oops!
+   ╭─[bad_file.rs:0:0]
+ 0 │ source
+ 1 │   text
+   ·   ──┬─
+   ·     ╰── this bit here
+ 2 │     here
+   ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
 #[test]
 fn single_line_highlight_offset_zero() -> Result<(), MietteError> {
     #[derive(Debug, Diagnostic, Error)]
@@ -395,6 +586,88 @@ fn single_line_highlight_with_empty_span() -> Result<(), MietteError> {
     Ok(())
 }
 
+#[test]
+fn single_line_highlight_with_empty_span_at_end_of_source() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("expected `}}` here")]
+        highlight: SourceSpan,
+    }
+
+    let src = "fn main() {\n    oops();\n".to_string();
+    let len = src.len();
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", src),
+        highlight: SourceSpan::point(len),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"
+  × oops!
+   ╭─[bad_file.rs:2:1]
+ 2 │     oops();
+ 3 │ 
+   · ▲
+   · ╰── expected `}` here
+   ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn single_line_highlight_with_empty_span_at_end_of_unterminated_source() -> Result<(), MietteError>
+{
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("here")]
+        highlight: SourceSpan,
+    }
+
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", "abc".to_string()),
+        highlight: SourceSpan::point(3),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"
+  × oops!
+   ╭─[bad_file.rs:1:1]
+ 1 │ abc
+   ·    ▲
+   ·    ╰── here
+   ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", "abc\ndef".to_string()),
+        highlight: SourceSpan::point(7),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"
+  × oops!
+   ╭─[bad_file.rs:1:1]
+ 1 │ abc
+ 2 │ def
+   ·    ▲
+   ·    ╰── here
+   ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
 #[test]
 fn single_line_highlight_no_label() -> Result<(), MietteError> {
     #[derive(Debug, Diagnostic, Error)]
@@ -646,66 +919,272 @@ line5
 }
 
 #[test]
-fn multiline_highlight_no_label() -> Result<(), MietteError> {
+fn multiline_highlight_max_snippet_lines() -> Result<(), MietteError> {
     #[derive(Debug, Diagnostic, Error)]
-    #[error("wtf?!\nit broke :(")]
-    #[diagnostic(code(oops::my::bad), help("try doing it better next time?"))]
+    #[error("oops!")]
     struct MyBad {
-        #[source]
-        source: Inner,
         #[source_code]
         src: NamedSource,
-        #[label = "block 1"]
-        highlight1: SourceSpan,
-        #[label]
-        highlight2: SourceSpan,
+        #[label = "all of it"]
+        highlight: SourceSpan,
     }
 
-    #[derive(Debug, Error)]
-    #[error("something went wrong\n\nHere's a more detailed explanation of everything that actually went wrong because it's actually important.\n")]
-    struct Inner(#[source] InnerInner);
-
-    #[derive(Debug, Error)]
-    #[error("very much went wrong")]
-    struct InnerInner;
-
-    let src = r#"line1
-line2
-line3
-line4
-line5
-"#
-    .to_string();
+    let src = (1..=10).map(|i| format!("line{}\n", i)).collect::<String>();
     let len = src.len();
     let err = MyBad {
-        source: Inner(InnerInner),
         src: NamedSource::new("bad_file.rs", src),
-        highlight1: (0, len).into(),
-        highlight2: (10, 9).into(),
+        highlight: (0, len).into(),
     };
-    let out = fmt_report(err.into());
+    let mut out = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .with_width(80)
+        .with_context_lines(5)
+        .with_max_snippet_lines(4)
+        .render_report(&mut out, &err)
+        .unwrap();
     println!("Error: {}", out);
-    let expected = "oops::my::bad
-
-  × wtf?!
-  │ it broke :(
-  ├─▶ something went wrong
-  │\u{20}\u{20}\u{20}
-  │   Here's a more detailed explanation of everything that actually went
-  │   wrong because it's actually important.
-  │\u{20}\u{20}\u{20}
-  ╰─▶ very much went wrong
-   ╭─[bad_file.rs:1:1]
- 1 │ ╭──▶ line1
- 2 │ │╭─▶ line2
- 3 │ ││   line3
- 4 │ │╰─▶ line4
- 5 │ ├──▶ line5
-   · ╰───── block 1
-   ╰────
-  help: try doing it better next time?
-"
-    .trim_start()
+    let expected = r#"
+  × oops!
+    ╭─[bad_file.rs:1:1]
+  1 │ ╭─▶ line1
+  2 │ │   line2
+    · │   ... 6 lines omitted ...
+  9 │ │   line9
+ 10 │ ├─▶ line10
+    · ╰──── all of it
+    ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+/// Drops the SGR escape sequences that styles are written with.
+fn strip_styles(styled: &str) -> String {
+    let mut out = String::new();
+    let mut chars = styled.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            chars.by_ref().find(|c| *c == 'm');
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[test]
+fn styled_omitted_lines_keep_their_gutter_aligned() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label = "all of it"]
+        all: SourceSpan,
+        #[label = "the start"]
+        start: SourceSpan,
+    }
+
+    let src = (1..=10).map(|i| format!("line{}\n", i)).collect::<String>();
+    let len = src.len();
+    let render = |theme| {
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src.clone()),
+            all: (0, len).into(),
+            start: (0, 8).into(),
+        };
+        let mut out = String::new();
+        GraphicalReportHandler::new_themed(theme)
+            .with_width(80)
+            .with_context_lines(5)
+            .with_max_snippet_lines(4)
+            .render_report(&mut out, &err)
+            .unwrap();
+        out
+    };
+    let plain = render(GraphicalTheme::unicode_nocolor());
+    let styled = render(GraphicalTheme::unicode());
+    println!("Error: {}", styled);
+    assert!(plain.contains("· │    ... 6 lines omitted ...\n"));
+    assert_ne!(plain, styled);
+    assert_eq!(plain, strip_styles(&styled));
+    Ok(())
+}
+
+#[test]
+fn header_column_expands_tabs() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label = "this bit here"]
+        highlight: SourceSpan,
+    }
+
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", "\tlet x = oops;\n"),
+        highlight: (9, 4).into(),
+    };
+    let mut out = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .with_width(80)
+        .with_context_lines(0)
+        .tab_width(4)
+        .render_report(&mut out, &err)
+        .unwrap();
+    println!("Error: {}", out);
+    let expected = r#"
+  × oops!
+   ╭─[bad_file.rs:1:13]
+ 1 │ oops
+   · ──┬─
+   ·   ╰── this bit here
+   ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn multiline_highlight_elided_lines() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label = "block 1"]
+        highlight1: SourceSpan,
+        #[label = "block 2"]
+        highlight2: SourceSpan,
+    }
+
+    let src = (1..=20).map(|i| format!("line{}\n", i)).collect::<String>();
+    let len = src.len();
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", src),
+        highlight1: (0, len).into(),
+        highlight2: (54, 13).into(),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"
+  × oops!
+    ╭─[bad_file.rs:1:1]
+  1 │ ╭──▶ line1
+  2 │ │    line2
+    · │    ...
+  9 │ │    line9
+ 10 │ │╭─▶ line10
+ 11 │ │├─▶ line11
+    · │╰──── block 2
+ 12 │ │    line12
+    · │    ...
+ 19 │ │    line19
+ 20 │ ├──▶ line20
+    · ╰───── block 1
+    ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn duplicate_cause_messages() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("failed to read config")]
+    #[diagnostic(code(oops::my::bad))]
+    struct MyBad {
+        #[source]
+        source: Inner,
+    }
+
+    #[derive(Debug, Error)]
+    #[error("failed to read config")]
+    struct Inner(#[source] std::io::Error);
+
+    let err = MyBad {
+        source: Inner(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "file not found",
+        )),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = "oops::my::bad
+
+  × failed to read config
+  ╰─▶ file not found
+"
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn multiline_highlight_no_label() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("wtf?!\nit broke :(")]
+    #[diagnostic(code(oops::my::bad), help("try doing it better next time?"))]
+    struct MyBad {
+        #[source]
+        source: Inner,
+        #[source_code]
+        src: NamedSource,
+        #[label = "block 1"]
+        highlight1: SourceSpan,
+        #[label]
+        highlight2: SourceSpan,
+    }
+
+    #[derive(Debug, Error)]
+    #[error("something went wrong\n\nHere's a more detailed explanation of everything that actually went wrong because it's actually important.\n")]
+    struct Inner(#[source] InnerInner);
+
+    #[derive(Debug, Error)]
+    #[error("very much went wrong")]
+    struct InnerInner;
+
+    let src = r#"line1
+line2
+line3
+line4
+line5
+"#
+    .to_string();
+    let len = src.len();
+    let err = MyBad {
+        source: Inner(InnerInner),
+        src: NamedSource::new("bad_file.rs", src),
+        highlight1: (0, len).into(),
+        highlight2: (10, 9).into(),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = "oops::my::bad
+
+  × wtf?!
+  │ it broke :(
+  ├─▶ something went wrong
+  │\u{20}\u{20}\u{20}
+  │   Here's a more detailed explanation of everything that actually went
+  │   wrong because it's actually important.
+  │\u{20}\u{20}\u{20}
+  ╰─▶ very much went wrong
+   ╭─[bad_file.rs:1:1]
+ 1 │ ╭──▶ line1
+ 2 │ │╭─▶ line2
+ 3 │ ││   line3
+ 4 │ │╰─▶ line4
+ 5 │ ├──▶ line5
+   · ╰───── block 1
+   ╰────
+  help: try doing it better next time?
+"
+    .trim_start()
     .to_string();
     assert_eq!(expected, out);
     Ok(())
@@ -867,6 +1346,160 @@ fn disable_url_links() -> Result<(), MietteError> {
     Ok(())
 }
 
+#[test]
+fn docs_url_template() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    #[diagnostic(code(oops::my::bad))]
+    struct MyBad;
+    let err = MyBad;
+    let mut out = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .with_docs_url("https://example.com/errors/{code}")
+        .render_report(&mut out, &err)
+        .unwrap();
+    println!("Error: {}", out);
+    assert!(out.contains("\u{1b}]8;;https://example.com/errors/oops::my::bad\u{1b}\\"));
+    assert!(out.contains("(link)"));
+
+    let mut out = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .with_links(false)
+        .with_docs_url("https://example.com/errors/{code}")
+        .render_report(&mut out, &err)
+        .unwrap();
+    println!("Error: {}", out);
+    assert!(out.contains("oops::my::bad (https://example.com/errors/oops::my::bad)"));
+    assert!(!out.contains("\u{1b}]8;;"));
+    Ok(())
+}
+
+/// Builds the `file://` URL that file links to `path` should point at:
+/// everything but unreserved characters, slashes and a Windows drive colon is
+/// percent-encoded.
+fn expected_file_url(path: &std::path::Path) -> String {
+    let path = path.to_str().unwrap().replace('\\', "/");
+    let mut url = String::from("file://");
+    let mut rest = path.as_str();
+    if !rest.starts_with('/') {
+        url.push('/');
+        if rest.as_bytes().get(1) == Some(&b':') {
+            url.push_str(&rest[..2]);
+            rest = &rest[2..];
+        }
+    }
+    for byte in rest.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            url.push(byte as char);
+        } else {
+            url.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    url
+}
+
+#[test]
+fn file_links() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("this bit here")]
+        highlight: SourceSpan,
+    }
+
+    let err = MyBad {
+        src: NamedSource::new("Cargo.toml", "[package]".to_string()),
+        highlight: (1, 7).into(),
+    };
+    let mut out = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .with_file_links(true)
+        .render_report(&mut out, &err)
+        .unwrap();
+    println!("Error: {}", out);
+    let url = expected_file_url(&std::env::current_dir().unwrap().join("Cargo.toml"));
+    assert!(out.contains(&format!(
+        "\u{1b}]8;;{}\u{1b}\\Cargo.toml\u{1b}]8;;\u{1b}\\:1:1]",
+        url
+    )));
+
+    // File links are opt-in.
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    assert!(out.contains("[Cargo.toml:1:1]"));
+    assert!(!out.contains("\u{1b}]8;;"));
+    Ok(())
+}
+
+#[test]
+fn file_links_are_percent_encoded() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("this bit here")]
+        highlight: SourceSpan,
+    }
+
+    let dir = std::env::temp_dir().join(format!("miette-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("a b#c%d[e].toml");
+    std::fs::write(&path, "[package]").unwrap();
+    let err = MyBad {
+        src: NamedSource::new(path.to_str().unwrap(), "[package]".to_string()),
+        highlight: (1, 7).into(),
+    };
+    let mut out = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .with_file_links(true)
+        .render_report(&mut out, &err)
+        .unwrap();
+    println!("Error: {}", out);
+    std::fs::remove_dir_all(&dir).unwrap();
+    let url = expected_file_url(&path);
+    assert!(url.ends_with("/a%20b%23c%25d%5Be%5D.toml"));
+    assert!(out.contains(&format!("\u{1b}]8;;{}\u{1b}\\", url)));
+    Ok(())
+}
+
+#[test]
+fn file_links_follow_the_file_system() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("this bit here")]
+        highlight: SourceSpan,
+    }
+
+    let dir = std::env::temp_dir().join(format!("miette-test-fs-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("later.toml");
+    let err = MyBad {
+        src: NamedSource::new(path.to_str().unwrap(), "[package]".to_string()),
+        highlight: (1, 7).into(),
+    };
+    let handler =
+        GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor()).with_file_links(true);
+
+    let mut out = String::new();
+    handler.render_report(&mut out, &err).unwrap();
+    assert!(!out.contains("\u{1b}]8;;"));
+
+    // The same handler links the source once the file exists.
+    std::fs::write(&path, "[package]").unwrap();
+    let mut out = String::new();
+    handler.render_report(&mut out, &err).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    println!("Error: {}", out);
+    assert!(out.contains(&format!("\u{1b}]8;;{}\u{1b}\\", expected_file_url(&path))));
+    Ok(())
+}
+
 #[test]
 fn related() -> Result<(), MietteError> {
     #[derive(Debug, Diagnostic, Error)]
@@ -924,10 +1557,133 @@ Error: oops::my::bad
 }
 
 #[test]
-fn related_source_code_propagation() -> Result<(), MietteError> {
+fn related_severity() -> Result<(), MietteError> {
     #[derive(Debug, Diagnostic, Error)]
     #[error("oops!")]
-    #[diagnostic(code(oops::my::bad), help("try doing it better next time?"))]
+    #[diagnostic(code(oops::my::bad))]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("this bit here")]
+        highlight: SourceSpan,
+        #[related]
+        related: Vec<MyWarning>,
+    }
+
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("first defined here")]
+    #[diagnostic(severity(Warning))]
+    struct MyWarning {
+        #[label("here")]
+        highlight: SourceSpan,
+    }
+
+    let src = "source\n  text\n    here".to_string();
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", src),
+        highlight: (9, 4).into(),
+        related: vec![MyWarning {
+            highlight: (0, 6).into(),
+        }],
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"oops::my::bad
+
+  × oops!
+   ╭─[bad_file.rs:1:1]
+ 1 │ source
+ 2 │   text
+   ·   ──┬─
+   ·     ╰── this bit here
+ 3 │     here
+   ╰────
+
+Warning: 
+  ⚠ first defined here
+   ╭─[bad_file.rs:1:1]
+ 1 │ source
+   · ───┬──
+   ·    ╰── here
+ 2 │   text
+   ╰────
+"#
+    .trim_start()
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn bug_and_info_severity() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    #[diagnostic(code(oops::my::bad), severity(Bug))]
+    struct MyBug {
+        #[related]
+        related: Vec<MyInfo>,
+    }
+
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("for your information")]
+    #[diagnostic(severity(Info))]
+    struct MyInfo;
+
+    let err = MyBug {
+        related: vec![MyInfo],
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"oops::my::bad
+
+  ‼ oops!
+
+Info: 
+  ℹ for your information
+"#
+    .trim_start()
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn tagged_labels() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("use of deprecated function")]
+    #[diagnostic(tags(deprecated))]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("deprecated")]
+        highlight: SourceSpan,
+    }
+
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", "old_fn();".to_string()),
+        highlight: (0, 6).into(),
+    };
+    let out = fmt_report(err.into());
+    assert!(out.contains("╰── deprecated"));
+
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", "old_fn();".to_string()),
+        highlight: (0, 6).into(),
+    };
+    let mut out = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode())
+        .render_report(&mut out, &err)
+        .unwrap();
+    // The label text is struck through.
+    assert!(out.contains("\u{1b}[9mdeprecated\u{1b}[0m"));
+    Ok(())
+}
+
+#[test]
+fn related_source_code_propagation() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    #[diagnostic(code(oops::my::bad), help("try doing it better next time?"))]
     struct MyBad {
         #[source_code]
         src: NamedSource,
@@ -1014,3 +1770,543 @@ fn zero_length_eol_span() {
 
     assert_eq!(expected, out);
 }
+
+#[test]
+fn custom_syntax_highlighter() {
+    use miette::highlighters::{Highlighter, HighlighterState};
+    use owo_colors::{Style, Styled};
+
+    struct FirstWordHighlighter;
+
+    impl Highlighter for FirstWordHighlighter {
+        fn start_highlighter_state<'h>(
+            &'h self,
+            _source: &dyn miette::SpanContents<'_>,
+        ) -> Box<dyn HighlighterState + 'h> {
+            Box::new(FirstWordHighlighter)
+        }
+    }
+
+    impl HighlighterState for FirstWordHighlighter {
+        fn highlight_line<'s>(&mut self, line: &'s str) -> Vec<Styled<&'s str>> {
+            let (first, rest) = line.split_at(line.find(' ').unwrap_or(line.len()));
+            vec![Style::new().bold().style(first), Style::new().style(rest)]
+        }
+    }
+
+    #[derive(Error, Debug, Diagnostic)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("This bit here")]
+        bad_bit: SourceSpan,
+    }
+    let err = MyBad {
+        src: NamedSource::new("issue", "let\tx = 1;"),
+        bad_bit: (4, 1).into(),
+    };
+    let mut out = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .with_width(80)
+        .with_syntax_highlighting(FirstWordHighlighter)
+        .render_report(&mut out, &err)
+        .unwrap();
+    println!("Error: {}", out);
+
+    let expected = "
+  × oops!
+   ╭─[issue:1:1]
+ 1 │ \u{1b}[1mlet\tx\u{1b}[0m = 1;
+   ·     ┬
+   ·     ╰── This bit here
+   ╰────
+"
+    .replace('\t', " ");
+
+    assert_eq!(expected, out);
+}
+
+#[cfg(feature = "syntect-highlighter")]
+#[test]
+fn syntect_highlighting() {
+    use miette::highlighters::SyntectHighlighter;
+
+    #[derive(Error, Debug, Diagnostic)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("This bit here")]
+        bad_bit: SourceSpan,
+    }
+    let render = |src: NamedSource| {
+        let mut out = String::new();
+        GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+            .with_width(80)
+            .with_syntax_highlighting(SyntectHighlighter::default())
+            .render_report(
+                &mut out,
+                &MyBad {
+                    src,
+                    bad_bit: (3, 4).into(),
+                },
+            )
+            .unwrap();
+        out
+    };
+
+    // Detected from the file extension...
+    assert!(render(NamedSource::new("main.rs", "fn main() {}")).contains("\u{1b}[38;2;"));
+    // ...or from an explicit language hint...
+    assert!(
+        render(NamedSource::new("main", "fn main() {}").with_language("Rust"))
+            .contains("\u{1b}[38;2;")
+    );
+    // ...but unknown languages are left alone.
+    assert!(!render(NamedSource::new("main", "fn main() {}")).contains('\u{1b}'));
+}
+
+#[test]
+fn overlapping_same_line_highlights() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("outer")]
+        outer: SourceSpan,
+        #[label("inner")]
+        inner: SourceSpan,
+        #[label("overlap")]
+        overlap: SourceSpan,
+    }
+
+    let src = "let x = foo(bar, baz);".to_string();
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", src),
+        outer: (8, 13).into(),
+        inner: (12, 3).into(),
+        overlap: (17, 5).into(),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"
+  × oops!
+   ╭─[bad_file.rs:1:1]
+ 1 │ let x = foo(bar, baz);
+   ·         ───────┬─────
+   ·             ─┬─│ ──┬──
+   ·              │ │   ╰── overlap
+   ·              │ ╰── outer
+   ·              ╰── inner
+   ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn identical_same_line_highlights() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("first")]
+        first: SourceSpan,
+        #[label("second")]
+        second: SourceSpan,
+    }
+
+    let src = "let x = foo;".to_string();
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", src),
+        first: (8, 3).into(),
+        second: (8, 3).into(),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"
+  × oops!
+   ╭─[bad_file.rs:1:1]
+ 1 │ let x = foo;
+   ·         ─┬─
+   ·         ─┼┬
+   ·          │╰── second
+   ·          ╰── first
+   ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn grouped_reports() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("this bit here")]
+        highlight: SourceSpan,
+    }
+
+    let src = "source\n  text\n    here".to_string();
+    let first = MyBad {
+        src: NamedSource::new("bad_file.rs", src.clone()),
+        highlight: (9, 4).into(),
+    };
+    let other = MyBad {
+        src: NamedSource::new("other_file.rs", src.clone()),
+        highlight: (0, 6).into(),
+    };
+    let second = MyBad {
+        src: NamedSource::new("bad_file.rs", src),
+        highlight: (18, 4).into(),
+    };
+    let mut out = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .with_width(80)
+        .render_grouped_reports(&mut out, vec![&first as &dyn Diagnostic, &other, &second])
+        .unwrap();
+    println!("Error: {}", out);
+    let expected = r#"╭─[bad_file.rs]
+
+  × oops!
+   ╭─[1:1]
+ 1 │ source
+ 2 │   text
+   ·   ──┬─
+   ·     ╰── this bit here
+ 3 │     here
+   ╰────
+
+  × oops!
+   ╭─[2:1]
+ 2 │   text
+ 3 │     here
+   ·     ──┬─
+   ·       ╰── this bit here
+   ╰────
+
+╭─[other_file.rs]
+
+  × oops!
+   ╭─[1:1]
+ 1 │ source
+   · ───┬──
+   ·    ╰── this bit here
+ 2 │   text
+   ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn suggested_fixes() -> Result<(), MietteError> {
+    #[derive(Debug, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        src: NamedSource,
+    }
+
+    impl Diagnostic for MyBad {
+        fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+            Some(&self.src)
+        }
+
+        fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+            Some(Box::new(std::iter::once(miette::LabeledSpan::new(
+                Some("this bit here".into()),
+                9,
+                4,
+            ))))
+        }
+
+        fn fixes(&self) -> Option<Box<dyn Iterator<Item = miette::Fix> + '_>> {
+            Some(Box::new(
+                vec![
+                    miette::Fix::new((9, 4), "texts"),
+                    miette::Fix::new((13, 5), "\n  "),
+                ]
+                .into_iter(),
+            ))
+        }
+    }
+
+    let src = "source\n  text\n    here".to_string();
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", src),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"
+  × oops!
+   ╭─[bad_file.rs:1:1]
+ 1 │ source
+ 2 │   text
+   ·   ──┬─
+   ·     ╰── this bit here
+ 3 │     here
+   ╰────
+   ╭─[bad_file.rs:2:3]
+ 2 │ -   text
+ 2 │ +   texts
+   ╰────
+   ╭─[bad_file.rs:2:7]
+ 2 │ -   text
+ 3 │ -     here
+ 2 │ +   text
+ 3 │ +   here
+   ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn unreadable_fixes_are_skipped() -> Result<(), MietteError> {
+    #[derive(Debug, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        src: NamedSource,
+    }
+
+    impl Diagnostic for MyBad {
+        fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+            Some(&self.src)
+        }
+
+        fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+            Some(Box::new(std::iter::once(miette::LabeledSpan::new(
+                Some("this bit here".into()),
+                9,
+                4,
+            ))))
+        }
+
+        fn fixes(&self) -> Option<Box<dyn Iterator<Item = miette::Fix> + '_>> {
+            Some(Box::new(std::iter::once(miette::Fix::new(
+                (100, 4),
+                "texts",
+            ))))
+        }
+    }
+
+    let src = "source\n  text\n    here".to_string();
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", src),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"
+  × oops!
+   ╭─[bad_file.rs:1:1]
+ 1 │ source
+ 2 │   text
+   ·   ──┬─
+   ·     ╰── this bit here
+ 3 │     here
+   ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn invalid_utf8() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    #[diagnostic(code(oops::my::bad), help("try doing it better next time?"))]
+    struct MyBad {
+        #[source_code]
+        src: Vec<u8>,
+        #[label("this bit here")]
+        highlight: SourceSpan,
+    }
+
+    let err = MyBad {
+        src: b"source\n  \xfftext\n    here".to_vec(),
+        highlight: (10, 4).into(),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"oops::my::bad
+
+  × oops!
+   ╭─[1:1]
+ 1 │ source
+ 2 │   �text
+   ·    ──┬─
+   ·      ╰── this bit here
+ 3 │     here
+   ╰────
+  help: try doing it better next time?
+"#
+    .trim_start()
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn byte_order_mark() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("this bit here")]
+        highlight: SourceSpan,
+    }
+
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", "\u{feff}source\n  text\n"),
+        highlight: (3, 6).into(),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"
+  × oops!
+   ╭─[bad_file.rs:1:1]
+ 1 │ source
+   · ───┬──
+   ·    ╰── this bit here
+ 2 │   text
+   ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn binary_hexdump() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("bad header")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("wrong magic")]
+        magic: SourceSpan,
+        #[label("unknown version")]
+        version: SourceSpan,
+    }
+
+    let mut src = b"\x7fELF\x02\x01\x09\x00".to_vec();
+    src.extend(0u8..40);
+    let err = MyBad {
+        src: NamedSource::new("a.out", src).with_binary(true),
+        magic: (0, 4).into(),
+        version: (6, 1).into(),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"
+  × bad header
+          ╭─[a.out:0x0]
+ 00000000 │ 7f 45 4c 46 02 01 09 00  00 01 02 03 04 05 06 07 │.ELF............│
+          · ─────────── wrong magic
+          ·                   ── unknown version
+ 00000010 │ 08 09 0a 0b 0c 0d                                │......          │
+          ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn binary_hexdump_detected() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("bad checksum")]
+    struct MyBad {
+        #[source_code]
+        src: Vec<u8>,
+        #[label("here")]
+        checksum: SourceSpan,
+    }
+
+    let mut src = vec![0xffu8; 64];
+    src[0x24..0x2c].copy_from_slice(b"\0\0sum\0\0\0");
+    let err = MyBad {
+        src,
+        checksum: (0x26, 3).into(),
+    };
+    let out = fmt_report(err.into());
+    println!("Error: {}", out);
+    let expected = r#"
+  × bad checksum
+          ╭─[0x26]
+ 00000010 │ ff ff ff ff ff ff ff ff  ff ff ff ff ff ff ff ff │................│
+ 00000020 │ ff ff ff ff 00 00 73 75  6d 00 00 00 ff ff ff ff │......sum.......│
+          ·                   ───────── here
+ 00000030 │ ff ff ff ff ff ff ff ff  ff ff ff ff ff ff ff ff │................│
+          ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("config doesn't match the schema")]
+struct Mismatch {
+    #[comparison]
+    comparison: SourceComparison,
+}
+
+fn mismatch(layout: ComparisonLayout) -> Mismatch {
+    Mismatch {
+        comparison: SourceComparison::new(
+            "expected.toml",
+            "port = 8080\n",
+            "actual.toml",
+            "name = \"app\"\nport = \"8080\"\n",
+        )
+        .with_spans(Some("expected an integer".into()), (7, 4), (20, 6))
+        .with_layout(layout),
+    }
+}
+
+#[test]
+fn comparison_side_by_side() -> Result<(), MietteError> {
+    let out = fmt_report(mismatch(ComparisonLayout::SideBySide).into());
+    println!("Error: {}", out);
+    let expected = r#"
+  × config doesn't match the schema
+   ╭─[expected.toml:1:8]     ╭─[actual.toml:2:8]
+ 1 │ port = 8080            2 │ port = "8080"
+   ·        ────              ·        ────── expected an integer
+   ╰────                     ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
+#[test]
+fn comparison_unified() -> Result<(), MietteError> {
+    let out = fmt_report(mismatch(ComparisonLayout::Unified).into());
+    println!("Error: {}", out);
+    let expected = r#"
+  × config doesn't match the schema
+   ╭─[expected.toml:1:8]
+ 1 │ - port = 8080
+   ·          ────
+   ├─[actual.toml:2:8]
+ 2 │ + port = "8080"
+   ·          ────── expected an integer
+   ╰────
+"#
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
@@ -47,7 +47,7 @@ Begin snippet for bad_file.rs starting at line 1, column 1
 
 snippet line 1: source
 snippet line 2:   👼🏼text
-    label at line 2, columns 3 to 6: this bit here
+    label at line 2, columns 3 to 4: this bit here
 snippet line 3:     here
 diagnostic help: try doing it better next time?
 diagnostic code: oops::my::bad
@@ -94,6 +94,43 @@ diagnostic code: oops::my::bad
     Ok(())
 }
 
+#[test]
+fn zero_based_line_numbers() -> Result<(), MietteError> {
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("this bit here")]
+        highlight: SourceSpan,
+    }
+
+    let src = "source\n  text\n    here".to_string();
+    let err = MyBad {
+        src: NamedSource::new("bad_file.rs", src),
+        highlight: (9, 4).into(),
+    };
+    let mut out = String::new();
+    NarratableReportHandler::new()
+        .with_zero_based_line_numbers(true)
+        .render_report(&mut out, &err)
+        .unwrap();
+    println!("Error: {}", out);
+    let expected = r#"oops!
+    Diagnostic severity: error
+Begin snippet for bad_file.rs starting at line 0, column 0
+
+snippet line 0: source
+snippet line 1:   text
+    label at line 1, columns 2 to 5: this bit here
+snippet line 2:     here
+"#
+    .trim_start()
+    .to_string();
+    assert_eq!(expected, out);
+    Ok(())
+}
+
 #[test]
 fn single_line_highlight_offset_zero() -> Result<(), MietteError> {
     #[derive(Debug, Diagnostic, Error)]
@@ -0,0 +1,145 @@
+#![cfg(feature = "async")]
+
+use futures::executor::block_on;
+use miette::{
+    AsyncSource, CompactReportHandler, Diagnostic, FetchedSource, MietteError, NamedSource,
+    ReadSpanFuture, Report, SourceCode, SourceSpan,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use thiserror::Error;
+
+/// Stands in for source code stored somewhere that has to be awaited.
+struct Remote {
+    source: NamedSource,
+    reads: AtomicUsize,
+}
+
+impl Remote {
+    fn new(text: &str) -> Self {
+        Self {
+            source: NamedSource::new("remote.rs", text.to_string()),
+            reads: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl AsyncSource for Remote {
+    fn read_span<'a>(
+        &'a self,
+        span: &'a SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> ReadSpanFuture<'a> {
+        Box::pin(async move {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            self.source
+                .read_span(span, context_lines_before, context_lines_after)
+        })
+    }
+}
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("oops!")]
+struct MyBad {
+    #[label("this")]
+    first: SourceSpan,
+    #[label("that")]
+    second: SourceSpan,
+}
+
+const TEXT: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\n";
+
+#[test]
+fn fetch() -> Result<(), MietteError> {
+    let remote = Remote::new(TEXT);
+    let err = MyBad {
+        first: (4, 3).into(),
+        second: (24, 3).into(),
+    };
+    let fetched = block_on(FetchedSource::fetch(&remote, &err, 1))?;
+    assert_eq!(remote.reads.load(Ordering::SeqCst), 2);
+
+    let contents = fetched.read_span(&err.first, 1, 1)?;
+    assert_eq!(contents.data(), b"one\ntwo\nthree\n");
+    assert_eq!(contents.name(), Some("remote.rs"));
+    let contents = fetched.read_span(&err.second, 1, 1)?;
+    assert_eq!(contents.data(), b"five\nsix\nseven\n");
+    assert_eq!(contents.line(), 4);
+
+    // Nothing was fetched for the lines in between.
+    assert!(fetched.read_span(&(14, 4).into(), 0, 0).is_err());
+    Ok(())
+}
+
+#[test]
+fn merge_overlapping() -> Result<(), MietteError> {
+    let remote = Remote::new(TEXT);
+    let err = MyBad {
+        first: (4, 3).into(),
+        second: (14, 4).into(),
+    };
+    let fetched = block_on(FetchedSource::fetch(&remote, &err, 1))?;
+    let contents = fetched.read_span(&(4, 14).into(), 1, 1)?;
+    assert_eq!(contents.data(), b"one\ntwo\nthree\nfour\nfive\n");
+    assert_eq!(contents.line(), 0);
+    Ok(())
+}
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("oops!")]
+struct Located {
+    #[label("here")]
+    here: SourceSpan,
+}
+
+const LONG_TEXT: &str = "fn main() {\n    let x = 1 + oops;\n    println!(\"{}\", x);\n}\n";
+
+/// Renders `err` with `source` as its source code, using `render`.
+fn render_with(
+    err: &Located,
+    source: impl SourceCode + 'static,
+    render: impl Fn(&dyn Diagnostic, &mut String) -> std::fmt::Result,
+) -> String {
+    let report = Report::new(Located { here: err.here }).with_source_code(source);
+    let mut out = String::new();
+    render(report.as_ref(), &mut out).unwrap();
+    out
+}
+
+#[test]
+fn fetched_renders_like_direct() -> Result<(), MietteError> {
+    let remote = Remote::new(LONG_TEXT);
+    let err = Located {
+        here: (28, 4).into(),
+    };
+    let fetched = block_on(FetchedSource::fetch(&remote, &err, 1))?;
+
+    {
+        let contents = fetched.read_span(&err.here, 0, 0)?;
+        assert_eq!(contents.data(), b"oops");
+        assert_eq!((contents.line(), contents.column()), (1, 16));
+    }
+    assert_eq!(fetched.visual_column(&err.here, 4)?, 16);
+
+    let compact = |diagnostic: &dyn Diagnostic, out: &mut String| {
+        CompactReportHandler::new().render_report(out, diagnostic)
+    };
+    let expected = render_with(&err, NamedSource::new("remote.rs", LONG_TEXT), compact);
+    assert!(expected.contains("2:17"), "{}", expected);
+    assert_eq!(expected, render_with(&err, fetched.clone(), compact));
+
+    #[cfg(feature = "fancy-no-backtrace")]
+    {
+        use miette::{GraphicalReportHandler, GraphicalTheme};
+
+        let graphical = |diagnostic: &dyn Diagnostic, out: &mut String| {
+            GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+                .render_report(out, diagnostic)
+        };
+        assert_eq!(
+            render_with(&err, NamedSource::new("remote.rs", LONG_TEXT), graphical),
+            render_with(&err, fetched, graphical)
+        );
+    }
+    Ok(())
+}
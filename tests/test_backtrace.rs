@@ -0,0 +1,135 @@
+use miette::{miette, Diagnostic, DiagnosticBacktrace, Report, WrapErr};
+use thiserror::Error;
+
+#[test]
+fn disabled() {
+    let backtrace = DiagnosticBacktrace::disabled();
+    assert!(!backtrace.is_captured());
+    assert_eq!(backtrace.to_string(), "");
+}
+
+#[test]
+fn force_capture() {
+    let backtrace = DiagnosticBacktrace::force_capture();
+    assert_eq!(backtrace.is_captured(), cfg!(feature = "fancy"));
+    if cfg!(feature = "fancy") {
+        // Frames inside miette itself are skipped.
+        let trace = backtrace.to_string();
+        assert!(trace
+            .trim_start()
+            .starts_with("0: test_backtrace::force_capture"));
+    }
+}
+
+#[test]
+fn forwarded() {
+    #[derive(Debug, Error)]
+    #[error("oops!")]
+    struct MyBad(DiagnosticBacktrace);
+
+    impl Diagnostic for MyBad {
+        fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+            Some(&self.0)
+        }
+    }
+
+    #[derive(Debug, Diagnostic, Error)]
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    struct Wrapper(MyBad);
+
+    let err = Wrapper(MyBad(DiagnosticBacktrace::force_capture()));
+    assert!(err.backtrace().is_some());
+
+    let report = Err::<(), _>(err).wrap_err("wrapped").unwrap_err();
+    assert_eq!(
+        report.backtrace().map(|bt| bt.is_captured()),
+        Some(cfg!(feature = "fancy"))
+    );
+}
+
+#[test]
+fn adhoc() {
+    // This is the only test capturing backtraces through the environment,
+    // since whether they're enabled is only checked once.
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    let report: Report = miette!("oops!");
+    assert_eq!(report.backtrace().is_some(), cfg!(feature = "fancy"));
+    assert_eq!(report.to_string(), "oops!");
+
+    // Downcasting still gets at the message.
+    let report = Report::msg(String::from("oops!"));
+    assert_eq!(report.downcast_ref::<String>().unwrap(), "oops!");
+    assert_eq!(report.downcast::<String>().unwrap(), "oops!");
+}
+
+#[cfg(feature = "fancy")]
+#[test]
+fn rendered() {
+    use miette::{GraphicalReportHandler, GraphicalTheme};
+
+    #[derive(Debug, Error)]
+    #[error("oops!")]
+    struct MyBad(DiagnosticBacktrace);
+
+    impl Diagnostic for MyBad {
+        fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+            Some(&self.0)
+        }
+    }
+
+    let err = MyBad(DiagnosticBacktrace::force_capture());
+    let mut out = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .render_report(&mut out, &err)
+        .unwrap();
+    assert!(out.starts_with("\n  × oops!\n\n  backtrace:\n     0: test_backtrace::rendered"));
+}
+
+#[cfg(feature = "fancy")]
+#[test]
+fn rendered_from_cause() {
+    use miette::{
+        GraphicalReportHandler, GraphicalTheme, NarratableReportHandler, VerboseReportHandler,
+    };
+
+    #[derive(Debug, Error)]
+    #[error("inner oops!")]
+    struct Inner(DiagnosticBacktrace);
+
+    impl Diagnostic for Inner {
+        fn backtrace(&self) -> Option<&DiagnosticBacktrace> {
+            Some(&self.0)
+        }
+    }
+
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!")]
+    struct Outer {
+        #[diagnostic_source]
+        inner: Inner,
+    }
+
+    let err = Outer {
+        inner: Inner(DiagnosticBacktrace::force_capture()),
+    };
+    assert!(err.backtrace().is_none());
+
+    let mut out = String::new();
+    GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+        .render_report(&mut out, &err)
+        .unwrap();
+    assert!(out.contains("  backtrace:\n     0: test_backtrace::rendered_from_cause"));
+
+    let mut out = String::new();
+    NarratableReportHandler::new()
+        .render_report(&mut out, &err)
+        .unwrap();
+    assert!(out.contains("diagnostic backtrace:\n   0: test_backtrace::rendered_from_cause"));
+
+    let mut out = String::new();
+    VerboseReportHandler::new()
+        .render_report(&mut out, &err)
+        .unwrap();
+    assert!(out.contains("  backtrace:\n       0: test_backtrace::rendered_from_cause"));
+}
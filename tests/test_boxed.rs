@@ -237,3 +237,46 @@ fn test_boxed_sources() {
             .to_string()
     );
 }
+
+#[derive(Error, Debug)]
+#[error("not thread-safe")]
+struct LocalDiagnostic {
+    _marker: std::rc::Rc<()>,
+}
+impl Diagnostic for LocalDiagnostic {}
+
+#[test]
+fn test_boxed_question_mark() {
+    fn local() -> Result<(), Box<dyn Diagnostic>> {
+        Err(LocalDiagnostic {
+            _marker: std::rc::Rc::new(()),
+        })?;
+        Ok(())
+    }
+
+    fn thread_safe() -> Result<(), Box<dyn Diagnostic + Send + Sync>> {
+        Err(MyError {
+            source: io::Error::new(io::ErrorKind::Other, "oh no!"),
+        })?;
+        Ok(())
+    }
+
+    fn send() -> Result<(), Box<dyn Diagnostic + Send>> {
+        thread_safe()?;
+        Ok(())
+    }
+
+    fn any() -> Result<(), Box<dyn Diagnostic>> {
+        send()?;
+        Ok(())
+    }
+
+    assert_eq!("not thread-safe", local().unwrap_err().to_string());
+    let error = any().unwrap_err();
+    assert_eq!("outer", error.to_string());
+    assert_eq!("oh no!", error.source().unwrap().to_string());
+
+    let error: Box<dyn StdError + Send + Sync> = "oh no!".into();
+    let error: Box<dyn Diagnostic> = error.into();
+    assert_eq!("oh no!", error.to_string());
+}
@@ -0,0 +1,73 @@
+use miette::{CodeRegistry, Diagnostic};
+use thiserror::Error;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("oops!")]
+#[diagnostic(code(my_app::oops))]
+struct MyBad {
+    #[related]
+    related: Vec<MyWarning>,
+    #[diagnostic_source]
+    cause: MyCause,
+}
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("careful")]
+#[diagnostic(code(my_app::careful), severity(Warning))]
+struct MyWarning;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("because")]
+#[diagnostic(code(my_app::because))]
+struct MyCause;
+
+fn registry() -> CodeRegistry {
+    CodeRegistry::new()
+        .with_code("my_app::oops", "Something went wrong", "It went wrong.")
+        .with_code("my_app::careful", "Be careful", "Look both ways.")
+}
+
+#[test]
+fn explain() {
+    let registry = registry();
+    let explanation = registry.explain("my_app::oops").unwrap();
+    assert_eq!(explanation.code(), "my_app::oops");
+    assert_eq!(explanation.title(), "Something went wrong");
+    assert_eq!(explanation.explanation(), "It went wrong.");
+    assert_eq!(
+        explanation.to_string(),
+        "my_app::oops: Something went wrong\n\nIt went wrong."
+    );
+    assert!(registry.explain("my_app::nope").is_none());
+}
+
+#[test]
+fn register_replaces() {
+    let mut registry = registry();
+    let previous = registry.register("my_app::oops", "Oops", "Try again.");
+    assert_eq!(previous.unwrap().title(), "Something went wrong");
+    assert_eq!(registry.explain("my_app::oops").unwrap().title(), "Oops");
+    assert_eq!(
+        registry.iter().map(|c| c.code()).collect::<Vec<_>>(),
+        vec!["my_app::careful", "my_app::oops"]
+    );
+}
+
+#[test]
+fn unregistered_codes() {
+    let err = MyBad {
+        related: vec![MyWarning, MyWarning],
+        cause: MyCause,
+    };
+    let registry = registry();
+    assert_eq!(registry.unregistered_codes(&err), vec!["my_app::because"]);
+
+    let registry = registry.with_code("my_app::because", "Because", "Just because.");
+    assert!(registry.unregistered_codes(&err).is_empty());
+
+    let registry = CodeRegistry::new();
+    assert_eq!(
+        registry.unregistered_codes(&err),
+        vec!["my_app::oops", "my_app::careful", "my_app::because"]
+    );
+}
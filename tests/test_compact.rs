@@ -0,0 +1,125 @@
+mod compact_report_handler {
+    use miette::{CompactReportHandler, Diagnostic, MietteError, NamedSource, Report, SourceSpan};
+
+    use thiserror::Error;
+
+    fn fmt_report(diag: Report) -> String {
+        let mut out = String::new();
+        CompactReportHandler::new()
+            .render_report(&mut out, diag.as_ref())
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn single_line() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(code(oops::my::bad), help("try doing it better next time?"))]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label("this bit here")]
+            highlight: SourceSpan,
+        }
+
+        let src = "source\n  👼🏼text\n    here".to_string();
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src),
+            highlight: (17, 4).into(),
+        };
+        let out = fmt_report(err.into());
+        assert_eq!("error[oops::my::bad] bad_file.rs:2:5: oops!", out);
+        Ok(())
+    }
+
+    #[test]
+    fn primary_label() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label("defined here")]
+            defined: SourceSpan,
+            #[label(primary, "used here")]
+            used: SourceSpan,
+        }
+
+        let src = "let x;\nx + 1".to_string();
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src),
+            defined: (4, 1).into(),
+            used: (7, 1).into(),
+        };
+        let out = fmt_report(err.into());
+        assert_eq!("error bad_file.rs:2:1: oops!", out);
+        Ok(())
+    }
+
+    #[test]
+    fn no_code_and_unnamed_source() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!\n  on two lines")]
+        #[diagnostic(severity(Warning))]
+        struct MyBad {
+            #[source_code]
+            src: String,
+            #[label]
+            highlight: SourceSpan,
+        }
+
+        let err = MyBad {
+            src: "source\n  text".into(),
+            highlight: (9, 4).into(),
+        };
+        let out = fmt_report(err.into());
+        assert_eq!("warning 2:3: oops! on two lines", out);
+        Ok(())
+    }
+
+    #[test]
+    fn no_labels() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        struct MyBad;
+
+        let out = fmt_report(MyBad.into());
+        assert_eq!("error: oops!", out);
+        Ok(())
+    }
+
+    #[test]
+    fn related() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(code(oops::my::bad))]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[related]
+            related: Vec<MyRelated>,
+        }
+
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("related")]
+        #[diagnostic(severity(Advice))]
+        struct MyRelated {
+            #[label]
+            highlight: SourceSpan,
+        }
+
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", "source\n  text".to_string()),
+            related: vec![MyRelated {
+                highlight: (0, 6).into(),
+            }],
+        };
+        let out = fmt_report(err.into());
+        assert_eq!(
+            "error[oops::my::bad]: oops!\nadvice bad_file.rs:1:1: related",
+            out
+        );
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 mod drop;
 
 use self::drop::{DetectDrop, Flag};
-use miette::{Diagnostic, Report, Result};
+use miette::{Diagnostic, IntoDiagnostic, Report, Result};
+use std::io;
 
 #[test]
 fn test_convert() {
@@ -40,3 +41,44 @@ fn test_question_mark_stderr() -> Result<(), Box<dyn std::error::Error>> {
     f()?;
     Ok(())
 }
+
+#[test]
+fn test_from_err() {
+    let io_error = std::io::Error::new(std::io::ErrorKind::Other, "oh no!");
+    let error = Report::from_err(io_error);
+    assert_eq!("oh no!", error.to_string());
+    assert!(error.code().is_none());
+}
+
+#[test]
+fn test_into_diagnostic_with_code() {
+    let result: Result<u32> = "x"
+        .parse::<u32>()
+        .into_diagnostic_with_code("config::parse");
+    let error = result.unwrap_err();
+    assert_eq!("invalid digit found in string", error.to_string());
+    assert_eq!("config::parse", error.code().unwrap().to_string());
+    assert!("x"
+        .parse::<u32>()
+        .into_diagnostic()
+        .unwrap_err()
+        .code()
+        .is_none());
+}
+
+#[test]
+fn test_into_diagnostic_with_code_default() {
+    struct Missing;
+
+    impl IntoDiagnostic<(), io::Error> for Missing {
+        fn into_diagnostic(self) -> Result<()> {
+            Err(io::Error::new(io::ErrorKind::NotFound, "missing")).into_diagnostic()
+        }
+    }
+
+    let error = Missing
+        .into_diagnostic_with_code("config::missing")
+        .unwrap_err();
+    assert_eq!("missing", error.to_string());
+    assert_eq!("config::missing", error.code().unwrap().to_string());
+}
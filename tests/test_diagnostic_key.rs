@@ -0,0 +1,74 @@
+use miette::{Diagnostic, DiagnosticKey, SourceSpan};
+use thiserror::Error;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("unused variable")]
+#[diagnostic(code(lint::unused), help("remove it"))]
+struct Unused {
+    #[label("this one")]
+    span: SourceSpan,
+    #[label(primary)]
+    primary: SourceSpan,
+}
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("unused variable")]
+struct NoCode;
+
+#[test]
+fn fields() {
+    let diag = Unused {
+        span: (0, 3).into(),
+        primary: (4, 1).into(),
+    };
+    let key = DiagnosticKey::new(&diag);
+    assert_eq!(key.code(), Some("lint::unused"));
+    assert_eq!(key.message(), "unused variable");
+    assert_eq!(key.span(), Some(&(4, 1).into()));
+
+    let key = DiagnosticKey::new(&NoCode);
+    assert_eq!(key.code(), None);
+    assert_eq!(key.span(), None);
+}
+
+#[test]
+fn equality() {
+    let a = Unused {
+        span: (0, 3).into(),
+        primary: (4, 1).into(),
+    };
+    // Only the primary span matters.
+    let b = Unused {
+        span: (8, 3).into(),
+        primary: (4, 1).into(),
+    };
+    let c = Unused {
+        span: (0, 3).into(),
+        primary: (5, 1).into(),
+    };
+    assert_eq!(DiagnosticKey::new(&a), DiagnosticKey::new(&b));
+    assert_ne!(DiagnosticKey::new(&a), DiagnosticKey::new(&c));
+    assert_ne!(DiagnosticKey::new(&a), DiagnosticKey::new(&NoCode));
+}
+
+#[test]
+fn dedup() {
+    let diags: Vec<Box<dyn Diagnostic>> = vec![
+        Box::new(NoCode),
+        Box::new(Unused {
+            span: (0, 3).into(),
+            primary: (4, 1).into(),
+        }),
+        Box::new(NoCode),
+        Box::new(Unused {
+            span: (0, 3).into(),
+            primary: (4, 1).into(),
+        }),
+    ];
+    let mut seen = std::collections::HashSet::new();
+    let unique: Vec<_> = diags
+        .iter()
+        .filter(|diag| seen.insert(DiagnosticKey::new(diag.as_ref())))
+        .collect();
+    assert_eq!(unique.len(), 2);
+}
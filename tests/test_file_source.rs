@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use miette::{FileSource, MietteError, SourceCode};
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("miette-test-{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn lazy() -> Result<(), MietteError> {
+    let path = temp_file("lazy.txt", "foo\nbarbar\nbaz\n");
+    let source = FileSource::new(&path);
+    assert!(!source.is_loaded());
+    assert_eq!(source.path(), path);
+
+    let contents = source.read_span(&(7, 4).into(), 0, 0)?;
+    assert!(source.is_loaded());
+    assert_eq!("bar\n", std::str::from_utf8(contents.data()).unwrap());
+    assert_eq!(1, contents.line());
+    assert_eq!(3, contents.column());
+    assert_eq!(Some(path.display().to_string().as_str()), contents.name());
+
+    std::fs::remove_file(&path).unwrap();
+    // Only the snippets read are kept, so later reads go back to the file.
+    assert!(matches!(
+        source.read_span(&(0, 3).into(), 0, 0),
+        Err(MietteError::IoError(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn matches_in_memory() -> Result<(), MietteError> {
+    let mut text = String::new();
+    for line in 0..5_000 {
+        let ending = ["\n", "\r\n", "\r"][line % 3];
+        text.push_str(&format!("line number {}{}", line, ending));
+    }
+    let path = temp_file("large.txt", &text);
+    let source = FileSource::new(&path);
+    for offset in [70_000, 0, 14, text.len() - 5] {
+        for (before, after) in [(0, 0), (2, 3)] {
+            let span = (offset, 5).into();
+            let expected = text.read_span(&span, before, after)?;
+            let actual = source.read_span(&span, before, after)?;
+            assert_eq!(expected.data(), actual.data());
+            assert_eq!(expected.span(), actual.span());
+            assert_eq!(expected.line(), actual.line());
+            assert_eq!(expected.column(), actual.column());
+            assert_eq!(expected.line_count(), actual.line_count());
+        }
+    }
+    std::fs::remove_file(&path).unwrap();
+    Ok(())
+}
+
+#[test]
+fn missing_file() {
+    let source = FileSource::new(std::env::temp_dir().join("miette-test-does-not-exist"));
+    assert!(matches!(
+        source.read_span(&(0, 1).into(), 0, 0),
+        Err(MietteError::IoError(_))
+    ));
+    assert!(!source.is_loaded());
+}
+
+#[test]
+fn out_of_bounds() {
+    let path = temp_file("out_of_bounds.txt", "foo");
+    let source = FileSource::new(&path);
+    assert!(matches!(
+        source.read_span(&(10, 1).into(), 0, 0),
+        Err(MietteError::OutOfBounds)
+    ));
+    std::fs::remove_file(&path).unwrap();
+}
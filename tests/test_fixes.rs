@@ -0,0 +1,48 @@
+use miette::{Applicability, Fix, MietteError};
+
+#[test]
+fn applicability_defaults_to_unspecified() {
+    let fix = Fix::new((0, 1), "x");
+    assert_eq!(fix.applicability(), Applicability::Unspecified);
+    let fix = fix.with_applicability(Applicability::MaybeIncorrect);
+    assert_eq!(fix.applicability(), Applicability::MaybeIncorrect);
+}
+
+#[test]
+fn apply_all() -> Result<(), MietteError> {
+    let source = "let x = 1\nlet y = x;";
+    let fixes = vec![
+        // Insertion.
+        Fix::new((9, 0), ";"),
+        // Replacement.
+        Fix::new((10, 3), "const"),
+        // Deletion.
+        Fix::new((0, 4), ""),
+    ];
+    assert_eq!(Fix::apply_all(source, &fixes)?, "x = 1;\nconst y = x;");
+    Ok(())
+}
+
+#[test]
+fn apply_all_skips_overlapping_fixes() -> Result<(), MietteError> {
+    let source = "foo bar baz";
+    let fixes = vec![Fix::new((0, 7), "qux"), Fix::new((4, 3), "quux")];
+    assert_eq!(Fix::apply_all(source, &fixes)?, "qux baz");
+    Ok(())
+}
+
+#[test]
+fn apply_all_out_of_bounds() {
+    let fixes = vec![Fix::new((2, 5), "x")];
+    assert!(matches!(
+        Fix::apply_all("abc", &fixes),
+        Err(MietteError::OutOfBounds)
+    ));
+
+    // Not on a character boundary.
+    let fixes = vec![Fix::new((1, 1), "x")];
+    assert!(matches!(
+        Fix::apply_all("👼", &fixes),
+        Err(MietteError::OutOfBounds)
+    ));
+}
@@ -5,3 +5,55 @@ fn test_handler() {
     let error: Report = miette!("oh no!");
     let _ = error.handler();
 }
+
+#[test]
+fn test_write_report() {
+    use miette::{miette, NarratableReportHandler, Report, ReportHandler};
+
+    let error: Report = miette!("oh no!");
+    let mut out = Vec::new();
+    error.write_report(&mut out).unwrap();
+    assert_eq!(format!("{:?}", error), String::from_utf8(out).unwrap());
+
+    let handler = NarratableReportHandler::new();
+    let mut expected = String::new();
+    handler
+        .render_report(&mut expected, error.as_ref())
+        .unwrap();
+    let mut out = Vec::new();
+    handler.write_report(error.as_ref(), &mut out).unwrap();
+    assert_eq!(expected, String::from_utf8(out).unwrap());
+}
+
+#[test]
+fn test_render_to_string() {
+    use miette::{miette, NarratableReportHandler, Report, ReportHandler};
+
+    let error: Report = miette!("oh no!");
+    assert_eq!(format!("{:?}", error), error.render_to_string().unwrap());
+
+    let handler = NarratableReportHandler::new();
+    let mut expected = String::new();
+    handler
+        .render_report(&mut expected, error.as_ref())
+        .unwrap();
+    assert_eq!(expected, handler.render_to_string(error.as_ref()).unwrap());
+}
+
+#[test]
+fn test_rendered() {
+    use miette::{miette, NarratableReportHandler, Rendered, Report};
+
+    let error: Report = miette!("oh no!");
+    assert_eq!(format!("{:?}", error), format!("{}", error.rendered()));
+
+    let handler = NarratableReportHandler::new();
+    let mut expected = String::new();
+    handler
+        .render_report(&mut expected, error.as_ref())
+        .unwrap();
+    assert_eq!(
+        expected,
+        format!("{}", Rendered::new(&handler, error.as_ref()))
+    );
+}
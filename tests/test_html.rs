@@ -0,0 +1,172 @@
+mod html_report_handler {
+    use miette::{Diagnostic, HtmlReportHandler, MietteError, NamedSource, Report, SourceSpan};
+
+    use thiserror::Error;
+
+    fn fmt_report(diag: Report) -> String {
+        let mut out = String::new();
+        HtmlReportHandler::new()
+            .render_report(&mut out, diag.as_ref())
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn single_line_highlight() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(code(oops::my::bad), help("try doing it better next time?"))]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label("this <bit> here")]
+            highlight: SourceSpan,
+        }
+
+        let src = "source\n  text & more\n    here".to_string();
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src),
+            highlight: (9, 4).into(),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = r#"<div class="miette-diagnostic miette-severity-error">
+<p class="miette-header"><span class="miette-severity">error</span><span class="miette-code">[oops::my::bad]</span>: <span class="miette-message">oops!</span></p>
+<figure class="miette-snippet">
+<figcaption class="miette-source">[bad_file.rs:1:1]</figcaption>
+<pre class="miette-source-code"><code><span class="miette-line"><span class="miette-linum">1</span>source</span>
+<span class="miette-line"><span class="miette-linum">2</span>  <mark class="miette-highlight miette-highlight-0">text</mark> &amp; more</span>
+<span class="miette-line"><span class="miette-linum">3</span>    here</span>
+</code></pre>
+<ul class="miette-labels">
+<li class="miette-label miette-highlight-0">this &lt;bit&gt; here</li>
+</ul>
+</figure>
+<p class="miette-help">help: try doing it better next time?</p>
+</div>
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn overlapping_and_empty_highlights() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(severity(Warning), url("https://example.com/oops"))]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label("outer")]
+            outer: SourceSpan,
+            #[label]
+            inner: SourceSpan,
+            #[label("here")]
+            point: SourceSpan,
+        }
+
+        let src = "let x = foo(bar);".to_string();
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src),
+            outer: (8, 8).into(),
+            inner: (12, 3).into(),
+            point: (16, 0).into(),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = r#"<div class="miette-diagnostic miette-severity-warning">
+<p class="miette-header"><span class="miette-severity">warning</span>: <span class="miette-message">oops!</span></p>
+<figure class="miette-snippet">
+<figcaption class="miette-source">[bad_file.rs:1:1]</figcaption>
+<pre class="miette-source-code"><code><span class="miette-line"><span class="miette-linum">1</span>let x = <mark class="miette-highlight miette-highlight-0">foo(</mark><mark class="miette-highlight miette-highlight-0 miette-highlight-1">bar</mark><mark class="miette-highlight miette-highlight-0">)</mark><mark class="miette-highlight miette-highlight-2 miette-point"></mark>;</span>
+</code></pre>
+<ul class="miette-labels">
+<li class="miette-label miette-highlight-0">outer</li>
+<li class="miette-label miette-highlight-2">here</li>
+</ul>
+</figure>
+<p class="miette-url">For more details, see <a href="https://example.com/oops">https://example.com/oops</a></p>
+</div>
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn causes_related_and_footer() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        struct MyBad {
+            #[source]
+            source: Inner,
+            #[related]
+            related: Vec<MyRelated>,
+        }
+
+        #[derive(Debug, Error)]
+        #[error("inner")]
+        struct Inner;
+
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("related")]
+        #[diagnostic(severity(Advice))]
+        struct MyRelated;
+
+        let err = MyBad {
+            source: Inner,
+            related: vec![MyRelated],
+        };
+        let mut out = String::new();
+        HtmlReportHandler::new()
+            .with_footer("the end".into())
+            .render_report(&mut out, &err)
+            .unwrap();
+        println!("Error: {}", out);
+        let expected = r#"<div class="miette-diagnostic miette-severity-error">
+<p class="miette-header"><span class="miette-severity">error</span>: <span class="miette-message">oops!</span></p>
+<ul class="miette-causes">
+<li>inner</li>
+</ul>
+<div class="miette-related">
+<div class="miette-diagnostic miette-severity-advice">
+<p class="miette-header"><span class="miette-severity">advice</span>: <span class="miette-message">related</span></p>
+</div>
+</div>
+</div>
+<footer class="miette-footer">the end</footer>
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn far_apart_labels_in_separate_snippets() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        struct MyBad {
+            #[source_code]
+            src: String,
+            #[label("first")]
+            first: SourceSpan,
+            #[label("second")]
+            second: SourceSpan,
+        }
+
+        let src = (1..=100)
+            .map(|i| format!("line {}\n", i))
+            .collect::<String>();
+        let offset = |line: usize| src.find(&format!("line {}\n", line)).unwrap();
+        let err = MyBad {
+            first: (offset(51), 4).into(),
+            second: (offset(91), 4).into(),
+            src,
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        assert_eq!(out.matches(r#"<figure class="miette-snippet">"#).count(), 2);
+        assert_eq!(out.matches(r#"<span class="miette-line">"#).count(), 6);
+        assert!(out.contains("[50:1]"));
+        assert!(out.contains("[90:1]"));
+        Ok(())
+    }
+}
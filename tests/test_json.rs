@@ -1,5 +1,5 @@
 mod json_report_handler {
-    use miette::{Diagnostic, MietteError, NamedSource, Report, SourceSpan};
+    use miette::{Diagnostic, DiagnosticMetadata, MietteError, NamedSource, Report, SourceSpan};
 
     use miette::JSONReportHandler;
 
@@ -926,4 +926,144 @@ mod json_report_handler {
         assert_eq!(expected, out);
         Ok(())
     }
+
+    #[test]
+    fn fixes() -> Result<(), MietteError> {
+        #[derive(Debug, Error)]
+        #[error("oops!")]
+        struct MyBad;
+
+        impl Diagnostic for MyBad {
+            fn fixes(&self) -> Option<Box<dyn Iterator<Item = miette::Fix> + '_>> {
+                Some(Box::new(
+                    vec![
+                        miette::Fix::new((4, 3), "\"x\"")
+                            .with_applicability(miette::Applicability::MachineApplicable),
+                        miette::Fix::new((9, 0), "(...)"),
+                    ]
+                    .into_iter(),
+                ))
+            }
+        }
+
+        let out = fmt_report(MyBad.into());
+        println!("Error: {}", out);
+        let expected: String = r#"
+        {
+            "message": "oops!",
+            "severity": "error",
+            "causes": [],
+            "labels": [],
+            "fixes": [
+                {
+                    "span": {
+                        "offset": 4,
+                        "length": 3
+                    },
+                    "replacement": "\"x\"",
+                    "applicability": "machine-applicable"
+                },{
+                    "span": {
+                        "offset": 9,
+                        "length": 0
+                    },
+                    "replacement": "(...)",
+                    "applicability": "unspecified"
+                }
+            ],
+            "related": []
+        }"#
+        .lines()
+        .into_iter()
+        .map(|s| s.trim_matches(|c| c == ' ' || c == '\n'))
+        .collect();
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn tags() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("unused variable")]
+        #[diagnostic(severity(Warning), tags(unnecessary))]
+        struct MyBad;
+
+        let out = fmt_report(MyBad.into());
+        println!("Error: {}", out);
+        let expected: String = r#"
+        {
+            "message": "unused variable",
+            "severity": "warning",
+            "tags": ["unnecessary"],
+            "causes": [],
+            "labels": [],
+            "related": []
+        }"#
+        .lines()
+        .into_iter()
+        .map(|s| s.trim_matches(|c| c == ' ' || c == '\n'))
+        .collect();
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn metadata() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        struct MyBad {
+            #[metadata]
+            metadata: DiagnosticMetadata,
+        }
+
+        let err = MyBad {
+            metadata: DiagnosticMetadata::new()
+                .with("request_id", "b2c7")
+                .with("lint_group", "\"style\""),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected: String = r#"
+        {
+            "message": "oops!",
+            "severity": "error",
+            "metadata": {"lint_group": "\"style\"","request_id": "b2c7"},
+            "causes": [],
+            "labels": [],
+            "related": []
+        }"#
+        .lines()
+        .into_iter()
+        .map(|s| s.trim_matches(|c| c == ' ' || c == '\n'))
+        .collect();
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn expected_found() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("assertion failed")]
+        #[diagnostic(expected("\"a\""), found("\"b\""))]
+        struct MyBad;
+
+        let out = fmt_report(MyBad.into());
+        println!("Error: {}", out);
+        let expected: String = r#"
+        {
+            "message": "assertion failed",
+            "severity": "error",
+            "causes": [],
+            "expected": "\"a\"",
+            "found": "\"b\"",
+            "labels": [],
+            "related": []
+        }"#
+        .lines()
+        .into_iter()
+        .map(|s| s.trim_matches(|c| c == ' ' || c == '\n'))
+        .collect();
+        assert_eq!(expected, out);
+        Ok(())
+    }
 }
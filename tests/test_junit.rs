@@ -0,0 +1,127 @@
+mod junit_report_handler {
+    use miette::{Diagnostic, JUnitReportHandler, MietteError, NamedSource, Report, SourceSpan};
+
+    use thiserror::Error;
+
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops <here>!")]
+    #[diagnostic(code(oops::my::bad), help("try doing it better next time?"))]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("this bit here")]
+        highlight: SourceSpan,
+    }
+
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("meh")]
+    #[diagnostic(code(oops::my::meh), severity(Warning))]
+    struct MyMeh {
+        #[source_code]
+        src: NamedSource,
+        #[label]
+        highlight: SourceSpan,
+    }
+
+    fn source() -> NamedSource {
+        NamedSource::new("bad_file.rs", "source\n  text\n    here".to_string())
+    }
+
+    #[test]
+    fn single_diagnostic() -> Result<(), MietteError> {
+        let err: Report = MyBad {
+            src: source(),
+            highlight: (9, 4).into(),
+        }
+        .into();
+        let mut out = String::new();
+        JUnitReportHandler::new()
+            .with_suite_name("my-linter")
+            .render_report(&mut out, err.as_ref())
+            .unwrap();
+        println!("Error: {}", out);
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="my-linter" tests="1" failures="1">
+<testsuite name="my-linter" tests="1" failures="1" errors="0" skipped="0">
+<testcase classname="bad_file.rs" name="oops::my::bad at line 2">
+<failure message="oops &lt;here&gt;!" type="oops::my::bad">error[oops::my::bad] bad_file.rs:2:3: oops &lt;here&gt;!
+help: try doing it better next time?</failure>
+</testcase>
+</testsuite>
+</testsuites>
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn testcase_per_diagnostic() -> Result<(), MietteError> {
+        let bad = MyBad {
+            src: source(),
+            highlight: (9, 4).into(),
+        };
+        let meh = MyMeh {
+            src: source(),
+            highlight: (18, 4).into(),
+        };
+        let mut out = String::new();
+        JUnitReportHandler::new()
+            .render_reports(&mut out, vec![&bad as &dyn Diagnostic, &meh])
+            .unwrap();
+        println!("Error: {}", out);
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="miette" tests="2" failures="1">
+<testsuite name="miette" tests="2" failures="1" errors="0" skipped="0">
+<testcase classname="bad_file.rs" name="oops::my::bad at line 2">
+<failure message="oops &lt;here&gt;!" type="oops::my::bad">error[oops::my::bad] bad_file.rs:2:3: oops &lt;here&gt;!
+help: try doing it better next time?</failure>
+</testcase>
+<testcase classname="bad_file.rs" name="oops::my::meh at line 3">
+<system-out>warning[oops::my::meh] bad_file.rs:3:5: meh</system-out>
+</testcase>
+</testsuite>
+</testsuites>
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn testcase_per_file() -> Result<(), MietteError> {
+        let bad1 = MyBad {
+            src: source(),
+            highlight: (0, 6).into(),
+        };
+        let bad2 = MyBad {
+            src: source(),
+            highlight: (9, 4).into(),
+        };
+        let meh = MyMeh {
+            src: NamedSource::new("other_file.rs", "other".to_string()),
+            highlight: (0, 5).into(),
+        };
+        let mut out = String::new();
+        JUnitReportHandler::new()
+            .with_testcase_per_file(true)
+            .render_reports(&mut out, vec![&bad1 as &dyn Diagnostic, &meh, &bad2])
+            .unwrap();
+        println!("Error: {}", out);
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="miette" tests="2" failures="1">
+<testsuite name="miette" tests="2" failures="1" errors="0" skipped="0">
+<testcase classname="miette" name="bad_file.rs">
+<failure message="2 errors">error[oops::my::bad] bad_file.rs:1:1: oops &lt;here&gt;!
+help: try doing it better next time?
+error[oops::my::bad] bad_file.rs:2:3: oops &lt;here&gt;!
+help: try doing it better next time?</failure>
+</testcase>
+<testcase classname="miette" name="other_file.rs">
+<system-out>warning[oops::my::meh] other_file.rs:1:1: meh</system-out>
+</testcase>
+</testsuite>
+</testsuites>
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+}
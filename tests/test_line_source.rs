@@ -0,0 +1,68 @@
+use miette::{LineSource, MietteError, SourceCode};
+
+#[test]
+fn read_span() -> Result<(), MietteError> {
+    let source = LineSource::new(vec!["foo".to_string(), "barbar".into(), "baz".into()]);
+    let contents = source.read_span(&(7, 4).into(), 0, 0)?;
+    assert_eq!("bar\n", std::str::from_utf8(contents.data()).unwrap());
+    assert_eq!(1, contents.line());
+    assert_eq!(3, contents.column());
+    Ok(())
+}
+
+#[test]
+fn matches_joined() -> Result<(), MietteError> {
+    let lines = (0..100)
+        .map(|line| format!("line number {}", line))
+        .collect::<Vec<_>>();
+    let text = lines
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect::<String>();
+    let source = LineSource::new(lines.iter().map(String::as_str).collect::<Vec<_>>());
+    for offset in [0, 5, 14, 15, 700, text.len() - 3, text.len()] {
+        for len in [0, 1, 40] {
+            for (before, after) in [(0, 0), (1, 1), (0, 2), (2, 3)] {
+                let span = (offset, len).into();
+                let (expected, actual) = match (
+                    text.read_span(&span, before, after),
+                    source.read_span(&span, before, after),
+                ) {
+                    (Ok(expected), Ok(actual)) => (expected, actual),
+                    (Err(_), Err(_)) => continue,
+                    _ => panic!("only one read of {:?} failed", span),
+                };
+                assert_eq!(expected.data(), actual.data());
+                assert_eq!(expected.span(), actual.span());
+                assert_eq!(expected.line(), actual.line());
+                assert_eq!(expected.column(), actual.column());
+                assert_eq!(expected.line_count(), actual.line_count());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn reuses_snippets() -> Result<(), MietteError> {
+    let source = LineSource::new(["foo", "barbar", "baz", "qux"]);
+    let wide = source
+        .read_span(&(7, 4).into(), 1, 1)?
+        .data()
+        .as_ptr_range();
+    for _ in 0..3 {
+        let narrow = source.read_span(&(7, 4).into(), 0, 0)?;
+        assert_eq!("bar\n", std::str::from_utf8(narrow.data()).unwrap());
+        assert!(wide.contains(&narrow.data().as_ptr()));
+    }
+    Ok(())
+}
+
+#[test]
+fn out_of_bounds() {
+    let source = LineSource::new(["foo"]);
+    assert!(matches!(
+        source.read_span(&(10, 1).into(), 0, 0),
+        Err(MietteError::OutOfBounds)
+    ));
+}
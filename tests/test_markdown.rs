@@ -0,0 +1,166 @@
+mod markdown_report_handler {
+    use miette::{Diagnostic, MarkdownReportHandler, MietteError, NamedSource, Report, SourceSpan};
+
+    use thiserror::Error;
+
+    fn fmt_report(diag: Report) -> String {
+        let mut out = String::new();
+        MarkdownReportHandler::new()
+            .render_report(&mut out, diag.as_ref())
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn single_line_highlight() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops *not* bold!")]
+        #[diagnostic(
+            code(oops::my::bad),
+            help("try doing it better next time?"),
+            url("https://example.com/oops")
+        )]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label("this bit here")]
+            highlight: SourceSpan,
+        }
+
+        let src = "source\n  text\n    here".to_string();
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src),
+            highlight: (9, 4).into(),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = r#"**error\[oops::my::bad\]**: oops \*not\* bold!
+
+`bad_file.rs:1:1`
+
+```
+1 | source
+2 |   text
+  |   ^^^^ this bit here
+3 |     here
+```
+
+> **help**: try doing it better next time?
+
+For more details, see <https://example.com/oops>
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn multiline_highlight_and_backticks() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(severity(Warning))]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label("these two lines")]
+            highlight: SourceSpan,
+        }
+
+        let src = "let s = ```;\n  text\n    here".to_string();
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src),
+            highlight: (8, 11).into(),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = r#"**warning**: oops!
+
+`bad_file.rs:1:1`
+
+````
+1 | let s = ```;
+  |         ^^^^
+2 |   text
+  | ^^^^^^ these two lines
+3 |     here
+````
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn causes_related_and_footer() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        struct MyBad {
+            #[source]
+            source: Inner,
+            #[related]
+            related: Vec<MyRelated>,
+        }
+
+        #[derive(Debug, Error)]
+        #[error("inner")]
+        struct Inner;
+
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("related")]
+        #[diagnostic(severity(Advice), help("a\nb"))]
+        struct MyRelated;
+
+        let err = MyBad {
+            source: Inner,
+            related: vec![MyRelated],
+        };
+        let mut out = String::new();
+        MarkdownReportHandler::new()
+            .with_footer("the end".into())
+            .render_report(&mut out, &err)
+            .unwrap();
+        println!("Error: {}", out);
+        let expected = r#"**error**: oops!
+
+Caused by:
+- inner
+
+**advice**: related
+
+> **help**: a
+> b
+
+the end
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn far_apart_labels_in_separate_snippets() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        struct MyBad {
+            #[source_code]
+            src: String,
+            #[label("first")]
+            first: SourceSpan,
+            #[label("second")]
+            second: SourceSpan,
+        }
+
+        let src = (1..=100)
+            .map(|i| format!("line {}\n", i))
+            .collect::<String>();
+        let offset = |line: usize| src.find(&format!("line {}\n", line)).unwrap();
+        let err = MyBad {
+            first: (offset(51), 4).into(),
+            second: (offset(91), 4).into(),
+            src,
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        assert!(out.contains("`50:1`"));
+        assert!(out.contains("`90:1`"));
+        assert_eq!(out.matches(" | line ").count(), 6);
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use miette::{Diagnostic, JSONReportHandler, LabeledSpan, MietteDiagnostic, Severity};
+
+#[test]
+fn defaults() {
+    let diag = MietteDiagnostic::new("oops!");
+    assert_eq!(diag.to_string(), "oops!");
+    assert!(diag.code().is_none());
+    assert!(diag.severity().is_none());
+    assert!(diag.help().is_none());
+    assert!(diag.url().is_none());
+    assert!(diag.labels().is_none());
+    assert!(diag.source_code().is_none());
+}
+
+#[test]
+fn setters() {
+    let diag = MietteDiagnostic::new("oops!")
+        .with_message("unknown key")
+        .with_code("config::unknown_key")
+        .with_severity(Severity::Warning)
+        .with_help("remove it")
+        .with_url("https://example.com")
+        .with_label(LabeledSpan::new_with_span(Some("here".into()), (0, 6)))
+        .with_labels(vec![LabeledSpan::new_with_span(None, (9, 5))]);
+    assert_eq!(diag.message(), "unknown key");
+    assert_eq!(diag.code().unwrap().to_string(), "config::unknown_key");
+    assert_eq!(diag.severity(), Some(Severity::Warning));
+    assert_eq!(diag.help().unwrap().to_string(), "remove it");
+    assert_eq!(diag.url().unwrap().to_string(), "https://example.com");
+    assert_eq!(
+        diag.labels().unwrap().collect::<Vec<_>>(),
+        vec![
+            LabeledSpan::new_with_span(Some("here".into()), (0, 6)),
+            LabeledSpan::new_with_span(None, (9, 5)),
+        ]
+    );
+}
+
+#[test]
+fn renders_with_source_code() {
+    let diag = MietteDiagnostic::new("unknown key")
+        .with_code("config::unknown_key")
+        .with_label(LabeledSpan::new_with_span(Some("this key".into()), (0, 6)))
+        .with_source_code("colour = \"red\"");
+    let mut out = String::new();
+    JSONReportHandler::new()
+        .render_report(&mut out, &diag)
+        .unwrap();
+    println!("Error: {}", out);
+    assert!(out.contains(r#""code": "config::unknown_key""#));
+    assert!(out.contains(r#""labels": [{"label": "this key","span": {"offset": 0,"length": 6}}]"#));
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "mmap")]
+
+use std::path::PathBuf;
+
+use miette::{MietteError, MmapSource, SourceCode};
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("miette-test-{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn read_span() -> Result<(), MietteError> {
+    let path = temp_file("mmap.txt", "foo\nbarbar\nbaz\n");
+    // SAFETY: each test writes its own file, and doesn't modify it.
+    let source = unsafe { MmapSource::open(&path)? };
+    assert_eq!(source.path(), path);
+    assert_eq!(source.as_bytes(), b"foo\nbarbar\nbaz\n");
+
+    let contents = source.read_span(&(7, 4).into(), 0, 0)?;
+    assert_eq!("bar\n", std::str::from_utf8(contents.data()).unwrap());
+    assert_eq!(1, contents.line());
+    assert_eq!(3, contents.column());
+    assert_eq!(Some(path.display().to_string().as_str()), contents.name());
+    drop(contents);
+
+    assert!(matches!(
+        source.read_span(&(30, 1).into(), 0, 0),
+        Err(MietteError::OutOfBounds)
+    ));
+    drop(source);
+    std::fs::remove_file(&path).unwrap();
+    Ok(())
+}
+
+#[test]
+fn empty_file() -> Result<(), MietteError> {
+    let path = temp_file("mmap_empty.txt", "");
+    // SAFETY: each test writes its own file, and doesn't modify it.
+    let source = unsafe { MmapSource::open(&path)? };
+    assert!(source.as_bytes().is_empty());
+    drop(source);
+    std::fs::remove_file(&path).unwrap();
+    Ok(())
+}
+
+#[test]
+fn missing_file() {
+    let path = std::env::temp_dir().join("miette-test-does-not-exist");
+    // SAFETY: there's no file to map.
+    assert!(unsafe { MmapSource::open(path) }.is_err());
+}
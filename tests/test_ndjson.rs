@@ -0,0 +1,53 @@
+mod ndjson_reporter {
+    use miette::{Diagnostic, NDJSONReporter, NamedSource, SourceSpan};
+
+    use thiserror::Error;
+
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("oops!\non two lines")]
+    #[diagnostic(code(oops::my::bad))]
+    struct MyBad {
+        #[source_code]
+        src: NamedSource,
+        #[label("this bit here")]
+        highlight: SourceSpan,
+    }
+
+    #[derive(Debug, Diagnostic, Error)]
+    #[error("careful")]
+    #[diagnostic(severity(Warning))]
+    struct MyWarning;
+
+    #[test]
+    fn one_line_per_diagnostic() -> std::io::Result<()> {
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", "source\n  text\n    here"),
+            highlight: (9, 4).into(),
+        };
+        let mut reporter = NDJSONReporter::new(Vec::new());
+        reporter.report(&err)?;
+        assert_eq!(
+            reporter.get_ref().iter().filter(|b| **b == b'\n').count(),
+            1
+        );
+        reporter.report(&MyWarning)?;
+
+        let out = String::from_utf8(reporter.into_inner()).unwrap();
+        println!("{}", out);
+        let expected = r#"{"message": "oops!\non two lines","code": "oops::my::bad","severity": "error","causes": [],"filename": "bad_file.rs","labels": [{"label": "this bit here","span": {"offset": 9,"length": 4}}],"related": []}
+{"message": "careful","severity": "warning","causes": [],"labels": [],"related": []}
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn report_all() -> std::io::Result<()> {
+        let diagnostics: Vec<&dyn Diagnostic> = vec![&MyWarning, &MyWarning, &MyWarning];
+        let mut reporter = NDJSONReporter::new(Vec::new());
+        reporter.report_all(diagnostics)?;
+        let out = String::from_utf8(reporter.into_inner()).unwrap();
+        assert_eq!(3, out.lines().count());
+        Ok(())
+    }
+}
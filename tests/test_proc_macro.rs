@@ -0,0 +1,18 @@
+#![cfg(feature = "proc-macro")]
+
+use miette::SourceSpan;
+use proc_macro2::{TokenStream, TokenTree};
+
+#[test]
+fn from_proc_macro2_span() {
+    let source = "fn main() { oops(); }";
+    let tokens: TokenStream = source.parse().unwrap();
+    let body = match tokens.into_iter().nth(3) {
+        Some(TokenTree::Group(group)) => group,
+        other => panic!("expected the function body, got {:?}", other),
+    };
+    let ident = body.stream().into_iter().next().unwrap();
+    let span = SourceSpan::from(ident.span());
+    assert_eq!(span, (12, 4).into());
+    assert_eq!(&source[std::ops::Range::from(span)], "oops");
+}
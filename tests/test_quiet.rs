@@ -0,0 +1,69 @@
+mod quiet_report_handler {
+    use miette::{Diagnostic, MietteError, NamedSource, QuietReportHandler, Report, SourceSpan};
+
+    use thiserror::Error;
+
+    fn fmt_report(diag: Report) -> String {
+        let mut out = String::new();
+        QuietReportHandler::new()
+            .render_report(&mut out, diag.as_ref())
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn single_line_highlight() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(
+            code(oops::my::bad),
+            help("try doing it better next time?"),
+            url("https://example.com")
+        )]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label("this bit here")]
+            highlight: SourceSpan,
+            #[source]
+            cause: std::io::Error,
+        }
+
+        let src = "source\n  text\n    here".to_string();
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src),
+            highlight: (9, 4).into(),
+            cause: std::io::Error::new(std::io::ErrorKind::Other, "the cause"),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        assert_eq!("error[oops::my::bad]: oops!", out);
+        Ok(())
+    }
+
+    #[test]
+    fn related_and_multiline() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops\n  on two lines")]
+        struct MyBad {
+            #[related]
+            related: Vec<MyRelated>,
+        }
+
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("related")]
+        #[diagnostic(code(oops::related), severity(Warning))]
+        struct MyRelated;
+
+        let err = MyBad {
+            related: vec![MyRelated, MyRelated],
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = "error: oops on two lines
+warning[oops::related]: related
+warning[oops::related]: related";
+        assert_eq!(expected, out);
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+mod rustc_report_handler {
+    use miette::{Diagnostic, MietteError, NamedSource, Report, RustcReportHandler, SourceSpan};
+
+    use thiserror::Error;
+
+    fn fmt_report(diag: Report) -> String {
+        let mut out = String::new();
+        RustcReportHandler::new()
+            .render_report(&mut out, diag.as_ref())
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn single_line_highlight() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("mismatched types")]
+        #[diagnostic(code(E0308), help("try doing it better next time?"))]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label("expected `i32`, found `&str`")]
+            found: SourceSpan,
+            #[label("expected due to this")]
+            expected: SourceSpan,
+        }
+
+        let src = "fn main() {\n    let x: i32 = \"a\";\n}\n".to_string();
+        let err = MyBad {
+            src: NamedSource::new("src/main.rs", src),
+            found: (29, 3).into(),
+            expected: (23, 3).into(),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = r#"error[E0308]: mismatched types
+ --> src/main.rs:2:18
+  |
+2 |     let x: i32 = "a";
+  |            --- expected due to this
+  |                  ^^^ expected `i32`, found `&str`
+  |
+  = help: try doing it better next time?
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn distant_labels_and_multiline() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(severity(Warning), url("https://example.com/oops"))]
+        struct MyBad {
+            #[source_code]
+            src: String,
+            #[label("these")]
+            first: SourceSpan,
+            #[label("here")]
+            second: SourceSpan,
+        }
+
+        let src = "one\n\ttwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n".to_string();
+        let err = MyBad {
+            src,
+            first: (5, 8).into(),
+            second: (46, 3).into(),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = r#"warning: oops!
+  --> 2:2
+   |
+ 2 |     two
+   |     ^^^
+ 3 | three
+   | ^^^^ these
+...
+10 | ten
+   | --- here
+   |
+   = note: for more information, see <https://example.com/oops>
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn causes_and_related() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        struct MyBad {
+            #[source]
+            source: Inner,
+            #[related]
+            related: Vec<MyRelated>,
+        }
+
+        #[derive(Debug, Error)]
+        #[error("inner")]
+        struct Inner;
+
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("related")]
+        #[diagnostic(severity(Advice))]
+        struct MyRelated;
+
+        let err = MyBad {
+            source: Inner,
+            related: vec![MyRelated],
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = r#"error: oops!
+  |
+  = note: caused by: inner
+
+note: related
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_utf8() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        struct MyBad {
+            #[source_code]
+            src: Vec<u8>,
+            #[label("here")]
+            highlight: SourceSpan,
+        }
+
+        let err = MyBad {
+            src: b"let x = \xff\xfe + 1;\n".to_vec(),
+            highlight: (13, 1).into(),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = r#"error: oops!
+ --> 1:14
+  |
+1 | let x = �� + 1;
+  |              ^ here
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn far_apart_labels_in_separate_snippets() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        struct MyBad {
+            #[source_code]
+            src: String,
+            #[label("first")]
+            first: SourceSpan,
+            #[label("second")]
+            second: SourceSpan,
+        }
+
+        let src = (1..=100)
+            .map(|i| format!("line {}\n", i))
+            .collect::<String>();
+        let offset = |line: usize| src.find(&format!("line {}\n", line)).unwrap();
+        let err = MyBad {
+            first: (offset(51), 4).into(),
+            second: (offset(91), 4).into(),
+            src,
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = r#"error: oops!
+  --> 51:1
+   |
+51 | line 51
+   | ^^^^ first
+...
+91 | line 91
+   | ---- second
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+mod sarif_report_handler {
+    use miette::{Diagnostic, MietteError, NamedSource, Report, SarifReportHandler, SourceSpan};
+
+    use thiserror::Error;
+
+    fn fmt_report(diag: Report) -> String {
+        let mut out = String::new();
+        SarifReportHandler::new()
+            .with_tool_name("my-linter")
+            .with_tool_version("1.0.0")
+            .render_report(&mut out, diag.as_ref())
+            .unwrap();
+        out
+    }
+
+    fn normalize(expected: &str) -> String {
+        expected
+            .lines()
+            .map(|s| s.trim_matches(|c| c == ' ' || c == '\n'))
+            .collect()
+    }
+
+    #[test]
+    fn single_line_highlight() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(code(oops::my::bad), help("try doing it better next time?"))]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label("this bit here")]
+            highlight: SourceSpan,
+        }
+
+        let src = "source\n  👼🏼text\n    here".to_string();
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src),
+            highlight: (17, 4).into(),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = normalize(
+            r#"
+        {
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "my-linter",
+                        "version": "1.0.0",
+                        "rules": [{
+                            "id": "oops::my::bad",
+                            "help": {"text": "try doing it better next time?"}
+                        }]
+                    }
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": [{
+                    "ruleId": "oops::my::bad",
+                    "level": "error",
+                    "message": {"text": "oops!"},
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": {"uri": "bad_file.rs"},
+                            "region": {
+                                "startLine": 2,
+                                "startColumn": 5,
+                                "endLine": 2,
+                                "endColumn": 9,
+                                "byteOffset": 17,
+                                "byteLength": 4
+                            }
+                        }
+                    }]
+                }]
+            }]
+        }"#,
+        );
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn multiline_and_related_locations() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(
+            code(oops::my::bad),
+            severity(Warning),
+            url("https://example.com/oops")
+        )]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label]
+            first: SourceSpan,
+            #[label("also \"here\"")]
+            second: SourceSpan,
+        }
+
+        let src = "source\n  text\n    here".to_string();
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src),
+            first: (9, 11).into(),
+            second: (0, 6).into(),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = normalize(
+            r#"
+        {
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "my-linter",
+                        "version": "1.0.0",
+                        "rules": [{
+                            "id": "oops::my::bad",
+                            "helpUri": "https://example.com/oops"
+                        }]
+                    }
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": [{
+                    "ruleId": "oops::my::bad",
+                    "level": "warning",
+                    "message": {"text": "oops!"},
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": {"uri": "bad_file.rs"},
+                            "region": {
+                                "startLine": 2,
+                                "startColumn": 3,
+                                "endLine": 3,
+                                "endColumn": 7,
+                                "byteOffset": 9,
+                                "byteLength": 11
+                            }
+                        }
+                    }],
+                    "relatedLocations": [{
+                        "id": 0,
+                        "physicalLocation": {
+                            "artifactLocation": {"uri": "bad_file.rs"},
+                            "region": {
+                                "startLine": 1,
+                                "startColumn": 1,
+                                "endLine": 1,
+                                "endColumn": 7,
+                                "byteOffset": 0,
+                                "byteLength": 6
+                            }
+                        },
+                        "message": {"text": "also \"here\""}
+                    }]
+                }]
+            }]
+        }"#,
+        );
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn related_diagnostics_become_results() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(code(oops::my::bad))]
+        struct MyBad {
+            #[related]
+            related: Vec<MyRelated>,
+        }
+
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("related")]
+        #[diagnostic(code(oops::my::related), severity(Advice))]
+        struct MyRelated;
+
+        let err = MyBad {
+            related: vec![MyRelated],
+        };
+        let mut out = String::new();
+        SarifReportHandler::new()
+            .render_report(&mut out, &err)
+            .unwrap();
+        println!("Error: {}", out);
+        let expected = normalize(
+            r#"
+        {
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "miette",
+                        "rules": [{
+                            "id": "oops::my::bad"
+                        },{
+                            "id": "oops::my::related"
+                        }]
+                    }
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": [{
+                    "ruleId": "oops::my::bad",
+                    "level": "error",
+                    "message": {"text": "oops!"}
+                },{
+                    "ruleId": "oops::my::related",
+                    "level": "note",
+                    "message": {"text": "related"}
+                }]
+            }]
+        }"#,
+        );
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn multiple_reports() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        struct MyBad;
+
+        let mut out = String::new();
+        SarifReportHandler::new()
+            .render_reports(&mut out, vec![&MyBad as &dyn Diagnostic, &MyBad])
+            .unwrap();
+        let expected = normalize(
+            r#"
+        {
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "miette",
+                        "rules": []
+                    }
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": [{
+                    "level": "error",
+                    "message": {"text": "oops!"}
+                },{
+                    "level": "error",
+                    "message": {"text": "oops!"}
+                }]
+            }]
+        }"#,
+        );
+        assert_eq!(expected, out);
+        Ok(())
+    }
+}
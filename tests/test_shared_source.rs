@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use miette::{
+    Diagnostic, JSONReportHandler, LabeledSpan, MietteDiagnostic, NamedSource, SourceCode,
+    SourceSpan,
+};
+use thiserror::Error;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("oops!")]
+struct MyBad {
+    #[source_code]
+    src: Arc<dyn SourceCode>,
+    #[label("here")]
+    span: SourceSpan,
+}
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("oops!")]
+struct MyNamedBad {
+    #[source_code]
+    src: NamedSource,
+    #[label("here")]
+    span: SourceSpan,
+}
+
+fn render(diagnostic: &dyn Diagnostic) -> String {
+    let mut out = String::new();
+    JSONReportHandler::new()
+        .render_report(&mut out, diagnostic)
+        .unwrap();
+    out
+}
+
+#[test]
+fn shared_arc() {
+    let src: Arc<dyn SourceCode> = Arc::new(String::from("foo\nbar\n"));
+    let errs = [
+        MyBad {
+            src: src.clone(),
+            span: (0, 3).into(),
+        },
+        MyBad {
+            src: src.clone(),
+            span: (4, 3).into(),
+        },
+    ];
+    assert_eq!(Arc::strong_count(&src), 3);
+    for (line, err) in errs.iter().enumerate() {
+        let contents = err
+            .source_code()
+            .unwrap()
+            .read_span(&err.span, 0, 0)
+            .unwrap();
+        assert_eq!(contents.line(), line);
+    }
+    assert_eq!(format!("{:?}", errs[0].src), "SourceCode { .. }");
+}
+
+#[test]
+fn shared_named_source() {
+    let src = NamedSource::new("bad_file.rs", String::from("foo\nbar\n"));
+    let errs = [
+        MyNamedBad {
+            src: src.clone(),
+            span: (0, 3).into(),
+        },
+        MyNamedBad {
+            src,
+            span: (4, 3).into(),
+        },
+    ];
+    for err in &errs {
+        assert!(render(err).contains(r#""filename": "bad_file.rs""#));
+    }
+}
+
+#[test]
+fn shared_miette_diagnostic() {
+    let src: Arc<dyn SourceCode> = Arc::new(String::from("foo\nbar\n"));
+    let diag = MietteDiagnostic::new("oops!")
+        .with_shared_source_code(src.clone())
+        .with_label(LabeledSpan::new_with_span(None, (0, 3)));
+    let other = diag
+        .clone()
+        .with_label(LabeledSpan::new_with_span(None, (4, 3)));
+    assert_eq!(Arc::strong_count(&src), 3);
+    assert!(diag.source_code().is_some());
+    assert_eq!(other.labels().unwrap().count(), 2);
+}
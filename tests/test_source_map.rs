@@ -0,0 +1,73 @@
+use miette::{Diagnostic, Fix, JSONReportHandler, LabeledSpan, Report, SourceMap};
+use thiserror::Error;
+
+// `let y = twice!(x) + z;` after `twice!(x)` is expanded into `x + x`.
+const ORIGINAL: &str = "let y = twice!(x) + z;";
+const GENERATED: &str = "let y = (x + x) + z;";
+
+fn source_map() -> SourceMap {
+    SourceMap::new(ORIGINAL)
+        .with_mapping((0, 8), (0, 8))
+        .with_mapping((8, 7), (8, 9))
+        .with_mapping((15, 5), (17, 5))
+}
+
+#[test]
+fn map_span() {
+    let map = source_map();
+    assert_eq!(&GENERATED[4..5], "y");
+    assert_eq!(map.map_span(&(4, 1).into()), Some((4, 1).into()));
+    // Anything in the expansion points at the whole macro call.
+    assert_eq!(map.map_span(&(12, 1).into()), Some((8, 9).into()));
+    assert_eq!(map.map_span(&(18, 1).into()), Some((20, 1).into()));
+    // Spans across mappings stretch across what they map to.
+    assert_eq!(map.map_span(&(4, 8).into()), Some((4, 13).into()));
+    assert_eq!(map.map_span(&(8, 0).into()), Some((8, 0).into()));
+    assert_eq!(map.map_span(&(30, 1).into()), None);
+}
+
+#[derive(Debug, Error)]
+#[error("cannot find value `z` in this scope")]
+struct NotFound;
+
+impl Diagnostic for NotFound {
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        Some(Box::new(
+            vec![
+                LabeledSpan::new_with_span(Some("not found".into()), (18, 1)),
+                // Generated code that isn't mapped to anything.
+                LabeledSpan::new_with_span(Some("elsewhere".into()), (40, 2)),
+            ]
+            .into_iter(),
+        ))
+    }
+
+    fn fixes(&self) -> Option<Box<dyn Iterator<Item = Fix> + '_>> {
+        Some(Box::new(std::iter::once(Fix::new((18, 1), "y"))))
+    }
+}
+
+#[test]
+fn report() {
+    let report = Report::new(NotFound).with_source_map(source_map());
+    let labels = report.labels().unwrap().collect::<Vec<_>>();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].label(), Some("not found"));
+    assert_eq!(labels[0].inner(), &(20, 1).into());
+    let fixes = report.fixes().unwrap().collect::<Vec<_>>();
+    assert_eq!(fixes[0].span(), &(20, 1).into());
+    assert_eq!(fixes[0].replacement(), "y");
+
+    let contents = report
+        .source_code()
+        .unwrap()
+        .read_span(labels[0].inner(), 0, 0)
+        .unwrap();
+    assert_eq!(contents.data(), b"z");
+
+    let mut out = String::new();
+    JSONReportHandler::new()
+        .render_report(&mut out, report.as_ref())
+        .unwrap();
+    assert!(out.contains(r#""offset": 20"#));
+}
@@ -0,0 +1,101 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use miette::{
+    Diagnostic, JSONReportHandler, MietteError, ResolvedSource, SourceCode, SourceResolver,
+    SourceSpan,
+};
+use thiserror::Error;
+
+/// An in-memory overlay of files, the way a language server would have one.
+#[derive(Default)]
+struct Overlay {
+    files: Mutex<HashMap<String, Arc<dyn SourceCode>>>,
+    lookups: AtomicUsize,
+}
+
+impl SourceResolver for Overlay {
+    fn resolve(&self, name: &str) -> Result<Arc<dyn SourceCode>, MietteError> {
+        self.lookups.fetch_add(1, Ordering::SeqCst);
+        self.files
+            .lock()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, name).into())
+    }
+}
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("oops!")]
+struct MyBad {
+    #[source_code]
+    src: ResolvedSource,
+    #[label("here")]
+    span: SourceSpan,
+}
+
+#[test]
+fn resolves_lazily() -> Result<(), MietteError> {
+    let overlay = Arc::new(Overlay::default());
+    overlay
+        .files
+        .lock()
+        .unwrap()
+        .insert("src/main.rs".into(), Arc::new("fn main() {}\n".to_string()));
+    let source = ResolvedSource::new(overlay.clone(), "src/main.rs");
+    assert_eq!(source.name(), "src/main.rs");
+    assert!(!source.is_resolved());
+    assert_eq!(overlay.lookups.load(Ordering::SeqCst), 0);
+
+    let contents = source.read_span(&(3, 4).into(), 0, 0)?;
+    assert_eq!(contents.data(), b"main");
+    assert_eq!(contents.name(), Some("src/main.rs"));
+    assert!(source.is_resolved());
+
+    source.read_span(&(0, 2).into(), 0, 0)?;
+    assert_eq!(overlay.lookups.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+fn unresolved() {
+    let overlay = Arc::new(Overlay::default());
+    let source = ResolvedSource::new(overlay.clone(), "missing.rs");
+    assert!(matches!(
+        source.read_span(&(0, 1).into(), 0, 0),
+        Err(MietteError::IoError(_))
+    ));
+    assert!(!source.is_resolved());
+
+    // The next read tries again.
+    overlay
+        .files
+        .lock()
+        .unwrap()
+        .insert("missing.rs".into(), Arc::new("found".to_string()));
+    assert!(source.read_span(&(0, 1).into(), 0, 0).is_ok());
+    assert_eq!(overlay.lookups.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn closure_resolver() {
+    let resolver: Arc<dyn SourceResolver> =
+        Arc::new(|name: &str| -> Result<Arc<dyn SourceCode>, MietteError> {
+            Ok(Arc::new(format!("contents of {}", name)))
+        });
+    let err = MyBad {
+        src: ResolvedSource::new(resolver, "lib.rs"),
+        span: (12, 6).into(),
+    };
+    let mut out = String::new();
+    JSONReportHandler::new()
+        .render_report(&mut out, &err)
+        .unwrap();
+    assert!(out.contains(r#""filename": "lib.rs""#));
+}
@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use miette::{MietteError, SourceCode, StreamingSource};
+
+fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("miette-test-{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn read_span() -> Result<(), MietteError> {
+    let path = temp_file("streaming.txt", b"foo\nbarbar\nbaz\n");
+    let source = StreamingSource::open(&path)?;
+    assert_eq!(source.path(), path);
+
+    let contents = source.read_span(&(7, 4).into(), 0, 0)?;
+    assert_eq!("bar\n", std::str::from_utf8(contents.data()).unwrap());
+    assert_eq!(1, contents.line());
+    assert_eq!(3, contents.column());
+    assert_eq!(Some(path.display().to_string().as_str()), contents.name());
+    std::fs::remove_file(&path).unwrap();
+    Ok(())
+}
+
+fn assert_matches_in_memory(name: &str, text: &str) -> Result<(), MietteError> {
+    let path = temp_file(name, text.as_bytes());
+    let source = StreamingSource::open(&path)?;
+    let offsets = [0, 5, 14, 70_000, 131_072, text.len() - 3];
+    // Read them in order and then backwards, so that later reads start from
+    // the line starts earlier ones remembered.
+    for offset in offsets.iter().chain(offsets.iter().rev()) {
+        for len in [0, 1, 40] {
+            for (before, after) in [(0, 0), (1, 1), (0, 2), (2, 3), (1500, 1)] {
+                let span = (*offset, len).into();
+                let (expected, actual) = match (
+                    text.read_span(&span, before, after),
+                    source.read_span(&span, before, after),
+                ) {
+                    (Ok(expected), Ok(actual)) => (expected, actual),
+                    (Err(_), Err(_)) => continue,
+                    _ => panic!("only one read of {:?} failed", span),
+                };
+                assert_eq!(expected.data(), actual.data());
+                assert_eq!(expected.span(), actual.span());
+                assert_eq!(expected.line(), actual.line());
+                assert_eq!(expected.column(), actual.column());
+                assert_eq!(expected.line_count(), actual.line_count());
+            }
+        }
+    }
+    std::fs::remove_file(&path).unwrap();
+    Ok(())
+}
+
+#[test]
+fn matches_in_memory() -> Result<(), MietteError> {
+    // Long enough to span several chunks, with every kind of line ending.
+    let mut text = String::new();
+    for line in 0..20_000 {
+        let ending = ["\n", "\r\n", "\r"][line % 3];
+        text.push_str(&format!("line number {}{}", line, ending));
+    }
+    assert_matches_in_memory("streaming-large.txt", &text)
+}
+
+#[test]
+fn carriage_returns_only() -> Result<(), MietteError> {
+    let mut text = String::new();
+    for line in 0..20_000 {
+        text.push_str(&format!("line number {}\r", line));
+    }
+    assert_matches_in_memory("streaming-cr.txt", &text)
+}
+
+#[test]
+fn reuses_snippets() -> Result<(), MietteError> {
+    let path = temp_file("streaming-reuse.txt", b"foo\nbarbar\nbaz\nqux\n");
+    let source = StreamingSource::open(&path)?;
+    let wide = source
+        .read_span(&(7, 4).into(), 1, 1)?
+        .data()
+        .as_ptr_range();
+    for _ in 0..3 {
+        let narrow = source.read_span(&(7, 4).into(), 0, 0)?;
+        assert_eq!("bar\n", std::str::from_utf8(narrow.data()).unwrap());
+        assert!(wide.contains(&narrow.data().as_ptr()));
+    }
+    std::fs::remove_file(&path).unwrap();
+    Ok(())
+}
+
+#[test]
+fn out_of_bounds() -> Result<(), MietteError> {
+    let path = temp_file("streaming-short.txt", b"foo\n");
+    let source = StreamingSource::open(&path)?;
+    assert!(matches!(
+        source.read_span(&(10, 1).into(), 0, 0),
+        Err(MietteError::OutOfBounds)
+    ));
+    std::fs::remove_file(&path).unwrap();
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use miette::{Diagnostic, DiagnosticSummary};
+use thiserror::Error;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("oops!")]
+struct MyBad {
+    #[related]
+    related: Vec<MyWarning>,
+}
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("careful")]
+#[diagnostic(severity(Warning))]
+struct MyWarning;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("consider this")]
+#[diagnostic(severity(Advice))]
+struct MyAdvice;
+
+#[test]
+fn empty() {
+    let summary = DiagnosticSummary::new();
+    assert!(summary.is_empty());
+    assert_eq!("", summary.to_string());
+}
+
+#[test]
+fn errors_and_warnings() {
+    let err = MyBad {
+        related: vec![MyWarning, MyWarning],
+    };
+    let other = MyBad { related: vec![] };
+    let diagnostics: Vec<&dyn Diagnostic> = vec![&err, &MyWarning, &other];
+    let summary = diagnostics.into_iter().collect::<DiagnosticSummary>();
+    assert_eq!(2, summary.errors());
+    assert_eq!(3, summary.warnings());
+    assert_eq!(0, summary.advice());
+    assert_eq!(
+        "error: aborting due to 2 previous errors; 3 warnings emitted",
+        summary.to_string()
+    );
+}
+
+#[test]
+fn single_error() {
+    let mut summary = DiagnosticSummary::new();
+    summary.add(&MyBad { related: vec![] });
+    assert_eq!(
+        "error: aborting due to 1 previous error",
+        summary.to_string()
+    );
+}
+
+#[test]
+fn warnings_and_advice() {
+    let mut summary = DiagnosticSummary::new();
+    summary.add(&MyWarning);
+    summary.add(&MyAdvice);
+    summary.add(&MyAdvice);
+    assert_eq!("warning: 1 warning, 2 advice emitted", summary.to_string());
+
+    let mut summary = DiagnosticSummary::new();
+    summary.add(&MyAdvice);
+    assert_eq!("advice: 1 advice emitted", summary.to_string());
+}
@@ -0,0 +1,111 @@
+mod tap_report_handler {
+    use miette::{Diagnostic, MietteError, NamedSource, Report, SourceSpan, TapReportHandler};
+
+    use thiserror::Error;
+
+    fn fmt_report(diag: Report) -> String {
+        let mut out = String::new();
+        TapReportHandler::new()
+            .render_report(&mut out, diag.as_ref())
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn single_line_highlight() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(code(oops::my::bad), help("try doing it \"better\" next time?"))]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label("this bit here")]
+            highlight: SourceSpan,
+        }
+
+        let src = "source\n  👼🏼text\n    here".to_string();
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src),
+            highlight: (17, 4).into(),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = r#"TAP version 13
+not ok 1 - oops!
+  ---
+  message: "oops!"
+  severity: error
+  code: "oops::my::bad"
+  file: "bad_file.rs"
+  line: 2
+  column: 5
+  help: "try doing it \"better\" next time?"
+  ...
+1..1
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn related_and_multiple() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops #1\n  on two lines")]
+        struct MyBad {
+            #[source_code]
+            src: String,
+            #[label]
+            highlight: SourceSpan,
+            #[related]
+            related: Vec<MyRelated>,
+        }
+
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("meh")]
+        #[diagnostic(severity(Warning))]
+        struct MyRelated {
+            #[label]
+            highlight: SourceSpan,
+        }
+
+        let err = MyBad {
+            src: "source\n  text".into(),
+            highlight: (9, 4).into(),
+            related: vec![MyRelated {
+                highlight: (0, 6).into(),
+            }],
+        };
+        let other = MyRelated {
+            highlight: (0, 1).into(),
+        };
+        let mut out = String::new();
+        TapReportHandler::new()
+            .render_reports(&mut out, vec![&err as &dyn Diagnostic, &other])
+            .unwrap();
+        println!("Error: {}", out);
+        let expected = r#"TAP version 13
+not ok 1 - oops \#1 on two lines
+  ---
+  message: "oops #1\n  on two lines"
+  severity: error
+  line: 2
+  column: 3
+  ...
+ok 2 - meh
+  ---
+  message: "meh"
+  severity: warning
+  line: 1
+  column: 1
+  ...
+ok 3 - meh
+  ---
+  message: "meh"
+  severity: warning
+  ...
+1..3
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+}
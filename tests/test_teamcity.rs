@@ -0,0 +1,104 @@
+mod teamcity_report_handler {
+    use miette::{Diagnostic, MietteError, NamedSource, Report, SourceSpan, TeamCityReportHandler};
+
+    use thiserror::Error;
+
+    fn fmt_report(diag: Report) -> String {
+        let mut out = String::new();
+        TeamCityReportHandler::new()
+            .with_category("my-linter")
+            .render_report(&mut out, diag.as_ref())
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn single_line_highlight() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(code(oops::my::bad), help("try doing it better next time?"))]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label("this bit here")]
+            highlight: SourceSpan,
+        }
+
+        let src = "source\n  text\n    here".to_string();
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src),
+            highlight: (9, 4).into(),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = "\
+##teamcity[inspectionType id='oops::my::bad' name='oops::my::bad' category='my-linter' description='try doing it better next time?']
+##teamcity[inspection typeId='oops::my::bad' message='oops!' file='bad_file.rs' line='2' SEVERITY='ERROR']
+##teamcity[buildProblem description='oops::my::bad: oops!']
+";
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn warning_without_code_or_source() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("it's [not] great\nbut | fine")]
+        #[diagnostic(severity(Warning))]
+        struct MyBad;
+
+        let out = fmt_report(MyBad.into());
+        println!("Error: {}", out);
+        let expected = "\
+##teamcity[inspectionType id='my-linter' name='my-linter' category='my-linter' description='my-linter']
+##teamcity[inspection typeId='my-linter' message='it|'s |[not|] great|nbut || fine' file='' SEVERITY='WARNING']
+";
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn related_and_no_build_problems() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(code(oops::my::bad))]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label]
+            highlight: SourceSpan,
+            #[related]
+            related: Vec<MyRelated>,
+        }
+
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("also this")]
+        #[diagnostic(code(oops::my::bad), severity(Advice))]
+        struct MyRelated {
+            #[label]
+            highlight: SourceSpan,
+        }
+
+        let src = "source\n  text\n    here".to_string();
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src),
+            highlight: (0, 6).into(),
+            related: vec![MyRelated {
+                highlight: (18, 4).into(),
+            }],
+        };
+        let mut out = String::new();
+        TeamCityReportHandler::new()
+            .with_build_problems(false)
+            .render_report(&mut out, &err)
+            .unwrap();
+        println!("Error: {}", out);
+        let expected = "\
+##teamcity[inspectionType id='oops::my::bad' name='oops::my::bad' category='miette' description='oops::my::bad']
+##teamcity[inspection typeId='oops::my::bad' message='oops!' file='bad_file.rs' line='1' SEVERITY='ERROR']
+##teamcity[inspection typeId='oops::my::bad' message='also this' file='bad_file.rs' line='3' SEVERITY='WEAK WARNING']
+";
+        assert_eq!(expected, out);
+        Ok(())
+    }
+}
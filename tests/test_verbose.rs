@@ -0,0 +1,114 @@
+mod verbose_report_handler {
+    use miette::{Diagnostic, MietteError, NamedSource, Report, SourceSpan, VerboseReportHandler};
+
+    use thiserror::Error;
+
+    fn fmt_report(diag: Report) -> String {
+        let mut out = String::new();
+        VerboseReportHandler::new()
+            .render_report(&mut out, diag.as_ref())
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn everything() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops!")]
+        #[diagnostic(
+            code(oops::my::bad),
+            url("https://example.com"),
+            note("text is reserved"),
+            help("try doing it better next time?")
+        )]
+        struct MyBad {
+            #[source_code]
+            src: NamedSource,
+            #[label("this bit here")]
+            highlight: SourceSpan,
+            #[label]
+            multiline: SourceSpan,
+            #[diagnostic_source]
+            cause: MyCause,
+            #[related]
+            related: Vec<MyRelated>,
+        }
+
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("the cause")]
+        #[diagnostic(code(oops::cause))]
+        struct MyCause {
+            #[source]
+            io: std::io::Error,
+        }
+
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("related")]
+        #[diagnostic(severity(Warning))]
+        struct MyRelated {
+            #[label("here")]
+            highlight: SourceSpan,
+        }
+
+        let src = "source\n  text\n    here".to_string();
+        let err = MyBad {
+            src: NamedSource::new("bad_file.rs", src),
+            highlight: (9, 4).into(),
+            multiline: (9, 10).into(),
+            cause: MyCause {
+                io: std::io::Error::new(std::io::ErrorKind::Other, "the io error"),
+            },
+            related: vec![MyRelated {
+                highlight: (0, 6).into(),
+            }],
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = r#"error[oops::my::bad]: oops!
+  severity: Error (default)
+  code: oops::my::bad
+  url: https://example.com
+  label "this bit here" at bad_file.rs:2:3 (bytes 9..13)
+    2 |   text
+  label at bad_file.rs:2:3 (bytes 9..19)
+    2 |   text
+    3 |     here
+  note: text is reserved
+  help: try doing it better next time?
+  caused by:
+    0: [oops::cause] the cause
+    1: the io error
+  related:
+    warning: related
+      severity: Warning
+      label "here" at bad_file.rs:1:1 (bytes 0..6)
+        1 | source
+"#;
+        assert_eq!(expected, out);
+        Ok(())
+    }
+
+    #[test]
+    fn no_source() -> Result<(), MietteError> {
+        #[derive(Debug, Diagnostic, Error)]
+        #[error("oops\non two lines")]
+        #[diagnostic(severity(Advice))]
+        struct MyBad {
+            #[label]
+            highlight: SourceSpan,
+        }
+
+        let err = MyBad {
+            highlight: (9, 4).into(),
+        };
+        let out = fmt_report(err.into());
+        println!("Error: {}", out);
+        let expected = "advice: oops
+  on two lines
+  severity: Advice
+  label (bytes 9..13)
+";
+        assert_eq!(expected, out);
+        Ok(())
+    }
+}
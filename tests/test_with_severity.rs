@@ -0,0 +1,58 @@
+use miette::{Diagnostic, Report, Severity, SourceSpan};
+use std::io;
+use thiserror::Error;
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("oops!")]
+#[diagnostic(code(oops::my::bad), help("try again"))]
+struct MyBad {
+    #[source_code]
+    src: String,
+    #[label("here")]
+    span: SourceSpan,
+    #[source]
+    cause: io::Error,
+}
+
+fn my_bad() -> MyBad {
+    MyBad {
+        src: "source code".into(),
+        span: (0, 6).into(),
+        cause: io::Error::new(io::ErrorKind::Other, "inner"),
+    }
+}
+
+#[derive(Debug, Diagnostic, Error)]
+#[error("careful")]
+#[diagnostic(severity(Warning))]
+struct MyWarning;
+
+#[test]
+fn demote() {
+    let report = Report::new(my_bad()).with_severity(Severity::Warning);
+    assert_eq!(report.severity(), Some(Severity::Warning));
+    assert_eq!(report.to_string(), "oops!");
+    assert_eq!(report.code().unwrap().to_string(), "oops::my::bad");
+    assert_eq!(report.help().unwrap().to_string(), "try again");
+    assert_eq!(report.labels().unwrap().count(), 1);
+    assert!(report.source_code().is_some());
+    assert_eq!(report.source().unwrap().to_string(), "inner");
+}
+
+#[test]
+fn promote() {
+    let report = Report::new(MyWarning);
+    assert_eq!(report.severity(), Some(Severity::Warning));
+    let report = report.with_severity(Severity::Error);
+    assert_eq!(report.severity(), Some(Severity::Error));
+    assert_eq!(report.to_string(), "careful");
+}
+
+#[test]
+fn with_source_code() {
+    let report = Report::new(MyWarning)
+        .with_severity(Severity::Advice)
+        .with_source_code("source code");
+    assert_eq!(report.severity(), Some(Severity::Advice));
+    assert!(report.source_code().is_some());
+}